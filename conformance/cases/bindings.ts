@@ -12,8 +12,11 @@ import * as r from 'rkyv-js';
 import { SipKeyedMap } from './sip-map.ts';
 import { btreeMap, btreeSet } from 'rkyv-js/lib/btreemap';
 import { bytes } from 'rkyv-js/lib/bytes';
+import { duration, unixTime } from 'rkyv-js/lib/duration';
+import { cString } from 'rkyv-js/lib/ffi';
 import { hashMap, hashSet } from 'rkyv-js/lib/hashmap';
 import { indexMap, indexSet } from 'rkyv-js/lib/indexmap';
+import { ipAddr, ipv4Addr, ipv6Addr, socketAddr, socketAddrV4, socketAddrV6 } from 'rkyv-js/lib/net';
 import { uuid } from 'rkyv-js/lib/uuid';
 
 export const ArchivedArraysTuples = r.struct({
@@ -50,6 +53,50 @@ export const ArchivedCompositeKey = r.struct({
 
 export type CompositeKey = r.Infer<typeof ArchivedCompositeKey>;
 
+/**
+ * Explicit discriminants: rkyv copies them onto its `repr(u8)` archived
+ * tag whatever the source repr, counting up implicitly in between.
+ */
+export const ArchivedDiscriminated = r.taggedEnum({
+  Pending: null,
+  Active: null,
+  Banned: { until: r.u64 },
+  Deleted: null,
+}, { discriminants: { Pending: 1, Active: 2, Banned: 16, Deleted: 17 } });
+
+/**
+ * Explicit discriminants: rkyv copies them onto its `repr(u8)` archived
+ * tag whatever the source repr, counting up implicitly in between.
+ */
+export type Discriminated = r.Infer<typeof ArchivedDiscriminated>;
+
+export const ArchivedDiscriminantCases = r.struct({
+  pending: ArchivedDiscriminated,
+  active: ArchivedDiscriminated,
+  banned: ArchivedDiscriminated,
+  deleted: ArchivedDiscriminated,
+});
+
+export type DiscriminantCases = r.Infer<typeof ArchivedDiscriminantCases>;
+
+/** Mutually recursive with [`Stmt`] through `Block`. */
+export const ArchivedExpr = r.taggedEnum({
+  Lit: r.i32,
+  Neg: r.box(r.lazy((): r.AnyCodec => ArchivedExpr)),
+  Add: [r.box(r.lazy((): r.AnyCodec => ArchivedExpr)), r.box(r.lazy((): r.AnyCodec => ArchivedExpr))],
+  Block: r.vec(r.lazy((): r.AnyCodec => ArchivedStmt)),
+});
+
+/** Mutually recursive with [`Stmt`] through `Block`. */
+export type Expr = r.Infer<typeof ArchivedExpr>;
+
+export const ArchivedStmt = r.taggedEnum({
+  Let: { name: r.string, value: ArchivedExpr },
+  Eval: ArchivedExpr,
+});
+
+export type Stmt = r.Infer<typeof ArchivedStmt>;
+
 export const ArchivedExternalTypes = r.struct({
   id: uuid,
   payload: bytes,
@@ -151,6 +198,10 @@ export const ArchivedKitchenSinkRef = r.struct({
 
 export type KitchenSinkRef = r.Infer<typeof ArchivedKitchenSinkRef>;
 
+/**
+ * The audit's smoking gun: variants whose first field has smaller alignment
+ * than the widest field. repr(u8) flattens fields directly after the tag.
+ */
 export const ArchivedMixedAlign = r.taggedEnum({
   V: { a: r.u8, b: r.u32 },
   W: { a: r.u32, b: r.u64 },
@@ -158,8 +209,68 @@ export const ArchivedMixedAlign = r.taggedEnum({
   Y: null,
 });
 
+/**
+ * The audit's smoking gun: variants whose first field has smaller alignment
+ * than the widest field. repr(u8) flattens fields directly after the tag.
+ */
 export type MixedAlign = r.Infer<typeof ArchivedMixedAlign>;
 
+/**
+ * `std::net` addresses archive as their octets (`IpAddr` and `SocketAddr`
+ * as `repr(u8)` enums over them), decoded as `Display` text; `CString`
+ * archives like a byte slice, trailing nul included.
+ */
+export const ArchivedNetTypes = r.struct({
+  v4: ipv4Addr,
+  v6: ipv6Addr,
+  ips: r.vec(ipAddr),
+  listen: socketAddrV4,
+  local: socketAddrV6,
+  peer_v4: socketAddr,
+  peer_v6: socketAddr,
+  hits: hashMap(ipAddr, r.u32),
+  name: cString,
+  empty_name: cString,
+});
+
+/**
+ * `std::net` addresses archive as their octets (`IpAddr` and `SocketAddr`
+ * as `repr(u8)` enums over them), decoded as `Display` text; `CString`
+ * archives like a byte slice, trailing nul included.
+ */
+export type NetTypes = r.Infer<typeof ArchivedNetTypes>;
+
+export const ArchivedNicheBool = r.struct({
+  some: r.niched(r.bool, r.niching.bool),
+  none: r.niched(r.bool, r.niching.bool),
+});
+
+export type NicheBool = r.Infer<typeof ArchivedNicheBool>;
+
+export const ArchivedNicheNaN = r.struct({
+  some: r.niched(r.f64, r.niching.nan),
+  none: r.niched(r.f32, r.niching.nan),
+});
+
+export type NicheNaN = r.Infer<typeof ArchivedNicheNaN>;
+
+export const ArchivedNicheNull = r.struct({
+  some: r.niched(r.box(r.string), r.niching.null),
+  none: r.niched(r.box(r.u32), r.niching.null),
+  mapped: r.niched(r.box(r.u64), r.niching.null),
+});
+
+export type NicheNull = r.Infer<typeof ArchivedNicheNull>;
+
+export const ArchivedNicheZero = r.struct({
+  some: r.niched(r.u32, r.niching.zero),
+  none: r.niched(r.u32, r.niching.zero),
+  wide: r.niched(r.i64, r.niching.zero),
+  size: r.niched(r.usize, r.niching.zero),
+});
+
+export type NicheZero = r.Infer<typeof ArchivedNicheZero>;
+
 export const ArchivedOptions = r.struct({
   none_int: r.option(r.u32),
   some_int: r.option(r.u32),
@@ -204,11 +315,19 @@ export const ArchivedPrimitives = r.struct({
 
 export type Primitives = r.Infer<typeof ArchivedPrimitives>;
 
+/**
+ * Two Rcs to the SAME allocation: rkyv dedups these on the Rust side;
+ * rkyv-js writes one copy per occurrence. Semantic-class only.
+ */
 export const ArchivedSharedRc = r.struct({
   a: r.rc(r.string),
   b: r.rc(r.string),
 });
 
+/**
+ * Two Rcs to the SAME allocation: rkyv dedups these on the Rust side;
+ * rkyv-js writes one copy per occurrence. Semantic-class only.
+ */
 export type SharedRc = r.Infer<typeof ArchivedSharedRc>;
 
 export const ArchivedSipHashedMap = r.struct({
@@ -217,6 +336,29 @@ export const ArchivedSipHashedMap = r.struct({
 
 export type SipHashedMap = r.Infer<typeof ArchivedSipHashedMap>;
 
+/**
+ * `Result` and `Bound` archive as `repr(u8)` enums in variant order, the
+ * ranges as `repr(C)` structs of their bounds.
+ */
+export const ArchivedStdEnums = r.struct({
+  ok: r.taggedEnum({ Ok: r.u32, Err: r.string }),
+  err: r.taggedEnum({ Ok: r.u32, Err: r.string }),
+  results: r.vec(r.taggedEnum({ Ok: r.unit, Err: r.u8 })),
+  span: r.struct({ start: r.u64, end: r.u64 }),
+  lines: r.struct({ start: r.u16, end: r.u16 }),
+  from: r.struct({ start: r.i32 }),
+  to: r.struct({ end: r.char }),
+  included: r.taggedEnum({ Included: r.u32, Excluded: r.u32, Unbounded: null }),
+  excluded: r.taggedEnum({ Included: r.u32, Excluded: r.u32, Unbounded: null }),
+  unbounded: r.taggedEnum({ Included: r.string, Excluded: r.string, Unbounded: null }),
+});
+
+/**
+ * `Result` and `Bound` archive as `repr(u8)` enums in variant order, the
+ * ranges as `repr(C)` structs of their bounds.
+ */
+export type StdEnums = r.Infer<typeof ArchivedStdEnums>;
+
 export const ArchivedStrings = r.struct({
   empty: r.string,
   one: r.string,
@@ -245,6 +387,29 @@ export const ArchivedHashMapStructKey = r.struct({
 
 export type HashMapStructKey = r.Infer<typeof ArchivedHashMapStructKey>;
 
+export const ArchivedTimeTypes = r.struct({
+  took: duration,
+  timeout: r.option(duration),
+  at: unixTime,
+});
+
+export type TimeTypes = r.Infer<typeof ArchivedTimeTypes>;
+
+export const ArchivedTreeNode = r.struct({
+  label: r.string,
+  weight: r.u32,
+  children: r.vec(r.lazy((): r.AnyCodec => ArchivedTreeNode)),
+});
+
+export type TreeNode = r.Infer<typeof ArchivedTreeNode>;
+
+export const ArchivedRecursive = r.struct({
+  tree: ArchivedTreeNode,
+  program: r.vec(ArchivedStmt),
+});
+
+export type Recursive = r.Infer<typeof ArchivedRecursive>;
+
 export const ArchivedTupleVariants = r.taggedEnum({
   Color: [r.u8, r.u8, r.u8],
   Wrap: r.string,
@@ -289,6 +454,26 @@ export const ArchivedEnumCases = r.struct({
 
 export type EnumCases = r.Infer<typeof ArchivedEnumCases>;
 
+/**
+ * Pointers to `str` and slices: a relative pointer plus a length. Unlike
+ * `String`, short text is never stored inline.
+ */
+export const ArchivedUnsizedPointers = r.struct({
+  name: r.boxedStr,
+  empty: r.boxedStr,
+  ids: r.vec(r.u32),
+  shared_name: r.boxedStr,
+  shared_points: r.vec(ArchivedPoint),
+  note: r.niched(r.boxedStr, r.niching.null),
+  missing: r.niched(r.vec(r.u8), r.niching.null),
+});
+
+/**
+ * Pointers to `str` and slices: a relative pointer plus a length. Unlike
+ * `String`, short text is never stored inline.
+ */
+export type UnsizedPointers = r.Infer<typeof ArchivedUnsizedPointers>;
+
 export const ArchivedVecs = r.struct({
   empty: r.vec(r.u32),
   one: r.vec(r.u32),
@@ -299,3 +484,47 @@ export const ArchivedVecs = r.struct({
 });
 
 export type Vecs = r.Infer<typeof ArchivedVecs>;
+
+export const ArchivedWideIntegers = r.struct({
+  a: r.u128,
+  b: r.i128,
+  c: r.usize,
+  d: r.isize,
+  e: r.u32,
+  f: r.option(r.u64),
+  g: r.option(r.i16),
+});
+
+export type WideIntegers = r.Infer<typeof ArchivedWideIntegers>;
+
+/** `MapKV` on a hash map: the hashed layout with wrapped keys and values. */
+export const ArchivedWithHashMap = r.struct({
+  m: hashMap(r.string, r.box(r.u32)),
+});
+
+/** `MapKV` on a hash map: the hashed layout with wrapped keys and values. */
+export type WithHashMap = r.Infer<typeof ArchivedWithHashMap>;
+
+/** rkyv's built-in `with` wrappers, each reshaping its field's archived type. */
+export const ArchivedWithWrappers = r.struct({
+  maybe: r.option(r.box(r.string)),
+  list: r.vec(r.box(r.u32)),
+  fixed: r.array(r.box(r.u16), 3),
+  sorted: btreeMap(r.box(r.string), r.u32),
+  entries: r.vec(r.struct({ key: r.u8, value: r.string })),
+  members: r.vec(r.u32),
+  guarded: r.u32,
+  shared: r.string,
+  cell: r.i32,
+  counted: r.string,
+  atomic_counted: r.u64,
+  text: r.string,
+  slice: r.vec(r.u16),
+  path: r.string,
+  count: r.u32,
+  flag: r.bool,
+  plain: r.u8,
+});
+
+/** rkyv's built-in `with` wrappers, each reshaping its field's archived type. */
+export type WithWrappers = r.Infer<typeof ArchivedWithWrappers>;
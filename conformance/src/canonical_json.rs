@@ -9,6 +9,8 @@
 //! - non-finite floats → `{"$bits32"/"$bits64": "hex"}` (JSON has no NaN)
 //! - finite floats → plain numbers (shortest round-trip is exact)
 //! - byte strings → `{"$base64": "…"}`
+//! - `SystemTime` (through [`unix_time`]) → `{"$date": millis}`, the
+//!   `Date` that `AsUnixTime` fields decode to
 //! - maps → `{"$map": [[k, v], …]}` (keys are values, which plain JSON
 //!   objects cannot express); UNORDERED hash containers must serialize
 //!   through [`sorted_map`]/[`sorted_set`] so golden order never depends on
@@ -35,6 +37,60 @@ pub fn to_string_pretty<T: Serialize>(value: &T) -> String {
     out
 }
 
+/// serde `serialize_with` for `usize`: a plain number, matching the JS
+/// `number` that `r.usize` decodes to (serde alone would tag it `$bigint`
+/// like any `u64`). Archived values are bounded by `FixedUsize`.
+// dead_code: the format-profile smoke crates include this module by path
+// but their reduced case sets carry no pointer-sized integers.
+#[allow(dead_code)]
+pub fn usize_number<S: ser::Serializer>(value: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+    match u32::try_from(*value) {
+        Ok(v) => serializer.serialize_u32(v),
+        Err(_) => serializer.serialize_f64(*value as f64),
+    }
+}
+
+/// serde `serialize_with` for `isize`; see [`usize_number`].
+#[allow(dead_code)]
+pub fn isize_number<S: ser::Serializer>(value: &isize, serializer: S) -> Result<S::Ok, S::Error> {
+    match i32::try_from(*value) {
+        Ok(v) => serializer.serialize_i32(v),
+        Err(_) => serializer.serialize_f64(*value as f64),
+    }
+}
+
+/// serde `serialize_with` for `Option<NonZeroUsize>`; see [`usize_number`].
+#[allow(dead_code)]
+pub fn nonzero_usize_number<S: ser::Serializer>(
+    value: &Option<std::num::NonZeroUsize>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => usize_number(&value.get(), serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// serde `serialize_with` for `SystemTime` fields archived with
+/// `AsUnixTime`: milliseconds since the epoch under a `$date` tag, revived as
+/// the `Date` that `unixTime` decodes to.
+// dead_code: the format-profile smoke crates include this module by path
+// but their reduced case sets carry no timestamps.
+#[allow(dead_code)]
+pub fn unix_time<S: ser::Serializer>(
+    value: &std::time::SystemTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use ser::SerializeStruct as _;
+
+    let since_epoch = value
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(ser::Error::custom)?;
+    let mut date = serializer.serialize_struct("Date", 1)?;
+    date.serialize_field("$date", &(since_epoch.as_millis() as f64))?;
+    date.end()
+}
+
 /// serde `serialize_with` for unordered hash maps: entries sorted by the
 /// canonical JSON of their key.
 ///
@@ -63,6 +119,29 @@ where
     serializer.collect_map(entries.into_iter().map(|(_, kv)| kv))
 }
 
+/// serde `serialize_with` for maps archived `with = AsVec`: an array of
+/// `{ key, value }` entries in map order, the shape `r.vec(r.struct(...))` decodes to.
+// dead_code: the format-profile smoke crates include this module by path
+// but their reduced case sets carry no `AsVec` maps.
+#[allow(dead_code)]
+pub fn map_entries<K, V, S>(
+    map: &std::collections::BTreeMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    S: ser::Serializer,
+{
+    #[derive(serde::Serialize)]
+    struct Entry<'a, K, V> {
+        key: &'a K,
+        value: &'a V,
+    }
+
+    serializer.collect_seq(map.iter().map(|(key, value)| Entry { key, value }))
+}
+
 /// serde `serialize_with` for unordered hash sets: elements sorted by their
 /// canonical JSON. Same rationale as [`sorted_map`].
 // dead_code: the format-profile smoke crates include this module by path
@@ -84,6 +163,62 @@ where
     serializer.collect_seq(items.into_iter().map(|(_, item)| item))
 }
 
+/// serde `serialize_with` for `SocketAddr`: the `{ ip, port, ... }` object
+/// the JS `socketAddr` codec decodes to, with the address as `Display` text
+/// (serde alone would write `"1.2.3.4:80"`).
+// dead_code: the format-profile smoke crates include this module by path
+// but their reduced case sets carry no network types.
+#[allow(dead_code)]
+pub fn socket_addr<S: ser::Serializer>(
+    value: &std::net::SocketAddr,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        std::net::SocketAddr::V4(addr) => socket_addr_v4(addr, serializer),
+        std::net::SocketAddr::V6(addr) => socket_addr_v6(addr, serializer),
+    }
+}
+
+/// serde `serialize_with` for `SocketAddrV4`; see [`socket_addr`].
+#[allow(dead_code)]
+pub fn socket_addr_v4<S: ser::Serializer>(
+    value: &std::net::SocketAddrV4,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use ser::SerializeStruct as _;
+
+    let mut addr = serializer.serialize_struct("SocketAddrV4", 2)?;
+    addr.serialize_field("ip", &value.ip().to_string())?;
+    addr.serialize_field("port", &value.port())?;
+    addr.end()
+}
+
+/// serde `serialize_with` for `SocketAddrV6`; see [`socket_addr`].
+#[allow(dead_code)]
+pub fn socket_addr_v6<S: ser::Serializer>(
+    value: &std::net::SocketAddrV6,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use ser::SerializeStruct as _;
+
+    let mut addr = serializer.serialize_struct("SocketAddrV6", 4)?;
+    addr.serialize_field("ip", &value.ip().to_string())?;
+    addr.serialize_field("port", &value.port())?;
+    addr.serialize_field("flowinfo", &value.flowinfo())?;
+    addr.serialize_field("scopeId", &value.scope_id())?;
+    addr.end()
+}
+
+/// serde `serialize_with` for `CString`: its UTF-8 text without the nul,
+/// as the JS `cString` codec decodes it (serde alone would write the bytes).
+#[allow(dead_code)]
+pub fn c_string<S: ser::Serializer>(
+    value: &std::ffi::CString,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(value.to_str().map_err(ser::Error::custom)?)
+}
+
 fn bigint(value: impl ToString) -> Value {
     let mut map = Map::new();
     map.insert("$bigint".into(), Value::String(value.to_string()));
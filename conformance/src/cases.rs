@@ -5,10 +5,20 @@
 //! data.bin, compares against data.json, re-encodes, and writes `js.bin`;
 //! `verify` then checks js.bin with real rkyv.
 
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::CString;
 use std::fmt::Debug;
 use std::io;
-use std::path::Path;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use std::num::{NonZeroI64, NonZeroU32, NonZeroU64, NonZeroUsize};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
 use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU32};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, UNIX_EPOCH};
 
 use rkyv::api::high::{HighDeserializer, HighSerializer, HighValidator};
 use rkyv::bytecheck::CheckBytes;
@@ -169,6 +179,34 @@ macro_rules! str_map_lookup {
     };
 }
 
+fn leaf(label: &str, weight: u32) -> TreeNode {
+    TreeNode { label: label.into(), weight, children: Vec::new() }
+}
+
+fn recursive() -> Recursive {
+    Recursive {
+        tree: TreeNode {
+            label: "root".into(),
+            weight: 0,
+            children: vec![
+                TreeNode {
+                    label: "branch with an out-of-line label".into(),
+                    weight: 1,
+                    children: vec![leaf("a", 2), leaf("b", 3)],
+                },
+                leaf("c", 4),
+            ],
+        },
+        program: vec![
+            Stmt::Let { name: "x".into(), value: Expr::Lit(7) },
+            Stmt::Eval(Expr::Add(
+                Box::new(Expr::Neg(Box::new(Expr::Lit(-2)))),
+                Box::new(Expr::Block(vec![Stmt::Eval(Expr::Lit(40))])),
+            )),
+        ],
+    }
+}
+
 fn sink() -> KitchenSink {
     KitchenSink {
         id: uuid::Uuid::from_u128(0x550e8400_e29b_41d4_a716_446655440000),
@@ -212,6 +250,10 @@ pub fn all_cases() -> Vec<Case> {
             g: u64::MAX, h: i64::MIN,
             i: f32::MAX, j: f64::MIN_POSITIVE, k: false, l: '\u{10FFFF}',
         }),
+        case!("wide_integers", WideIntegers, Identical, ordered: false, WideIntegers {
+            a: u128::MAX - 1, b: i128::MIN + 7, c: 4_000_000_000, d: -2_000_000_000,
+            e: NonZeroU32::MAX, f: NonZeroU64::new(1 << 40), g: None,
+        }),
         case!("float_specials", FloatSpecials, Identical, ordered: false, FloatSpecials {
             nan32: f32::NAN, nan64: f64::NAN,
             pos_inf: f64::INFINITY, neg_inf: f32::NEG_INFINITY,
@@ -262,12 +304,62 @@ pub fn all_cases() -> Vec<Case> {
             wrap: TupleVariants::Wrap("a wrapped out-of-line string value".into()),
             in_option: Some(MixedAlign::V { a: 1, b: 2 }),
         }),
+        case!("std_enums", StdEnums, Identical, ordered: false, StdEnums {
+            ok: Ok(7),
+            err: Err("failed to parse".into()),
+            results: vec![Ok(()), Err(3)],
+            span: 16..u64::MAX,
+            lines: 3..=12,
+            from: -5..,
+            to: ..'z',
+            included: Bound::Included(1),
+            excluded: Bound::Excluded(u32::MAX),
+            unbounded: Bound::Unbounded,
+        }),
+        case!("net_types", NetTypes, Identical, ordered: false, NetTypes {
+            v4: Ipv4Addr::new(192, 168, 0, 1),
+            v6: "2001:db8::ff00:42:8329".parse().unwrap(),
+            ips: vec![
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(Ipv6Addr::LOCALHOST),
+                IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+                IpAddr::V6(Ipv4Addr::new(10, 0, 0, 7).to_ipv6_mapped()),
+                IpAddr::V6("fe80:0:0:1::1:0:0".parse().unwrap()),
+            ],
+            listen: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 8080),
+            local: SocketAddrV6::new("fe80::1".parse().unwrap(), 443, 0x000c_afe0, 3),
+            peer_v4: "203.0.113.9:65535".parse().unwrap(),
+            peer_v6: "[2001:db8:0:0:1::]:53".parse().unwrap(),
+            hits: hash_map([
+                (IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 3),
+                (IpAddr::V4(Ipv4Addr::BROADCAST), 1),
+                (IpAddr::V6(Ipv6Addr::LOCALHOST), 7),
+                (IpAddr::V6("2606:4700::1111".parse().unwrap()), 42),
+            ]),
+            name: CString::new("eth0 — uplink").unwrap(),
+            empty_name: CString::default(),
+        }),
+        case!("discriminants", DiscriminantCases, Identical, ordered: false, DiscriminantCases {
+            pending: Discriminated::Pending,
+            active: Discriminated::Active,
+            banned: Discriminated::Banned { until: 1_700_000_000 },
+            deleted: Discriminated::Deleted,
+        }),
         case!("pointers", Pointers, Identical, ordered: false, Pointers {
             boxed: Box::new("boxed string, long enough to go out of line".into()),
             boxed_int: Box::new(0x1122_3344_5566_7788),
             rc: Rc::new("reference counted".into()),
             weak_dead: Weak::new(),
         }),
+        case!("unsized_pointers", UnsizedPointers, Identical, ordered: false, UnsizedPointers {
+            name: "boxed".into(),
+            empty: "".into(),
+            ids: vec![1, 2, 3].into(),
+            shared_name: "shared, and too long to fit inline".into(),
+            shared_points: vec![Point { x: 1.0, y: -2.5 }].into(),
+            note: Some("ü".into()),
+            missing: None,
+        }),
         case!("shared_rc", SharedRc, Semantic, ordered: false, {
             let shared = Rc::new("shared allocation".to_string());
             SharedRc { a: Rc::clone(&shared), b: shared }
@@ -459,6 +551,48 @@ pub fn all_cases() -> Vec<Case> {
             deque: (0..10).collect(),
             shared: triomphe::Arc::new("triomphe shared string".into()),
         }),
+        // Millisecond precision: `unixTime` decodes to a `Date`.
+        case!("time_types", TimeTypes, Identical, ordered: false, TimeTypes {
+            took: Duration::new(3, 141_592_653),
+            timeout: Some(Duration::from_secs(30)),
+            at: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        }),
+        case!("with_wrappers", WithWrappers, Identical, ordered: true, WithWrappers {
+            maybe: Some("boxed".into()),
+            list: vec![1, 2, 3],
+            fixed: [7, 8, 9],
+            sorted: BTreeMap::from([("a".into(), 1), ("b".into(), 2)]),
+            entries: BTreeMap::from([(1, "one".into()), (2, "two".into())]),
+            members: BTreeSet::from([3, 1, 2]),
+            guarded: Mutex::new(42),
+            shared: RwLock::new("locked".into()),
+            cell: Cell::new(-5),
+            counted: Rc::new("unshared".into()),
+            atomic_counted: Arc::new(u64::MAX),
+            text: Cow::Borrowed("borrowed"),
+            slice: Cow::Owned(vec![4, 5]),
+            path: PathBuf::from("/tmp/archive.bin"),
+            count: AtomicU32::new(17),
+            flag: AtomicBool::new(true),
+            plain: 255,
+        }),
+        case!("with_hash_map", WithHashMap, Semantic, ordered: false, WithHashMap {
+            m: hash_map((0..10).map(|i| (format!("key_{i}"), i))),
+        }),
+        case!("niche_zero", NicheZero, Identical, ordered: false, NicheZero {
+            some: NonZeroU32::new(7), none: None,
+            wide: NonZeroI64::new(-1), size: NonZeroUsize::new(4096),
+        }),
+        case!("niche_nan", NicheNaN, Identical, ordered: false, NicheNaN {
+            some: Some(2.5), none: None,
+        }),
+        case!("niche_bool", NicheBool, Identical, ordered: false, NicheBool {
+            some: Some(false), none: None,
+        }),
+        case!("niche_null", NicheNull, Identical, ordered: false, NicheNull {
+            some: Some(Box::new("niched".into())), none: None, mapped: Some(u64::MAX),
+        }),
+        case!("recursive", Recursive, Identical, ordered: false, recursive()),
         case!("kitchen_sink", KitchenSink, Semantic, ordered: false, sink()),
     ]
 }
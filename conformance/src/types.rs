@@ -15,13 +15,29 @@
 //! exception is [`SipKeyedMap`], which archives with a custom hasher on
 //! purpose to pin the JS side's pluggable-hasher support.
 
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::CString;
 use std::hash::BuildHasherDefault;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::num::{NonZeroI16, NonZeroI64, NonZeroU32, NonZeroU64, NonZeroUsize};
+use std::ops::{Bound, Range, RangeFrom, RangeInclusive, RangeTo};
+use std::path::PathBuf;
 use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
 
 use rkyv::collections::swiss_table::map::{ArchivedHashMap, HashMapResolver};
 use rkyv::rancor::{Fallible, Source};
 use rkyv::ser::{Allocator, Writer};
 use rkyv::string::ArchivedString;
+use rkyv::niche::niching::{Bool, NaN, Null, Zero};
+use rkyv::with::{
+    AsBox, AsOwned, AsString, AsUnixTime, AsVec, AtomicLoad, DefaultNiche, Identity, Lock, Map,
+    MapKV, MapNiche, Niche, NicheInto, Relaxed, Unsafe, Unshare,
+};
 use rkyv::{Archive, Archived, Deserialize, Place, Serialize};
 use siphasher::sip::SipHasher13;
 
@@ -65,6 +81,21 @@ pub struct Primitives {
     pub l: char,
 }
 
+// usize/isize archive as the pointer-width `FixedUsize`/`FixedIsize` and
+// decode to JS numbers, so their goldens are plain numbers too.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct WideIntegers {
+    pub a: u128,
+    pub b: i128,
+    #[serde(serialize_with = "crate::canonical_json::usize_number")]
+    pub c: usize,
+    #[serde(serialize_with = "crate::canonical_json::isize_number")]
+    pub d: isize,
+    pub e: NonZeroU32,
+    pub f: Option<NonZeroU64>,
+    pub g: Option<NonZeroI16>,
+}
+
 #[derive(Archive, Serialize, Deserialize, Debug, serde::Serialize)]
 pub struct FloatSpecials {
     pub nan32: f32,
@@ -173,6 +204,25 @@ pub enum TupleVariants {
 // NOTE: rkyv's derive rejects enums with more than 256 variants (u8
 // discriminant only); the JS taggedEnum enforces the same limit.
 
+/// Explicit discriminants: rkyv copies them onto its `repr(u8)` archived
+/// tag whatever the source repr, counting up implicitly in between.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+#[repr(u16)]
+pub enum Discriminated {
+    Pending = 1,
+    Active,
+    Banned { until: u64 } = 0x10,
+    Deleted,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct DiscriminantCases {
+    pub pending: Discriminated,
+    pub active: Discriminated,
+    pub banned: Discriminated,
+    pub deleted: Discriminated,
+}
+
 #[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
 pub struct EnumCases {
     pub unit: UnitOnly,
@@ -185,6 +235,46 @@ pub struct EnumCases {
     pub in_option: Option<MixedAlign>,
 }
 
+/// `Result` and `Bound` archive as `repr(u8)` enums in variant order, the
+/// ranges as `repr(C)` structs of their bounds.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct StdEnums {
+    pub ok: Result<u32, String>,
+    pub err: Result<u32, String>,
+    pub results: Vec<Result<(), u8>>,
+    pub span: Range<u64>,
+    pub lines: RangeInclusive<u16>,
+    pub from: RangeFrom<i32>,
+    pub to: RangeTo<char>,
+    pub included: Bound<u32>,
+    pub excluded: Bound<u32>,
+    pub unbounded: Bound<String>,
+}
+
+/// `std::net` addresses archive as their octets (`IpAddr` and `SocketAddr`
+/// as `repr(u8)` enums over them), decoded as `Display` text; `CString`
+/// archives like a byte slice, trailing nul included.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct NetTypes {
+    pub v4: Ipv4Addr,
+    pub v6: Ipv6Addr,
+    pub ips: Vec<IpAddr>,
+    #[serde(serialize_with = "crate::canonical_json::socket_addr_v4")]
+    pub listen: SocketAddrV4,
+    #[serde(serialize_with = "crate::canonical_json::socket_addr_v6")]
+    pub local: SocketAddrV6,
+    #[serde(serialize_with = "crate::canonical_json::socket_addr")]
+    pub peer_v4: SocketAddr,
+    #[serde(serialize_with = "crate::canonical_json::socket_addr")]
+    pub peer_v6: SocketAddr,
+    #[serde(serialize_with = "crate::canonical_json::sorted_map")]
+    pub hits: HashMap<IpAddr, u32>,
+    #[serde(serialize_with = "crate::canonical_json::c_string")]
+    pub name: CString,
+    #[serde(serialize_with = "crate::canonical_json::c_string")]
+    pub empty_name: CString,
+}
+
 // ============================================================================
 // Pointers
 // ============================================================================
@@ -235,6 +325,21 @@ pub struct SharedRc {
     pub b: Rc<String>,
 }
 
+/// Pointers to `str` and slices: a relative pointer plus a length. Unlike
+/// `String`, short text is never stored inline.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct UnsizedPointers {
+    pub name: Box<str>,
+    pub empty: Box<str>,
+    pub ids: Box<[u32]>,
+    pub shared_name: Arc<str>,
+    pub shared_points: Rc<[Point]>,
+    #[rkyv(with = Niche)]
+    pub note: Option<Box<str>>,
+    #[rkyv(with = Niche)]
+    pub missing: Option<Box<[u8]>>,
+}
+
 // NOTE: there is deliberately no live-Weak case. rkyv-js does not dedup
 // shared pointers on encode, so a JS-encoded live `Weak` points at its own
 // copy of the target; Rust's deserializer drops that temporary Rc and the
@@ -422,6 +527,185 @@ pub struct ExternalTypes {
     pub shared: triomphe::Arc<String>,
 }
 
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct TimeTypes {
+    pub took: Duration,
+    pub timeout: Option<Duration>,
+    #[rkyv(with = AsUnixTime)]
+    #[serde(serialize_with = "crate::canonical_json::unix_time")]
+    pub at: SystemTime,
+}
+
+// ============================================================================
+// With-wrappers
+// ============================================================================
+
+/// rkyv's built-in `with` wrappers, each reshaping its field's archived type.
+#[derive(Archive, Serialize, Deserialize, Debug, serde::Serialize)]
+pub struct WithWrappers {
+    #[rkyv(with = Map<AsBox>)]
+    pub maybe: Option<String>,
+    #[rkyv(with = Map<AsBox>)]
+    pub list: Vec<u32>,
+    #[rkyv(with = Map<AsBox>)]
+    pub fixed: [u16; 3],
+    #[rkyv(with = MapKV<AsBox, Identity>)]
+    pub sorted: BTreeMap<String, u32>,
+    #[rkyv(with = AsVec)]
+    #[serde(serialize_with = "crate::canonical_json::map_entries")]
+    pub entries: BTreeMap<u8, String>,
+    #[rkyv(with = AsVec)]
+    pub members: BTreeSet<u32>,
+    #[rkyv(with = Lock)]
+    pub guarded: Mutex<u32>,
+    #[rkyv(with = Lock)]
+    pub shared: RwLock<String>,
+    #[rkyv(with = Unsafe)]
+    pub cell: Cell<i32>,
+    #[rkyv(with = Unshare)]
+    pub counted: Rc<String>,
+    #[rkyv(with = Unshare)]
+    pub atomic_counted: Arc<u64>,
+    #[rkyv(with = AsOwned)]
+    pub text: Cow<'static, str>,
+    #[rkyv(with = AsOwned)]
+    pub slice: Cow<'static, [u16]>,
+    #[rkyv(with = AsString)]
+    pub path: PathBuf,
+    #[rkyv(with = AtomicLoad<Relaxed>)]
+    pub count: AtomicU32,
+    #[rkyv(with = AtomicLoad<Relaxed>)]
+    pub flag: AtomicBool,
+    #[rkyv(with = Identity)]
+    pub plain: u8,
+}
+
+// Locks and atomics have no PartialEq; compare the values they hold.
+impl PartialEq for WithWrappers {
+    fn eq(&self, other: &Self) -> bool {
+        self.maybe == other.maybe
+            && self.list == other.list
+            && self.fixed == other.fixed
+            && self.sorted == other.sorted
+            && self.entries == other.entries
+            && self.members == other.members
+            && *self.guarded.lock().unwrap() == *other.guarded.lock().unwrap()
+            && *self.shared.read().unwrap() == *other.shared.read().unwrap()
+            && self.cell == other.cell
+            && self.counted == other.counted
+            && self.atomic_counted == other.atomic_counted
+            && self.text == other.text
+            && self.slice == other.slice
+            && self.path == other.path
+            && self.count.load(Ordering::Relaxed) == other.count.load(Ordering::Relaxed)
+            && self.flag.load(Ordering::Relaxed) == other.flag.load(Ordering::Relaxed)
+            && self.plain == other.plain
+    }
+}
+
+/// `MapKV` on a hash map: the hashed layout with wrapped keys and values.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct WithHashMap {
+    #[rkyv(with = MapKV<Identity, AsBox>)]
+    #[serde(serialize_with = "crate::canonical_json::sorted_map")]
+    pub m: HashMap<String, u32>,
+}
+
+// ============================================================================
+// Niched options
+// ============================================================================
+
+// One type per niche kind; each holds a `Some` and a `None`, and neither
+// option takes a tag byte.
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct NicheZero {
+    #[rkyv(with = Niche)]
+    pub some: Option<NonZeroU32>,
+    #[rkyv(with = Niche)]
+    pub none: Option<NonZeroU32>,
+    #[rkyv(with = NicheInto<Zero>)]
+    pub wide: Option<NonZeroI64>,
+    #[rkyv(with = Niche)]
+    #[serde(serialize_with = "crate::canonical_json::nonzero_usize_number")]
+    pub size: Option<NonZeroUsize>,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct NicheNaN {
+    #[rkyv(with = NicheInto<NaN>)]
+    pub some: Option<f64>,
+    #[rkyv(with = NicheInto<NaN>)]
+    pub none: Option<f32>,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct NicheBool {
+    #[rkyv(with = NicheInto<Bool>)]
+    pub some: Option<bool>,
+    #[rkyv(with = DefaultNiche)]
+    pub none: Option<bool>,
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct NicheNull {
+    #[rkyv(with = Niche)]
+    pub some: Option<Box<String>>,
+    #[rkyv(with = NicheInto<Null>)]
+    pub none: Option<Box<u32>>,
+    #[rkyv(with = MapNiche<AsBox>)]
+    pub mapped: Option<u64>,
+}
+
+// ============================================================================
+// Recursive types
+// ============================================================================
+
+// Recursion needs `omit_bounds` on the recursive fields, with the bounds
+// restated per trait so the derive does not require itself.
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+#[rkyv(serialize_bounds(__S: Writer + Allocator, __S::Error: Source))]
+#[rkyv(deserialize_bounds(__D::Error: Source))]
+#[rkyv(bytecheck(bounds(__C: rkyv::validation::ArchiveContext, __C::Error: Source)))]
+pub struct TreeNode {
+    pub label: String,
+    pub weight: u32,
+    #[rkyv(omit_bounds)]
+    pub children: Vec<TreeNode>,
+}
+
+/// Mutually recursive with [`Stmt`] through `Block`.
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+#[rkyv(serialize_bounds(__S: Writer + Allocator, __S::Error: Source))]
+#[rkyv(deserialize_bounds(__D::Error: Source))]
+#[rkyv(bytecheck(bounds(__C: rkyv::validation::ArchiveContext, __C::Error: Source)))]
+pub enum Expr {
+    Lit(i32),
+    Neg(#[rkyv(omit_bounds)] Box<Expr>),
+    Add(#[rkyv(omit_bounds)] Box<Expr>, #[rkyv(omit_bounds)] Box<Expr>),
+    Block(#[rkyv(omit_bounds)] Vec<Stmt>),
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+#[rkyv(serialize_bounds(__S: Writer + Allocator, __S::Error: Source))]
+#[rkyv(deserialize_bounds(__D::Error: Source))]
+#[rkyv(bytecheck(bounds(__C: rkyv::validation::ArchiveContext, __C::Error: Source)))]
+pub enum Stmt {
+    Let {
+        name: String,
+        #[rkyv(omit_bounds)]
+        value: Expr,
+    },
+    Eval(#[rkyv(omit_bounds)] Expr),
+}
+
+#[derive(Archive, Serialize, Deserialize, Debug, PartialEq, serde::Serialize)]
+pub struct Recursive {
+    pub tree: TreeNode,
+    pub program: Vec<Stmt>,
+}
+
 // ============================================================================
 // Kitchen sink
 // ============================================================================
@@ -22,12 +22,12 @@ fn main() -> Result<(), Error> {
 
     // Extract every type annotated with #[derive(Archive)].
     codegen.add_source_file(manifest_dir.join("src/lib.rs"))?;
+    codegen.emit_cargo_rerun_directives();
 
     // Write to OUT_DIR (standard cargo location) and to the in-tree copy consumed by the TypeScript workspace.
     codegen.write_to_file(out_dir.join("bindings.ts"))?;
     codegen.write_to_file(manifest_dir.join("generated/bindings.ts"))?;
 
-    println!("cargo:rerun-if-changed=src/lib.rs");
     println!("cargo:rerun-if-changed=build.rs");
     Ok(())
 }
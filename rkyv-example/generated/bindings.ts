@@ -11,78 +11,103 @@ import { hashMap, hashSet } from 'rkyv-js/lib/hashmap';
 import { indexMap, indexSet } from 'rkyv-js/lib/indexmap';
 import { uuid } from 'rkyv-js/lib/uuid';
 
+/** Shared data using triomphe::Arc for reference counting. */
 export const ArchivedArcShared = r.struct({
   shared_data: r.rc(r.string),
   local_data: r.u32,
 });
 
+/** Shared data using triomphe::Arc for reference counting. */
 export type ArcShared = r.Infer<typeof ArchivedArcShared>;
 
+/** A buffer using arrayvec::ArrayVec for fixed-capacity inline storage. */
 export const ArchivedArrayVecBuffer = r.struct({
   data: r.vec(r.u32),
   name: r.string,
 });
 
+/** A buffer using arrayvec::ArrayVec for fixed-capacity inline storage. */
 export type ArrayVecBuffer = r.Infer<typeof ArchivedArrayVecBuffer>;
 
+/** Configuration using std::collections::BTreeMap for sorted key order. */
 export const ArchivedBTreeMapConfig = r.struct({
   settings: btreeMap(r.string, r.u32),
   version: r.u32,
 });
 
+/** Configuration using std::collections::BTreeMap for sorted key order. */
 export type BTreeMapConfig = r.Infer<typeof ArchivedBTreeMapConfig>;
 
+/** Sorted unique items using std::collections::BTreeSet. */
 export const ArchivedBTreeSetData = r.struct({
   values: btreeSet(r.i64),
   label: r.string,
 });
 
+/** Sorted unique items using std::collections::BTreeSet. */
 export type BTreeSetData = r.Infer<typeof ArchivedBTreeSetData>;
 
+/** A message with binary payload using bytes::Bytes. */
 export const ArchivedBytesMessage = r.struct({
   payload: bytes,
   checksum: r.u32,
 });
 
+/** A message with binary payload using bytes::Bytes. */
 export type BytesMessage = r.Infer<typeof ArchivedBytesMessage>;
 
+/** Configuration using std::collections::HashMap. */
 export const ArchivedHashMapData = r.struct({
   entries: hashMap(r.string, r.u32),
   name: r.string,
 });
 
+/** Configuration using std::collections::HashMap. */
 export type HashMapData = r.Infer<typeof ArchivedHashMapData>;
 
+/** Unique items using std::collections::HashSet. */
 export const ArchivedHashSetData = r.struct({
   ids: hashSet(r.string),
   count: r.u32,
 });
 
+/** Unique items using std::collections::HashSet. */
 export type HashSetData = r.Infer<typeof ArchivedHashSetData>;
 
+/** Configuration using indexmap::IndexMap to preserve insertion order. */
 export const ArchivedIndexMapConfig = r.struct({
   settings: indexMap(r.string, r.u32),
   version: r.u32,
 });
 
+/** Configuration using indexmap::IndexMap to preserve insertion order. */
 export type IndexMapConfig = r.Infer<typeof ArchivedIndexMapConfig>;
 
+/** Tags using indexmap::IndexSet to preserve insertion order. */
 export const ArchivedIndexSetTags = r.struct({
   tags: indexSet(r.string),
   count: r.u32,
 });
 
+/** Tags using indexmap::IndexSet to preserve insertion order. */
 export type IndexSetTags = r.Infer<typeof ArchivedIndexSetTags>;
 
+/** A message enum with different variant types. */
 export const ArchivedMessage = r.taggedEnum({
+  /** No content */
   Quit: null,
+  /** Movement with coordinates */
   Move: { x: r.i32, y: r.i32 },
+  /** A text message */
   Write: r.string,
+  /** RGB color change */
   ChangeColor: [r.u8, r.u8, r.u8],
 });
 
+/** A message enum with different variant types. */
 export type Message = r.Infer<typeof ArchivedMessage>;
 
+/** A person with various field types. */
 export const ArchivedPerson = r.struct({
   name: r.string,
   age: r.u32,
@@ -91,15 +116,19 @@ export const ArchivedPerson = r.struct({
   active: r.bool,
 });
 
+/** A person with various field types. */
 export type Person = r.Infer<typeof ArchivedPerson>;
 
+/** A simple 2D point. */
 export const ArchivedPoint = r.struct({
   x: r.f64,
   y: r.f64,
 });
 
+/** A simple 2D point. */
 export type Point = r.Infer<typeof ArchivedPoint>;
 
+/** Game state containing nested structures. */
 export const ArchivedGameState = r.struct({
   player_position: ArchivedPoint,
   health: r.u32,
@@ -107,56 +136,81 @@ export const ArchivedGameState = r.struct({
   current_message: r.option(ArchivedMessage),
 });
 
+/** Game state containing nested structures. */
 export type GameState = r.Infer<typeof ArchivedGameState>;
 
+/**
+ * A struct that uses a remote type via an `AsJson` wrapper.
+ *
+ * The codegen never inspects `remote::Coord`
+ * the registered `AsJson` wrapper replaces the field codec with the hand-written `Coord` codec from `generated/coord.ts`.
+ */
 export const ArchivedRemoteEvent = r.struct({
   name: r.string,
   location: Coord,
   priority: r.u32,
 });
 
+/**
+ * A struct that uses a remote type via an `AsJson` wrapper.
+ *
+ * The codegen never inspects `remote::Coord`
+ * the registered `AsJson` wrapper replaces the field codec with the hand-written `Coord` codec from `generated/coord.ts`.
+ */
 export type RemoteEvent = r.Infer<typeof ArchivedRemoteEvent>;
 
+/** Data using smallvec::SmallVec for small-vector optimization. */
 export const ArchivedSmallVecData = r.struct({
   items: r.vec(r.u32),
   tags: r.vec(r.string),
 });
 
+/** Data using smallvec::SmallVec for small-vector optimization. */
 export type SmallVecData = r.Infer<typeof ArchivedSmallVecData>;
 
+/** A config entry using smol_str::SmolStr for small string optimization. */
 export const ArchivedSmolStrConfig = r.struct({
   key: r.string,
   value: r.string,
   priority: r.u32,
 });
 
+/** A config entry using smol_str::SmolStr for small string optimization. */
 export type SmolStrConfig = r.Infer<typeof ArchivedSmolStrConfig>;
 
+/** A data container using thin_vec::ThinVec for stack-efficient storage. */
 export const ArchivedThinVecData = r.struct({
   items: r.vec(r.u32),
   labels: r.vec(r.string),
 });
 
+/** A data container using thin_vec::ThinVec for stack-efficient storage. */
 export type ThinVecData = r.Infer<typeof ArchivedThinVecData>;
 
+/** Data using tinyvec::TinyVec for inline/heap hybrid storage. */
 export const ArchivedTinyVecData = r.struct({
   values: r.vec(r.u32),
   enabled: r.bool,
 });
 
+/** Data using tinyvec::TinyVec for inline/heap hybrid storage. */
 export type TinyVecData = r.Infer<typeof ArchivedTinyVecData>;
 
+/** A record with a UUID identifier. */
 export const ArchivedUuidRecord = r.struct({
   id: uuid,
   name: r.string,
   active: r.bool,
 });
 
+/** A record with a UUID identifier. */
 export type UuidRecord = r.Infer<typeof ArchivedUuidRecord>;
 
+/** Data using std::collections::VecDeque for double-ended queue. */
 export const ArchivedVecDequeData = r.struct({
   items: r.vec(r.u32),
   name: r.string,
 });
 
+/** Data using std::collections::VecDeque for double-ended queue. */
 export type VecDequeData = r.Infer<typeof ArchivedVecDequeData>;
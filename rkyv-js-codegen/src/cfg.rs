@@ -0,0 +1,351 @@
+//! Conditional compilation: `#[cfg(...)]` and `#[cfg_attr(...)]` evaluation.
+//!
+//! Sources are stripped right after parsing, the way rustc does before macro expansion:
+//! `cfg_attr` attributes are expanded into the attributes they guard, and items, fields and variants
+//! whose `cfg` predicate is false are removed. Extraction then never sees a type, field or variant
+//! the real build would drop, so the emitted layout matches the compiled one.
+
+use std::collections::BTreeSet;
+
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Fields, Meta, Token};
+
+/// The set of active `cfg` options that `#[cfg(...)]` and `#[cfg_attr(...)]` are evaluated against.
+///
+/// A [`CodeGenerator`](crate::CodeGenerator) starts from [`CfgSet::from_env`],
+/// which inside a build script mirrors the options of the crate being built.
+///
+/// ```
+/// use rkyv_js_codegen::{CfgSet, CodeGenerator};
+///
+/// let mut cfg = CfgSet::new();
+/// cfg.enable("unix").enable_value("target_os", "linux").enable_feature("rkyv");
+///
+/// let mut generator = CodeGenerator::new();
+/// generator.set_cfg(cfg);
+/// generator.add_source_str(r#"
+///     #[cfg_attr(feature = "rkyv", derive(rkyv::Archive))]
+///     pub struct Point {
+///         pub x: f64,
+///         #[cfg(windows)]
+///         pub handle: u64,
+///     }
+/// "#)?;
+///
+/// let code = generator.generate()?;
+/// assert!(code.contains("export const ArchivedPoint = r.struct({\n  x: r.f64,\n});"));
+/// # Ok::<(), rkyv_js_codegen::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgSet {
+    /// `(name, None)` for a flag like `unix`, `(name, Some(value))` for `name = "value"`.
+    options: BTreeSet<(String, Option<String>)>,
+}
+
+/// Cargo spells feature `foo-bar` as `CARGO_FEATURE_FOO_BAR`, so features compare case- and dash-insensitively.
+fn normalize_feature(feature: &str) -> String {
+    feature.to_lowercase().replace('-', "_")
+}
+
+impl CfgSet {
+    /// An empty set: every option is inactive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The options cargo passes to a build script: every `CARGO_FEATURE_<NAME>` becomes `feature = "<name>"`,
+    /// and every `CARGO_CFG_<NAME>` becomes the flag `<name>` (empty value) or `<name> = "<value>"`
+    /// for each comma-separated value.
+    ///
+    /// Outside a build script none of these variables are set and the result is empty.
+    pub fn from_env() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    pub(crate) fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut cfg = Self::new();
+        for (key, value) in vars {
+            if let Some(feature) = key.strip_prefix("CARGO_FEATURE_") {
+                cfg.enable_feature(feature);
+            } else if let Some(name) = key.strip_prefix("CARGO_CFG_") {
+                let name = name.to_lowercase();
+                if value.is_empty() {
+                    cfg.enable(name);
+                } else {
+                    for value in value.split(',') {
+                        cfg.enable_value(name.clone(), value);
+                    }
+                }
+            }
+        }
+        cfg
+    }
+
+    /// Activate a flag option, e.g. `unix` or `test`.
+    pub fn enable(&mut self, name: impl Into<String>) -> &mut Self {
+        self.options.insert((name.into(), None));
+        self
+    }
+
+    /// Activate a key-value option, e.g. `target_os = "linux"`.
+    /// A key may be active with several values at once.
+    pub fn enable_value(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        let value = value.into();
+        let value = if name == "feature" {
+            normalize_feature(&value)
+        } else {
+            value
+        };
+        self.options.insert((name, Some(value)));
+        self
+    }
+
+    /// Activate a cargo feature: shorthand for `enable_value("feature", feature)`.
+    pub fn enable_feature(&mut self, feature: impl AsRef<str>) -> &mut Self {
+        self.enable_value("feature", feature.as_ref())
+    }
+
+    /// Whether the option `name` (or `name = "value"`) is active.
+    pub fn is_enabled(&self, name: &str, value: Option<&str>) -> bool {
+        let value = match value {
+            Some(value) if name == "feature" => Some(normalize_feature(value)),
+            other => other.map(str::to_string),
+        };
+        self.options.contains(&(name.to_string(), value))
+    }
+
+    /// Evaluate a cfg predicate. `None` if it is malformed.
+    fn eval(&self, predicate: &Meta) -> Option<bool> {
+        match predicate {
+            Meta::Path(path) => Some(self.is_enabled(&path.get_ident()?.to_string(), None)),
+            Meta::NameValue(name_value) => {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) = &name_value.value
+                else {
+                    return None;
+                };
+                let name = name_value.path.get_ident()?.to_string();
+                Some(self.is_enabled(&name, Some(&value.value())))
+            }
+            Meta::List(list) => {
+                let args = list
+                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    .ok()?;
+                let mut results = args.iter().map(|arg| self.eval(arg));
+                match list.path.get_ident()?.to_string().as_str() {
+                    "all" => results.try_fold(true, |all, result| Some(all && result?)),
+                    "any" => results.try_fold(false, |any, result| Some(any || result?)),
+                    "not" if args.len() == 1 => results.next()?.map(|result| !result),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Whether every `#[cfg(...)]` in `attrs` holds.
+    /// A malformed predicate is treated as true, so the item is kept rather than silently dropped.
+    fn is_active(&self, attrs: &[Attribute]) -> bool {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .all(|attr| {
+                attr.parse_args::<Meta>()
+                    .ok()
+                    .and_then(|predicate| self.eval(&predicate))
+                    .unwrap_or(true)
+            })
+    }
+
+    /// Replace every `#[cfg_attr(predicate, attrs...)]` with `attrs...` if the predicate holds,
+    /// or drop it if it does not. Nested `cfg_attr`s are expanded too.
+    fn expand_cfg_attrs(&self, attrs: &mut Vec<Attribute>) {
+        let mut expanded = Vec::with_capacity(attrs.len());
+        for attr in attrs.drain(..) {
+            self.expand_cfg_attr(attr, &mut expanded);
+        }
+        *attrs = expanded;
+    }
+
+    fn expand_cfg_attr(&self, attr: Attribute, out: &mut Vec<Attribute>) {
+        if !attr.path().is_ident("cfg_attr") {
+            out.push(attr);
+            return;
+        }
+        let parsed = attr.parse_args_with(|input: ParseStream| {
+            let predicate: Meta = input.parse()?;
+            input.parse::<Token![,]>()?;
+            let guarded = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+            Ok((predicate, guarded))
+        });
+        let Ok((predicate, guarded)) = parsed else {
+            out.push(attr);
+            return;
+        };
+        if self.eval(&predicate) != Some(true) {
+            return;
+        }
+        for meta in guarded {
+            self.expand_cfg_attr(Attribute { meta, ..attr.clone() }, out);
+        }
+    }
+
+    /// Expand `cfg_attr`s and report whether the attributes' `cfg`s hold.
+    fn configure(&self, attrs: &mut Vec<Attribute>) -> bool {
+        self.expand_cfg_attrs(attrs);
+        self.is_active(attrs)
+    }
+
+    /// Strip a parsed file down to what the build would compile.
+    pub(crate) fn strip_file(&self, file: &mut syn::File) {
+        self.configure(&mut file.attrs);
+        self.strip_items(&mut file.items);
+    }
+
+    fn strip_items(&self, items: &mut Vec<syn::Item>) {
+        items.retain_mut(|item| match item {
+            syn::Item::Struct(item) => {
+                self.strip_fields(&mut item.fields);
+                self.configure(&mut item.attrs)
+            }
+            syn::Item::Enum(item) => {
+                item.variants = std::mem::take(&mut item.variants)
+                    .into_iter()
+                    .filter_map(|mut variant| {
+                        self.strip_fields(&mut variant.fields);
+                        self.configure(&mut variant.attrs).then_some(variant)
+                    })
+                    .collect();
+                self.configure(&mut item.attrs)
+            }
+            syn::Item::Mod(item) => {
+                if let Some((_, content)) = &mut item.content {
+                    self.strip_items(content);
+                }
+                self.configure(&mut item.attrs)
+            }
+            syn::Item::Use(item) => self.configure(&mut item.attrs),
+            syn::Item::Type(item) => self.configure(&mut item.attrs),
+            _ => true,
+        });
+    }
+
+    fn strip_fields(&self, fields: &mut Fields) {
+        let fields = match fields {
+            Fields::Named(named) => &mut named.named,
+            Fields::Unnamed(unnamed) => &mut unnamed.unnamed,
+            Fields::Unit => return,
+        };
+        *fields = std::mem::take(fields)
+            .into_iter()
+            .filter_map(|mut field| self.configure(&mut field.attrs).then_some(field))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(cfg: &CfgSet, predicate: &str) -> Option<bool> {
+        cfg.eval(&syn::parse_str(predicate).unwrap())
+    }
+
+    #[test]
+    fn predicates_follow_rustc_semantics() {
+        let mut cfg = CfgSet::new();
+        cfg.enable("unix").enable_value("target_os", "linux").enable_feature("rkyv");
+        assert_eq!(eval(&cfg, "unix"), Some(true));
+        assert_eq!(eval(&cfg, "windows"), Some(false));
+        assert_eq!(eval(&cfg, r#"target_os = "linux""#), Some(true));
+        assert_eq!(eval(&cfg, r#"target_os = "macos""#), Some(false));
+        assert_eq!(eval(&cfg, r#"all(unix, feature = "rkyv")"#), Some(true));
+        assert_eq!(eval(&cfg, r#"all(unix, feature = "serde")"#), Some(false));
+        assert_eq!(eval(&cfg, "any(windows, unix)"), Some(true));
+        assert_eq!(eval(&cfg, "not(unix)"), Some(false));
+        // Empty `all()` is true and empty `any()` is false, as in rustc.
+        assert_eq!(eval(&cfg, "all()"), Some(true));
+        assert_eq!(eval(&cfg, "any()"), Some(false));
+        assert_eq!(eval(&cfg, "not(unix, windows)"), None);
+        assert_eq!(eval(&cfg, "bogus(unix)"), None);
+    }
+
+    #[test]
+    fn features_compare_dash_insensitively() {
+        let mut cfg = CfgSet::new();
+        cfg.enable_feature("FOO_BAR");
+        assert!(cfg.is_enabled("feature", Some("foo-bar")));
+        assert!(cfg.is_enabled("feature", Some("foo_bar")));
+        assert!(!cfg.is_enabled("target_os", Some("foo_bar")));
+    }
+
+    #[test]
+    fn build_script_vars_populate_the_set() {
+        let cfg = CfgSet::from_vars([
+            ("CARGO_FEATURE_SERDE_JSON".to_string(), "1".to_string()),
+            ("CARGO_CFG_UNIX".to_string(), String::new()),
+            ("CARGO_CFG_TARGET_OS".to_string(), "linux".to_string()),
+            ("CARGO_CFG_TARGET_FEATURE".to_string(), "sse,sse2".to_string()),
+            ("CARGO_PKG_NAME".to_string(), "demo".to_string()),
+        ]);
+        assert!(cfg.is_enabled("feature", Some("serde-json")));
+        assert!(cfg.is_enabled("unix", None));
+        assert!(cfg.is_enabled("target_os", Some("linux")));
+        assert!(cfg.is_enabled("target_feature", Some("sse2")));
+        assert!(!cfg.is_enabled("pkg_name", Some("demo")));
+    }
+
+    #[test]
+    fn cfg_attr_expands_nested_attributes() {
+        let mut cfg = CfgSet::new();
+        cfg.enable("a");
+        let item: syn::ItemStruct = syn::parse_str(
+            r#"
+            #[cfg_attr(a, derive(Archive), cfg_attr(b, rkyv(archived = B)), cfg_attr(a, repr(C)))]
+            #[cfg_attr(not(a), cfg(never))]
+            struct S;
+        "#,
+        )
+        .unwrap();
+        let mut attrs = item.attrs;
+        assert!(cfg.configure(&mut attrs));
+        let paths: Vec<String> = attrs
+            .iter()
+            .map(|attr| attr.path().get_ident().unwrap().to_string())
+            .collect();
+        assert_eq!(paths, ["derive", "repr"]);
+    }
+
+    #[test]
+    fn stripping_drops_inactive_items_fields_and_variants() {
+        let mut cfg = CfgSet::new();
+        cfg.enable_feature("extra");
+        let mut file: syn::File = syn::parse_str(
+            r#"
+            struct Kept {
+                a: u8,
+                #[cfg(feature = "extra")] b: u8,
+                #[cfg(not(feature = "extra"))] c: u8,
+            }
+            struct Tuple(u8, #[cfg(test)] u16);
+            enum E { A, #[cfg(test)] B, C(#[cfg(feature = "extra")] u8) }
+            #[cfg(test)]
+            mod tests { struct Gone; }
+            mod inner { #[cfg(windows)] struct Gone; }
+            #[cfg_attr(feature = "extra", cfg(any()))]
+            struct AlsoGone;
+        "#,
+        )
+        .unwrap();
+        cfg.strip_file(&mut file);
+        let stripped = quote::quote!(#file).to_string().replace(' ', "");
+        assert_eq!(
+            stripped,
+            "structKept{a:u8,#[cfg(feature=\"extra\")]b:u8}structTuple(u8);\
+             enumE{A,C(#[cfg(feature=\"extra\")]u8)}modinner{}"
+        );
+    }
+}
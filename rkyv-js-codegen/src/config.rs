@@ -0,0 +1,556 @@
+//! Declarative configuration: external types, with-wrappers and naming loaded from a TOML or JSON file.
+//!
+//! A config file produces the same [`ExternalType`] and [`WithWrapper`] registrations as
+//! [`register_external`](CodeGenerator::register_external) and [`register_with`](CodeGenerator::register_with),
+//! so the mapping table can be maintained without touching a build script.
+//! Codecs are written as template strings:
+//!
+//! - `r.vec($0)` calls the `rkyv-js` runtime (`r.niching.zero` included) with the first type argument;
+//! - `money` is a named import, resolved through the entry's `import` module or the `[imports]` table;
+//! - `16` is an integer literal and `[r.u8, $1]` an array literal.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::casing::Casing;
+use crate::error::Error;
+use crate::expr::{CodecExpr, Import};
+use crate::generator::CodeGenerator;
+use crate::registry::{ExternalType, WithWrapper};
+
+/// The syntax of a configuration source; see [`CodeGenerator::add_config_str`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// TOML, the format of `rkyv-js.toml`.
+    Toml,
+    /// JSON, with the same keys as the TOML format.
+    Json,
+}
+
+/// The members of the `r` namespace a template may reference.
+const RUNTIME_NAMES: &[&str] = &[
+    "u8",
+    "i8",
+    "u16",
+    "i16",
+    "u32",
+    "i32",
+    "u64",
+    "i64",
+    "u128",
+    "i128",
+    "usize",
+    "isize",
+    "f32",
+    "f64",
+    "bool",
+    "unit",
+    "char",
+    "string",
+    "vec",
+    "option",
+    "niched",
+    "box",
+    "rc",
+    "boxedStr",
+    "weak",
+    "array",
+    "tuple",
+    "struct",
+    "taggedEnum",
+    "stringEnum",
+    "numberEnum",
+    "union",
+    "transform",
+    "newtype",
+    "lazy",
+    "niching.zero",
+    "niching.nan",
+    "niching.bool",
+    "niching.null",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    field_casing: Option<CasingName>,
+    variant_casing: Option<CasingName>,
+    named_aliases: Option<bool>,
+    /// Export name to module, for imports shared between entries.
+    #[serde(default)]
+    imports: BTreeMap<String, String>,
+    #[serde(default)]
+    archived_names: BTreeMap<String, String>,
+    #[serde(default)]
+    types: BTreeMap<String, TypeEntry>,
+    #[serde(default)]
+    wrappers: BTreeMap<String, WrapperEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum CasingName {
+    Preserve,
+    Camel,
+    Pascal,
+    Snake,
+}
+
+impl From<CasingName> for Casing {
+    fn from(name: CasingName) -> Self {
+        match name {
+            CasingName::Preserve => Casing::Preserve,
+            CasingName::Camel => Casing::Camel,
+            CasingName::Pascal => Casing::Pascal,
+            CasingName::Snake => Casing::Snake,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct TypeEntry {
+    codec: String,
+    #[serde(default)]
+    arity: usize,
+    #[serde(default)]
+    trailing_args: bool,
+    import: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct WrapperEntry {
+    kind: WrapperKind,
+    codec: Option<String>,
+    import: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum WrapperKind {
+    Replace,
+    Map,
+    Identity,
+    Skip,
+}
+
+impl Config {
+    fn parse(source: &str, format: ConfigFormat) -> Result<Self, String> {
+        match format {
+            ConfigFormat::Toml => toml::from_str(source).map_err(|err| err.to_string()),
+            ConfigFormat::Json => serde_json::from_str(source).map_err(|err| err.to_string()),
+        }
+    }
+
+    /// Parse the template of the entry at `key`; bare names import from `module`, else from `[imports]`.
+    fn template(&self, key: &str, source: &str, module: Option<&str>) -> Result<CodecExpr, String> {
+        let resolve = |name: &str| {
+            module
+                .or_else(|| self.imports.get(name).map(String::as_str))
+                .map(|module| Import::new(module, name))
+        };
+        Template {
+            source,
+            pos: 0,
+            resolve: &resolve,
+        }
+        .parse()
+        .map_err(|message| format!("`{key}.codec`: {message} in `{source}`"))
+    }
+
+    fn external(&self, path: &str, entry: &TypeEntry) -> Result<ExternalType, String> {
+        let key = format!("types.\"{path}\"");
+        let template = self.template(&key, &entry.codec, entry.import.as_deref())?;
+        if let Some(max) = template.max_param()
+            && max >= entry.arity
+        {
+            return Err(format!(
+                "`{key}.codec` references `${max}`, but the arity is {}",
+                entry.arity
+            ));
+        }
+        let external = ExternalType::generic(entry.arity, |_| template);
+        Ok(if entry.trailing_args {
+            external.allow_trailing_args()
+        } else {
+            external
+        })
+    }
+
+    fn wrapper(&self, path: &str, entry: &WrapperEntry) -> Result<WithWrapper, String> {
+        let key = format!("wrappers.\"{path}\"");
+        let codec = match (entry.kind, &entry.codec) {
+            (WrapperKind::Identity, None) => return Ok(WithWrapper::identity()),
+            (WrapperKind::Skip, None) => return Ok(WithWrapper::skip()),
+            (WrapperKind::Identity | WrapperKind::Skip, Some(_)) => {
+                return Err(format!("`{key}` takes no `codec`"));
+            }
+            (_, None) => return Err(format!("`{key}` needs a `codec`")),
+            (_, Some(codec)) => codec,
+        };
+        let template = self.template(&key, codec, entry.import.as_deref())?;
+        match (entry.kind == WrapperKind::Map, template.max_param()) {
+            (false, None) => Ok(WithWrapper::replace(template)),
+            (true, None | Some(0)) => Ok(WithWrapper::map(|_| template)),
+            (_, Some(max)) => Err(format!(
+                "`{key}.codec` references `${max}`; a `map` wrapper only has `$0`, \
+                 the underlying codec, and a `replace` wrapper none"
+            )),
+        }
+    }
+}
+
+/// A recursive-descent parser over a codec template string.
+struct Template<'a> {
+    source: &'a str,
+    pos: usize,
+    resolve: &'a dyn Fn(&str) -> Option<Import>,
+}
+
+impl<'a> Template<'a> {
+    fn parse(mut self) -> Result<CodecExpr, String> {
+        let expr = self.expr()?;
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(expr),
+            Some(c) => Err(format!("unexpected `{c}`")),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.source[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+
+    /// A run of identifier characters, `.` included.
+    fn word(&mut self) -> &'a str {
+        let start = self.pos;
+        let rest = &self.source[start..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '.'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &self.source[start..start + len]
+    }
+
+    fn expr(&mut self) -> Result<CodecExpr, String> {
+        let mut expr = self.atom()?;
+        while self.eat('(') {
+            expr = CodecExpr::call(expr, self.list(')')?);
+        }
+        Ok(expr)
+    }
+
+    fn list(&mut self, close: char) -> Result<Vec<CodecExpr>, String> {
+        let mut items = Vec::new();
+        loop {
+            if self.eat(close) {
+                return Ok(items);
+            }
+            items.push(self.expr()?);
+            if !self.eat(',') {
+                return match self.eat(close) {
+                    true => Ok(items),
+                    false => Err(format!("expected `,` or `{close}`")),
+                };
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Result<CodecExpr, String> {
+        if self.eat('[') {
+            return Ok(CodecExpr::array(self.list(']')?));
+        }
+        self.skip_whitespace();
+        let word = self.word();
+        if word.is_empty() {
+            return Err(match self.peek() {
+                Some(c) => format!("unexpected `{c}`"),
+                None => "unexpected end".to_string(),
+            });
+        }
+        if let Some(index) = word.strip_prefix('$') {
+            return index
+                .parse()
+                .map(CodecExpr::Param)
+                .map_err(|_| format!("invalid parameter `{word}`"));
+        }
+        if word.starts_with(|c: char| c.is_ascii_digit()) {
+            return word
+                .parse()
+                .map(CodecExpr::LitInt)
+                .map_err(|_| format!("invalid integer `{word}`"));
+        }
+        if let Some(member) = word.strip_prefix("r.") {
+            return RUNTIME_NAMES
+                .iter()
+                .find(|name| **name == member)
+                .map(|name| CodecExpr::runtime(name))
+                .ok_or_else(|| format!("unknown runtime codec `{word}`"));
+        }
+        if word.contains('.') {
+            return Err(format!("`{word}` is neither a runtime codec nor an import"));
+        }
+        (self.resolve)(word).map(CodecExpr::Import).ok_or_else(|| {
+            format!("`{word}` has no import module; set `import` or add it to `[imports]`")
+        })
+    }
+}
+
+impl CodeGenerator {
+    /// Load a configuration file: `.json` files are read as JSON, anything else as TOML.
+    ///
+    /// Registrations apply to sources added afterwards, so load the config first.
+    ///
+    /// ```no_run
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// fn main() -> Result<(), rkyv_js_codegen::Error> {
+    ///     CodeGenerator::new()
+    ///         .add_config_file("rkyv-js.toml")?
+    ///         .add_crate_root("src/lib.rs")?
+    ///         .write_to_file("generated/bindings.ts")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_config_file(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        self.tracked_paths.insert(path.to_path_buf());
+        let source = fs::read_to_string(path)?;
+        let format = if path.extension().is_some_and(|ext| ext == "json") {
+            ConfigFormat::Json
+        } else {
+            ConfigFormat::Toml
+        };
+        self.apply_config(&source, format)
+            .map_err(|message| Error::Config {
+                file: Some(path.to_path_buf()),
+                message,
+            })?;
+        Ok(self)
+    }
+
+    /// Load configuration from a string.
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, ConfigFormat};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.add_config_str(r#"
+    ///     field-casing = "camel"
+    ///
+    ///     [types."my_crate::Money"]
+    ///     codec = "money"
+    ///     import = "./money.ts"
+    ///
+    ///     [types."my_crate::Tagged"]
+    ///     arity = 1
+    ///     codec = "r.tuple(r.string, $0)"
+    ///
+    ///     [wrappers."my_crate::AsCents"]
+    ///     kind = "replace"
+    ///     codec = "r.u64"
+    /// "#, ConfigFormat::Toml)?;
+    /// generator.add_source_str(r#"
+    ///     #[derive(rkyv::Archive)]
+    ///     pub struct Invoice {
+    ///         pub total_due: my_crate::Money,
+    ///         pub labelled: my_crate::Tagged<u32>,
+    ///         #[rkyv(with = my_crate::AsCents)]
+    ///         pub tip: f64,
+    ///     }
+    /// "#)?;
+    ///
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("import { money } from './money.ts';"));
+    /// assert!(code.contains("  totalDue: money,\n  labelled: r.tuple(r.string, r.u32),\n  tip: r.u64,"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn add_config_str(
+        &mut self,
+        source: &str,
+        format: ConfigFormat,
+    ) -> Result<&mut Self, Error> {
+        self.apply_config(source, format)
+            .map_err(|message| Error::Config {
+                file: None,
+                message,
+            })?;
+        Ok(self)
+    }
+
+    /// Validate every entry first, so a bad config registers nothing.
+    fn apply_config(&mut self, source: &str, format: ConfigFormat) -> Result<(), String> {
+        let config = Config::parse(source, format)?;
+        let externals = config
+            .types
+            .iter()
+            .map(|(path, entry)| Ok((path, config.external(path, entry)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let wrappers = config
+            .wrappers
+            .iter()
+            .map(|(path, entry)| Ok((path, config.wrapper(path, entry)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for (path, external) in externals {
+            self.register_external(path.clone(), external);
+        }
+        for (path, wrapper) in wrappers {
+            self.register_with(path.clone(), wrapper);
+        }
+        for (type_name, name) in &config.archived_names {
+            self.set_archived_name(type_name.clone(), name.clone());
+        }
+        if let Some(casing) = config.field_casing {
+            self.set_field_casing(casing.into());
+        }
+        if let Some(casing) = config.variant_casing {
+            self.set_variant_casing(casing.into());
+        }
+        if let Some(enabled) = config.named_aliases {
+            self.set_named_aliases(enabled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(source: &str) -> Result<String, String> {
+        let resolve = |name: &str| Some(Import::new("./codecs.ts", name));
+        let template = Template {
+            source,
+            pos: 0,
+            resolve: &resolve,
+        };
+        let args = [crate::codec::u32(), crate::codec::string()];
+        template
+            .parse()
+            .map(|expr| expr.substitute(&args).render(&BTreeMap::new()).unwrap())
+    }
+
+    #[test]
+    fn templates_parse() {
+        assert_eq!(template("r.u32").unwrap(), "r.u32");
+        assert_eq!(template("r.vec( $0 )").unwrap(), "r.vec(r.u32)");
+        assert_eq!(template("pair($1, $0)").unwrap(), "pair(r.string, r.u32)");
+        assert_eq!(template("r.array(r.u8, 16)").unwrap(), "r.array(r.u8, 16)");
+        assert_eq!(
+            template("r.niched(r.u32, r.niching.zero)").unwrap(),
+            "r.niched(r.u32, r.niching.zero)"
+        );
+        assert_eq!(template("money").unwrap(), "money");
+        assert_eq!(
+            template("wrap([r.u8, r.string,])").unwrap(),
+            "wrap([r.u8, r.string])"
+        );
+        assert_eq!(
+            template("curried(r.u8)(r.u16)").unwrap(),
+            "curried(r.u8)(r.u16)"
+        );
+    }
+
+    #[test]
+    fn malformed_templates_are_rejected() {
+        assert_eq!(
+            template("r.vector($0)").unwrap_err(),
+            "unknown runtime codec `r.vector`"
+        );
+        assert_eq!(template("r.vec($0").unwrap_err(), "expected `,` or `)`");
+        assert_eq!(template("r.u8 r.u16").unwrap_err(), "unexpected `r`");
+        assert_eq!(template("").unwrap_err(), "unexpected end");
+    }
+
+    #[test]
+    fn json_config_registers_wrappers() {
+        let mut generator = CodeGenerator::new();
+        generator
+            .add_config_str(
+                r#"{
+                    "imports": { "compressed": "./compress.ts" },
+                    "archived-names": { "Blob": "ArchivedBlobV2" },
+                    "wrappers": {
+                        "my_crate::Compressed": { "kind": "map", "codec": "compressed($0)" },
+                        "my_crate::Internal": { "kind": "skip" }
+                    }
+                }"#,
+                ConfigFormat::Json,
+            )
+            .unwrap();
+        generator
+            .add_source_str(
+                "#[derive(rkyv::Archive)]
+                 pub struct Blob {
+                     #[rkyv(with = my_crate::Compressed)] pub data: Vec<u8>,
+                     #[rkyv(with = my_crate::Internal)] pub cache: u64,
+                 }",
+            )
+            .unwrap();
+        let code = generator.generate().unwrap();
+        assert!(code.contains("import { compressed } from './compress.ts';"));
+        assert!(code.contains(
+            "export const ArchivedBlobV2 = r.struct({\n  data: compressed(r.vec(r.u8)),\n});"
+        ));
+    }
+
+    #[test]
+    fn invalid_entries_register_nothing() {
+        let mut generator = CodeGenerator::new();
+        let error = generator
+            .add_config_str(
+                r#"
+                [types."a::Good"]
+                codec = "r.u32"
+
+                [types."a::Bad"]
+                codec = "r.vec($1)"
+                arity = 1
+                "#,
+                ConfigFormat::Toml,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid config: `types.\"a::Bad\".codec` references `$1`, but the arity is 1"
+        );
+        generator
+            .add_source_str("#[derive(rkyv::Archive)] pub struct S { pub good: a::Good }")
+            .unwrap();
+        assert!(generator.generate().is_err());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = CodeGenerator::new()
+            .add_config_str(
+                "[types.\"a::B\"]\ncodec = \"r.u8\"\narty = 1\n",
+                ConfigFormat::Toml,
+            )
+            .map(|_| ())
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("unknown field `arty`"),
+            "{error}"
+        );
+    }
+}
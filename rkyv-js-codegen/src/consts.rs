@@ -0,0 +1,306 @@
+//! Integer constant evaluation for array lengths and enum discriminants.
+//!
+//! rustc evaluates `[u8; HASH_LEN]` and `A = BASE + 1` at compile time; the extractor has to do the same
+//! to emit the right layout. Covered are the expressions such declarations use in practice:
+//! integer literals, arithmetic, bitwise and shift operators, casts between integer types, `{ N }` blocks,
+//! the `MIN`/`MAX`/`BITS` constants of integer types, and paths to other constants, which the caller resolves.
+//!
+//! Each operation is typed like rustc types it: by a literal's suffix, a cast, the declared type of
+//! a referenced constant, or else the type the expression is expected to have (the `const`'s declared
+//! type, `usize` for an array length). Overflow of that type and division by zero fail the evaluation,
+//! as they fail compilation. Literals with no type to infer are evaluated at `i128` width.
+
+use syn::{BinOp, Expr, UnOp};
+
+/// A primitive integer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct IntType {
+    bits: u32,
+    signed: bool,
+}
+
+impl IntType {
+    /// `usize`, the type of array lengths.
+    pub(crate) const USIZE: Self = Self {
+        bits: 64,
+        signed: false,
+    };
+
+    /// The integer type named `name`.
+    /// `usize` and `isize` are taken as 64-bit, the width of every target rkyv-js supports.
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        let (bits, signed) = match name {
+            "u8" => (8, false),
+            "u16" => (16, false),
+            "u32" => (32, false),
+            "u64" | "usize" => (64, false),
+            "u128" => (128, false),
+            "i8" => (8, true),
+            "i16" => (16, true),
+            "i32" => (32, true),
+            "i64" | "isize" => (64, true),
+            "i128" => (128, true),
+            _ => return None,
+        };
+        Some(Self { bits, signed })
+    }
+
+    /// The integer type `ty` names, if it is a bare primitive integer.
+    pub(crate) fn of(ty: &syn::Type) -> Option<Self> {
+        let syn::Type::Path(syn::TypePath { qself: None, path }) = ty else {
+            return None;
+        };
+        Self::parse(&path.get_ident()?.to_string())
+    }
+
+    fn min(self) -> i128 {
+        if self.signed {
+            -1i128 << (self.bits - 1)
+        } else {
+            0
+        }
+    }
+
+    /// `None` for `u128::MAX`, which is out of the evaluator's range.
+    fn max(self) -> Option<i128> {
+        match (self.bits, self.signed) {
+            (_, true) => Some(i128::MAX >> (128 - self.bits)),
+            (128, false) => None,
+            (bits, false) => Some((1 << bits) - 1),
+        }
+    }
+
+    fn contains(self, value: i128) -> bool {
+        value >= self.min() && self.max().is_none_or(|max| value <= max)
+    }
+}
+
+/// An evaluated constant, with its integer type when one is known.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Value {
+    pub(crate) value: i128,
+    pub(crate) ty: Option<IntType>,
+}
+
+impl Value {
+    /// A value of no particular type, such as a constant registered from another crate.
+    pub(crate) fn untyped(value: i128) -> Self {
+        Self { value, ty: None }
+    }
+
+    /// The value typed as `ty`, if it is in that type's range.
+    fn checked(value: i128, ty: Option<IntType>) -> Option<Self> {
+        ty.is_none_or(|ty| ty.contains(value))
+            .then_some(Self { value, ty })
+    }
+}
+
+/// Evaluate `expr`, expected to be of type `expected`, resolving paths to other constants with `resolve`.
+///
+/// `None` means the expression is not a supported integer constant expression,
+/// refers to a constant `resolve` does not know, or does not compile.
+pub(crate) fn eval(
+    expr: &Expr,
+    expected: Option<IntType>,
+    resolve: &mut dyn FnMut(&syn::Path) -> Option<Value>,
+) -> Option<Value> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit_int),
+            ..
+        }) => {
+            let value: i128 = lit_int.base10_parse().ok()?;
+            match lit_int.suffix() {
+                "" => Value::checked(value, expected),
+                suffix => Value::checked(value, Some(IntType::parse(suffix)?)),
+            }
+        }
+        Expr::Paren(syn::ExprParen { expr, .. }) | Expr::Group(syn::ExprGroup { expr, .. }) => {
+            eval(expr, expected, resolve)
+        }
+        Expr::Block(syn::ExprBlock { block, .. }) => match &block.stmts[..] {
+            [syn::Stmt::Expr(expr, None)] => eval(expr, expected, resolve),
+            _ => None,
+        },
+        Expr::Unary(syn::ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => {
+            let operand = eval(expr, expected, resolve)?;
+            let ty = operand.ty.or(expected);
+            if ty.is_some_and(|ty| !ty.signed) {
+                return None;
+            }
+            Value::checked(operand.value.checked_neg()?, ty)
+        }
+        Expr::Binary(syn::ExprBinary {
+            left, op, right, ..
+        }) => {
+            let left = eval(left, expected, resolve)?;
+            // A shift amount does not take the type of the shifted value.
+            let shift = matches!(op, BinOp::Shl(_) | BinOp::Shr(_));
+            let right = eval(
+                right,
+                if shift { None } else { left.ty.or(expected) },
+                resolve,
+            )?;
+            if shift {
+                // Shifting by the width or more overflows; bits shifted out are dropped.
+                let ty = left.ty.or(expected);
+                if u32::try_from(right.value).ok()? >= ty.map_or(128, |ty| ty.bits) {
+                    return None;
+                }
+                let value = binary(left.value, op, right.value)?;
+                return Some(Value {
+                    value: ty.map_or(Some(value), |ty| cast(value, ty))?,
+                    ty,
+                });
+            }
+            let ty = left.ty.or(right.ty).or(expected);
+            Value::checked(binary(left.value, op, right.value)?, ty)
+        }
+        Expr::Cast(syn::ExprCast { expr, ty, .. }) => {
+            let ty = IntType::of(ty)?;
+            Some(Value {
+                value: cast(eval(expr, None, resolve)?.value, ty)?,
+                ty: Some(ty),
+            })
+        }
+        Expr::Path(syn::ExprPath {
+            qself: None, path, ..
+        }) => integer_const(path).or_else(|| resolve(path)),
+        _ => None,
+    }
+}
+
+fn binary(left: i128, op: &BinOp, right: i128) -> Option<i128> {
+    match op {
+        BinOp::Add(_) => left.checked_add(right),
+        BinOp::Sub(_) => left.checked_sub(right),
+        BinOp::Mul(_) => left.checked_mul(right),
+        BinOp::Div(_) => left.checked_div(right),
+        BinOp::Rem(_) => left.checked_rem(right),
+        BinOp::BitAnd(_) => Some(left & right),
+        BinOp::BitOr(_) => Some(left | right),
+        BinOp::BitXor(_) => Some(left ^ right),
+        BinOp::Shl(_) => left.checked_shl(u32::try_from(right).ok()?),
+        BinOp::Shr(_) => left.checked_shr(u32::try_from(right).ok()?),
+        _ => None,
+    }
+}
+
+/// `value as ty`: wraps into the target type like Rust's integer casts.
+fn cast(value: i128, ty: IntType) -> Option<i128> {
+    let IntType { bits, signed } = ty;
+    if bits == 128 {
+        // `u128` values above `i128::MAX` are out of the evaluator's range.
+        return (signed || value >= 0).then_some(value);
+    }
+    let wrapped = value.rem_euclid(1 << bits);
+    Some(if signed && wrapped >= 1 << (bits - 1) {
+        wrapped - (1 << bits)
+    } else {
+        wrapped
+    })
+}
+
+/// `u8::MAX`, `i32::MIN`, `u64::BITS`, ...
+fn integer_const(path: &syn::Path) -> Option<Value> {
+    let [ty, name] = &path.segments.iter().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let ty = IntType::parse(&ty.ident.to_string())?;
+    let (value, ty) = match name.ident.to_string().as_str() {
+        "BITS" => (ty.bits.into(), IntType::parse("u32")?),
+        "MIN" => (ty.min(), ty),
+        "MAX" => (ty.max()?, ty),
+        _ => return None,
+    };
+    Some(Value {
+        value,
+        ty: Some(ty),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_as(source: &str, expected: Option<&str>) -> Option<i128> {
+        let expr: Expr = syn::parse_str(source).unwrap();
+        let expected = expected.map(|ty| IntType::parse(ty).unwrap());
+        let value = eval(
+            &expr,
+            expected,
+            &mut |path| match path.get_ident()?.to_string().as_str() {
+                "HASH_LEN" => Some(Value::untyped(32)),
+                "NEG" => Some(Value::untyped(-4)),
+                "SMALL" => Some(Value {
+                    value: 200,
+                    ty: IntType::parse("u8"),
+                }),
+                _ => None,
+            },
+        );
+        value.map(|value| value.value)
+    }
+
+    fn eval_str(source: &str) -> Option<i128> {
+        eval_as(source, None)
+    }
+
+    #[test]
+    fn evaluates_integer_expressions() {
+        assert_eq!(eval_str("32"), Some(32));
+        assert_eq!(eval_str("0x20_u8"), Some(32));
+        assert_eq!(eval_str("3 * 4"), Some(12));
+        assert_eq!(eval_str("(1 << 4) | 1"), Some(17));
+        assert_eq!(eval_str("HASH_LEN / 2 + 1"), Some(17));
+        assert_eq!(eval_str("{ HASH_LEN * 2 }"), Some(64));
+        assert_eq!(eval_str("-NEG % 3"), Some(1));
+        assert_eq!(eval_str("HASH_LEN as u8 ^ 0xff"), Some(223));
+    }
+
+    #[test]
+    fn casts_wrap_like_rust() {
+        assert_eq!(eval_str("300 as u8"), Some(44));
+        assert_eq!(eval_str("255 as i8"), Some(-1));
+        assert_eq!(eval_str("-1 as u16"), Some(65535));
+        assert_eq!(eval_str("-1 as i64"), Some(-1));
+    }
+
+    #[test]
+    fn integer_type_constants() {
+        assert_eq!(eval_str("u8::MAX"), Some(255));
+        assert_eq!(eval_str("i16::MIN"), Some(-32768));
+        assert_eq!(eval_str("i64::MAX"), Some(i64::MAX.into()));
+        assert_eq!(eval_str("u32::BITS / 8"), Some(4));
+    }
+
+    #[test]
+    fn unsupported_or_failing_expressions() {
+        assert_eq!(eval_str("UNKNOWN"), None);
+        assert_eq!(eval_str("1 / 0"), None);
+        assert_eq!(eval_str("1 << 200"), None);
+        assert_eq!(eval_str("size_of::<u64>()"), None);
+        assert_eq!(eval_str("1.5 as usize"), None);
+        assert_eq!(eval_str("!0"), None);
+    }
+
+    #[test]
+    fn overflow_is_checked_against_the_operand_type() {
+        assert_eq!(eval_str("SMALL + 100"), None);
+        assert_eq!(eval_str("(SMALL + 100) as usize"), None);
+        assert_eq!(eval_str("SMALL as usize + 100"), Some(300));
+        assert_eq!(eval_str("250u8 + 10"), None);
+        assert_eq!(eval_str("u8::MAX + 1"), None);
+        assert_eq!(eval_str("1u8 << 9"), None);
+        assert_eq!(eval_str("0xf0u8 << 4"), Some(0));
+        assert_eq!(eval_str("-SMALL"), None);
+        assert_eq!(eval_as("200 + 100", Some("u8")), None);
+        assert_eq!(eval_as("200 + 100", Some("u16")), Some(300));
+        assert_eq!(eval_as("-1", Some("usize")), None);
+        assert_eq!(eval_as("(300 as u8) as usize", Some("usize")), Some(44));
+    }
+}
@@ -20,8 +20,24 @@ pub enum Error {
         /// The underlying parse error.
         source: syn::Error,
     },
+    /// A configuration file (or string) failed to parse or names an invalid codec.
+    Config {
+        /// The file that failed to load; `None` for
+        /// [`add_config_str`](crate::CodeGenerator::add_config_str).
+        file: Option<PathBuf>,
+        /// What is wrong with it.
+        message: String,
+    },
     /// One or more code-generation diagnostics, aggregated.
     Codegen(Vec<Diagnostic>),
+    /// Bindings on disk differ from the generated ones; see
+    /// [`check_file`](crate::CodeGenerator::check_file).
+    Stale {
+        /// The checked file, or directory for [`check_dir`](crate::CodeGenerator::check_dir).
+        path: PathBuf,
+        /// A unified diff from the files on disk to the generated bindings.
+        diff: String,
+    },
 }
 
 impl fmt::Display for Error {
@@ -46,6 +62,10 @@ impl fmt::Display for Error {
                     ),
                 }
             }
+            Error::Config { file, message } => match file {
+                Some(path) => write!(f, "invalid config {}: {message}", path.display()),
+                None => write!(f, "invalid config: {message}"),
+            },
             Error::Codegen(diagnostics) => {
                 writeln!(
                     f,
@@ -58,6 +78,9 @@ impl fmt::Display for Error {
                 }
                 Ok(())
             }
+            Error::Stale { path, diff } => {
+                write!(f, "{} is out of date; regenerate it:\n{diff}", path.display())
+            }
         }
     }
 }
@@ -67,7 +90,7 @@ impl std::error::Error for Error {
         match self {
             Error::Io(err) => Some(err),
             Error::Parse { source, .. } => Some(source),
-            Error::Codegen(_) => None,
+            Error::Config { .. } | Error::Codegen(_) | Error::Stale { .. } => None,
         }
     }
 }
@@ -141,12 +164,20 @@ pub enum DiagnosticKind {
         /// The unresolved wrapper path.
         wrapper_path: String,
     },
-    /// A registered generic type instantiated with the wrong number of type
-    /// arguments.
+    /// A built-in `#[rkyv(with = ...)]` wrapper applied to a field type it cannot reshape
+    /// (e.g. `Map<AsBox>` on something other than an `Option`, `Vec` or array).
+    UnsupportedWrapperTarget {
+        /// The resolved wrapper path.
+        wrapper_path: String,
+        /// The field type, as written.
+        rust_type: String,
+    },
+    /// A registered or generated generic type instantiated with the wrong
+    /// number of type arguments.
     GenericArity {
-        /// The registered path.
+        /// The registered path, or the Rust name of a generated type.
         rust_path: String,
-        /// The arity the registration expects.
+        /// The arity the registration or type declaration expects.
         expected: usize,
         /// The number of type arguments found at the use site.
         found: usize,
@@ -163,6 +194,29 @@ pub enum DiagnosticKind {
         /// The type name the rename targeted.
         type_name: String,
     },
+    /// A [`set_unit_enum_style_for`](crate::CodeGenerator::set_unit_enum_style_for)
+    /// target that is not a non-generic enum whose variants all carry no data.
+    UnitEnumStyleTarget {
+        /// The type name the style targeted.
+        type_name: String,
+    },
+    /// A [`set_roots`](crate::CodeGenerator::set_roots) root that was never added.
+    UnknownRoot {
+        /// The root type name.
+        type_name: String,
+    },
+    /// A type excluded by the allow/deny paths that an emitted type references.
+    ExcludedType {
+        /// The excluded type name.
+        name: String,
+    },
+    /// An output module of [`generate_modules`](crate::CodeGenerator::generate_modules)
+    /// named like the `index` barrel it would overwrite, or a Rust module `bindings`
+    /// that would merge into the crate root's `bindings` module.
+    ReservedModuleName {
+        /// The reserved module name.
+        module: String,
+    },
     /// The same type name added more than once.
     DuplicateType {
         /// The duplicated name.
@@ -180,8 +234,34 @@ pub enum DiagnosticKind {
         /// The field type, printed as Rust source.
         rust_type: String,
     },
-    /// Two or more names within one type collapse to the same identifier
-    /// under the configured [`Casing`](crate::Casing).
+    /// An array length that does not evaluate to a length: not an integer constant expression,
+    /// or one naming a constant that no added source declares and no
+    /// [`register_const`](crate::CodeGenerator::register_const) call registers.
+    UnsupportedArrayLength {
+        /// The length expression, printed as Rust source.
+        expr: String,
+    },
+    /// An explicit enum discriminant that is not an integer constant expression.
+    UnsupportedDiscriminant {
+        /// The discriminant expression, printed as Rust source.
+        expr: String,
+    },
+    /// An enum discriminant outside `0..=255`: rkyv archives every enum tag as a `u8`,
+    /// whatever the Rust `#[repr]`.
+    DiscriminantOutOfRange {
+        /// The explicit or implied discriminant value, in decimal.
+        value: String,
+    },
+    /// Two variants of one enum sharing a discriminant.
+    DuplicateDiscriminant {
+        /// The shared tag value.
+        value: u8,
+        /// The variants using it, in declaration order.
+        variants: Vec<String>,
+    },
+    /// Two or more names collapse to the same emitted identifier: fields or
+    /// variants of one type under the configured [`Casing`](crate::Casing),
+    /// or types from different modules sharing one exported name.
     NameCollision {
         /// The identifier every one of `originals` converts to.
         emitted: String,
@@ -212,6 +292,13 @@ impl fmt::Display for DiagnosticKind {
                 "unknown `#[rkyv(with = ...)]` wrapper `{wrapper_path}`; register it with \
                  `register_with(\"{wrapper_path}\", ...)`"
             ),
+            DiagnosticKind::UnsupportedWrapperTarget {
+                wrapper_path,
+                rust_type,
+            } => write!(
+                f,
+                "`#[rkyv(with = {wrapper_path})]` cannot be applied to `{rust_type}`"
+            ),
             DiagnosticKind::GenericArity {
                 rust_path,
                 expected,
@@ -231,6 +318,28 @@ impl fmt::Display for DiagnosticKind {
                 "`set_archived_name` targets `{type_name}`, but no type with that name was \
                  added to the generator"
             ),
+            DiagnosticKind::UnitEnumStyleTarget { type_name } => write!(
+                f,
+                "`set_unit_enum_style_for` targets `{type_name}`, which is not a field-less enum \
+                 added to the generator"
+            ),
+            DiagnosticKind::UnknownRoot { type_name } => {
+                write!(f, "root type `{type_name}` was never added to the generator")
+            }
+            DiagnosticKind::ExcludedType { name } => write!(
+                f,
+                "type `{name}` is excluded by `allow_path`/`deny_path` but still referenced"
+            ),
+            DiagnosticKind::ReservedModuleName { module } if module == "bindings" => write!(
+                f,
+                "output module `bindings` is reserved for the crate root; assign the types \
+                 of Rust module `bindings` elsewhere with `set_output_module`"
+            ),
+            DiagnosticKind::ReservedModuleName { module } => write!(
+                f,
+                "output module `{module}` is reserved for the barrel; assign its types \
+                 elsewhere with `set_output_module`, or disable the barrel with `set_barrel(false)`"
+            ),
             DiagnosticKind::DuplicateType { name } => {
                 write!(f, "type `{name}` is defined more than once")
             }
@@ -244,10 +353,34 @@ impl fmt::Display for DiagnosticKind {
                 "unsupported field type `{rust_type}`; only types mappable to rkyv-js \
                  codecs are supported"
             ),
+            DiagnosticKind::UnsupportedArrayLength { expr } => write!(
+                f,
+                "cannot evaluate array length `{expr}`; lengths must be integer constant \
+                 expressions over literals and `const` items of the added sources \
+                 (register others with `register_const`)"
+            ),
+            DiagnosticKind::UnsupportedDiscriminant { expr } => write!(
+                f,
+                "unsupported enum discriminant `{expr}`; only integer constant expressions \
+                 are supported"
+            ),
+            DiagnosticKind::DiscriminantOutOfRange { value } => write!(
+                f,
+                "enum discriminant {value} does not fit rkyv's `u8` archived tag"
+            ),
+            DiagnosticKind::DuplicateDiscriminant { value, variants } => write!(
+                f,
+                "enum discriminant {value} is used by {}",
+                variants
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+            ),
             DiagnosticKind::NameCollision { emitted, originals } => write!(
                 f,
-                "{} collapse to `{emitted}` under the configured casing; \
-                 a duplicate object key would silently drop one of them",
+                "{} collapse to `{emitted}` once emitted; \
+                 a duplicate name would silently drop one of them",
                 originals
                     .iter()
                     .map(|name| format!("`{name}`"))
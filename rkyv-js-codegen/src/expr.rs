@@ -6,7 +6,9 @@
 //! TypeScript source.
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
 
+use crate::casing::Casing;
 use crate::error::DiagnosticKind;
 
 /// A named import contributed by a [`CodecExpr::Import`] node.
@@ -53,7 +55,13 @@ pub enum CodecExpr {
     Import(Import),
     /// A reference to a generated type by its *Rust* name. Resolved to the
     /// archived (exported) name at `generate()` time.
+    ///
+    /// A generic type is instantiated by calling its reference:
+    /// `Call(TypeRef("Page"), [TypeRef("User")])` renders as `ArchivedPage(ArchivedUser)`.
     TypeRef(String),
+    /// A generic parameter of the enclosing type by its *Rust* name;
+    /// renders as the codec argument of the emitted factory function (`T` -> `t`).
+    TypeParam(String),
     /// A call expression: `callee(args...)`.
     Call(Box<CodecExpr>, Vec<CodecExpr>),
     /// An object literal `{ k: v, ... }` — used for enum struct-variant
@@ -87,6 +95,11 @@ impl CodecExpr {
         CodecExpr::TypeRef(name.into())
     }
 
+    /// A generic parameter of the enclosing type by its Rust name.
+    pub fn type_param(name: impl Into<String>) -> Self {
+        CodecExpr::TypeParam(name.into())
+    }
+
     /// A call expression `callee(args...)`.
     pub fn call(callee: CodecExpr, args: impl IntoIterator<Item = CodecExpr>) -> Self {
         CodecExpr::Call(Box::new(callee), args.into_iter().collect())
@@ -133,6 +146,56 @@ impl CodecExpr {
         }
     }
 
+    /// Replace every [`CodecExpr::TypeParam`] named in `params` with the argument at the same position.
+    pub(crate) fn substitute_type_params(
+        &self,
+        params: &[String],
+        args: &[CodecExpr],
+    ) -> CodecExpr {
+        let Ok(expr) = self.try_rewrite(&mut |node| {
+            Ok::<_, Infallible>(match node {
+                CodecExpr::TypeParam(name) => params
+                    .iter()
+                    .position(|param| param == name)
+                    .and_then(|i| args.get(i).cloned()),
+                _ => None,
+            })
+        });
+        expr
+    }
+
+    /// Rebuild the tree top-down: a node for which `f` returns a replacement is swapped out
+    /// whole, any other node keeps its shape with rewritten children.
+    pub(crate) fn try_rewrite<E>(
+        &self,
+        f: &mut impl FnMut(&CodecExpr) -> Result<Option<CodecExpr>, E>,
+    ) -> Result<CodecExpr, E> {
+        if let Some(replacement) = f(self)? {
+            return Ok(replacement);
+        }
+        Ok(match self {
+            CodecExpr::Call(callee, args) => CodecExpr::Call(
+                Box::new(callee.try_rewrite(f)?),
+                args.iter()
+                    .map(|arg| arg.try_rewrite(f))
+                    .collect::<Result<_, _>>()?,
+            ),
+            CodecExpr::Object(entries) => CodecExpr::Object(
+                entries
+                    .iter()
+                    .map(|(k, v)| Ok((k.clone(), v.try_rewrite(f)?)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            CodecExpr::Array(elements) => CodecExpr::Array(
+                elements
+                    .iter()
+                    .map(|element| element.try_rewrite(f))
+                    .collect::<Result<_, _>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+
     /// Walk the expression tree in pre-order, calling `f` on every node.
     ///
     /// [`CodecExpr::Raw`] contents are never inspected (the node itself is still visited).
@@ -188,6 +251,36 @@ impl CodecExpr {
         });
     }
 
+    /// Collect every reference to a generated type with the number of type
+    /// arguments it is instantiated with: a bare [`CodecExpr::TypeRef`] counts as 0,
+    /// a call whose callee is a `TypeRef` as its argument count.
+    pub(crate) fn collect_type_ref_arities(&self, into: &mut Vec<(String, usize)>) {
+        match self {
+            CodecExpr::TypeRef(name) => into.push((name.clone(), 0)),
+            CodecExpr::Call(callee, args) => {
+                if let CodecExpr::TypeRef(name) = callee.as_ref() {
+                    into.push((name.clone(), args.len()));
+                } else {
+                    callee.collect_type_ref_arities(into);
+                }
+                for arg in args {
+                    arg.collect_type_ref_arities(into);
+                }
+            }
+            CodecExpr::Object(entries) => {
+                for (_, v) in entries {
+                    v.collect_type_ref_arities(into);
+                }
+            }
+            CodecExpr::Array(elements) => {
+                for element in elements {
+                    element.collect_type_ref_arities(into);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Render the expression to TypeScript source.
     ///
     /// `archived_names` maps Rust type names to their exported archived names.
@@ -227,6 +320,7 @@ impl CodecExpr {
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(format!("[{}]", elements.join(", ")))
             }
+            CodecExpr::TypeParam(name) => Ok(param_ident(name)),
             CodecExpr::LitInt(n) => Ok(n.to_string()),
             CodecExpr::Param(i) => panic!(
                 "CodecExpr::Param({i}) escaped template instantiation; registry templates \
@@ -235,6 +329,174 @@ impl CodecExpr {
             CodecExpr::Raw(ts) => Ok(ts.clone()),
         }
     }
+
+    /// The TypeScript type of the values this codec expression decodes to.
+    ///
+    /// `type_names` maps Rust type names to their exported type names, and
+    /// `codec_type` is the codec type of the target direction (`r.Codec`).
+    /// Codecs the generator knows nothing about are typed through `r.Infer`
+    /// of their import; [`CodecExpr::Raw`] is opaque and types as `unknown`.
+    pub(crate) fn ts_type(&self, type_names: &BTreeMap<String, String>, codec_type: &str) -> String {
+        let ts = |expr: &CodecExpr| expr.ts_type(type_names, codec_type);
+        match self {
+            CodecExpr::Runtime(name) => match *name {
+                "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "usize" | "isize" | "f32" | "f64" => {
+                    "number".to_string()
+                }
+                "u64" | "i64" | "u128" | "i128" => "bigint".to_string(),
+                "bool" => "boolean".to_string(),
+                "char" | "string" | "boxedStr" => "string".to_string(),
+                "unit" => "null".to_string(),
+                _ => format!("r.Infer<typeof r.{name}>"),
+            },
+            CodecExpr::Import(import) => match (import.module.as_str(), import.export.as_str()) {
+                ("rkyv-js/lib/uuid", "uuid") => "string".to_string(),
+                ("rkyv-js/lib/bytes", "bytes") => "Uint8Array".to_string(),
+                ("rkyv-js/lib/duration", "unixTime") => "Date".to_string(),
+                ("rkyv-js/lib/net", "ipv4Addr" | "ipv6Addr" | "ipAddr")
+                | ("rkyv-js/lib/ffi", "cString") => "string".to_string(),
+                ("rkyv-js/lib/net", "ipv4AddrBytes" | "ipv6AddrBytes" | "ipAddrBytes")
+                | ("rkyv-js/lib/ffi", "cStringBytes") => "Uint8Array".to_string(),
+                (_, export) => format!("r.Infer<typeof {export}>"),
+            },
+            CodecExpr::TypeRef(name) => type_names
+                .get(name)
+                .cloned()
+                .expect("type references are validated before emission"),
+            CodecExpr::TypeParam(name) => name.clone(),
+            CodecExpr::Call(callee, args) => match (callee.as_ref(), args.as_slice()) {
+                (CodecExpr::Runtime("vec" | "array"), [element, ..]) => array_type(&ts(element)),
+                (CodecExpr::Runtime("option" | "niched" | "weak"), [inner, ..]) => {
+                    format!("{} | null", ts(inner))
+                }
+                (CodecExpr::Runtime("box" | "rc"), [inner]) => ts(inner),
+                (CodecExpr::Runtime("tuple"), elements) => {
+                    let elements: Vec<String> = elements.iter().map(ts).collect();
+                    format!("[{}]", elements.join(", "))
+                }
+                (CodecExpr::Runtime("struct"), [fields @ CodecExpr::Object(_)]) => ts(fields),
+                (CodecExpr::Runtime("taggedEnum"), [CodecExpr::Object(variants), ..]) => {
+                    let variants: Vec<String> = variants
+                        .iter()
+                        .map(|(tag, value)| {
+                            let value = match value {
+                                CodecExpr::Raw(raw) if raw == "null" => "null".to_string(),
+                                value => ts(value),
+                            };
+                            format!("{{ tag: '{tag}'; value: {value} }}")
+                        })
+                        .collect();
+                    if variants.is_empty() {
+                        "never".to_string()
+                    } else {
+                        variants.join(" | ")
+                    }
+                }
+                (CodecExpr::Import(import), [key, value])
+                    if import.module.starts_with("rkyv-js/lib/")
+                        && import.export.ends_with("Map") =>
+                {
+                    format!("Map<{}, {}>", ts(key), ts(value))
+                }
+                (CodecExpr::Import(import), [element])
+                    if import.module.starts_with("rkyv-js/lib/")
+                        && import.export.ends_with("Set") =>
+                {
+                    format!("Set<{}>", ts(element))
+                }
+                (CodecExpr::TypeRef(_), args) => {
+                    let args: Vec<String> = args.iter().map(ts).collect();
+                    format!("{}<{}>", ts(callee), args.join(", "))
+                }
+                // Instantiate a foreign factory with codecs of the argument types.
+                (CodecExpr::Import(import), args)
+                    if args.iter().all(|arg| !matches!(arg, CodecExpr::LitInt(_) | CodecExpr::Raw(_))) =>
+                {
+                    let args: Vec<String> = args
+                        .iter()
+                        .map(|arg| format!("{codec_type}<{}>", ts(arg)))
+                        .collect();
+                    format!(
+                        "r.Infer<ReturnType<typeof {}<{}>>>",
+                        import.export,
+                        args.join(", ")
+                    )
+                }
+                _ => "unknown".to_string(),
+            },
+            CodecExpr::Object(fields) => {
+                if fields.is_empty() {
+                    return "{}".to_string();
+                }
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, expr)| format!("{name}: {}", ts(expr)))
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            CodecExpr::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(ts).collect();
+                format!("[{}]", elements.join(", "))
+            }
+            CodecExpr::LitInt(_) | CodecExpr::Param(_) | CodecExpr::Raw(_) => "unknown".to_string(),
+        }
+    }
+}
+
+/// `T[]`, parenthesizing a union element.
+fn array_type(element: &str) -> String {
+    if element.contains(" | ") {
+        format!("({element})[]")
+    } else {
+        format!("{element}[]")
+    }
+}
+
+/// The JavaScript identifier a generic parameter is bound to in an emitted
+/// factory function: the parameter name in camelCase (`T` -> `t`, `Item` -> `item`).
+///
+/// A name that would shadow the `r` namespace import or collide with a
+/// reserved word gets a trailing `_` (`R` -> `r_`, `In` -> `in_`).
+pub(crate) fn param_ident(name: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "r", "break", "case", "catch", "class", "const", "continue", "debugger", "default",
+        "delete", "do", "else", "enum", "export", "extends", "false", "finally", "for",
+        "function", "if", "import", "in", "instanceof", "let", "new", "null", "return",
+        "static", "super", "switch", "this", "throw", "true", "try", "typeof", "var", "void",
+        "while", "with", "yield",
+    ];
+    let ident = Casing::Camel.apply(name);
+    if RESERVED.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+/// How a niched option stores `None` inside its value's own bytes;
+/// mirrors rkyv's `Niching` implementors and the `r.niching` strategies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Niching {
+    /// All-zero bytes (`NonZero*` integers).
+    Zero,
+    /// A NaN (`f32`/`f64`).
+    NaN,
+    /// A byte above `1` (`bool`).
+    Bool,
+    /// The invalid relative pointer (`Box<T>`).
+    Null,
+}
+
+impl Niching {
+    /// The `r.niching` member implementing this strategy.
+    pub fn runtime_name(self) -> &'static str {
+        match self {
+            Niching::Zero => "niching.zero",
+            Niching::NaN => "niching.nan",
+            Niching::Bool => "niching.bool",
+            Niching::Null => "niching.null",
+        }
+    }
 }
 
 /// Builders mirroring the `rkyv-js` runtime combinators.
@@ -249,7 +511,7 @@ impl CodecExpr {
 /// assert_eq!(expr.render(&BTreeMap::new()).unwrap(), "r.vec(r.option(r.u32))");
 /// ```
 pub mod codec {
-    use super::CodecExpr;
+    use super::{CodecExpr, Niching};
 
     /// `r.u8`
     pub fn u8() -> CodecExpr {
@@ -283,6 +545,22 @@ pub mod codec {
     pub fn i64() -> CodecExpr {
         CodecExpr::runtime("i64")
     }
+    /// `r.u128`
+    pub fn u128() -> CodecExpr {
+        CodecExpr::runtime("u128")
+    }
+    /// `r.i128`
+    pub fn i128() -> CodecExpr {
+        CodecExpr::runtime("i128")
+    }
+    /// `r.usize`, sized by the format's pointer width
+    pub fn usize() -> CodecExpr {
+        CodecExpr::runtime("usize")
+    }
+    /// `r.isize`, sized by the format's pointer width
+    pub fn isize() -> CodecExpr {
+        CodecExpr::runtime("isize")
+    }
     /// `r.f32`
     pub fn f32() -> CodecExpr {
         CodecExpr::runtime("f32")
@@ -315,6 +593,13 @@ pub mod codec {
     pub fn option(inner: CodecExpr) -> CodecExpr {
         CodecExpr::call(CodecExpr::runtime("option"), [inner])
     }
+    /// `r.niched(inner, r.niching.<niching>)` - an `Option` without a tag byte.
+    pub fn niched(inner: CodecExpr, niching: Niching) -> CodecExpr {
+        CodecExpr::call(
+            CodecExpr::runtime("niched"),
+            [inner, CodecExpr::runtime(niching.runtime_name())],
+        )
+    }
     /// `r.box(inner)`
     pub fn boxed(inner: CodecExpr) -> CodecExpr {
         CodecExpr::call(CodecExpr::runtime("box"), [inner])
@@ -323,6 +608,10 @@ pub mod codec {
     pub fn rc(inner: CodecExpr) -> CodecExpr {
         CodecExpr::call(CodecExpr::runtime("rc"), [inner])
     }
+    /// `r.boxedStr` (`Box<str>`, `Rc<str>`, `Arc<str>`)
+    pub fn boxed_str() -> CodecExpr {
+        CodecExpr::runtime("boxedStr")
+    }
     /// `r.weak(inner)`
     pub fn weak(inner: CodecExpr) -> CodecExpr {
         CodecExpr::call(CodecExpr::runtime("weak"), [inner])
@@ -345,6 +634,17 @@ pub mod codec {
     pub fn named(rust_name: impl Into<String>) -> CodecExpr {
         CodecExpr::type_ref(rust_name)
     }
+    /// An instantiation of a generic generated type: `Archived{name}(arg0, ...)`.
+    pub fn instantiate(
+        rust_name: impl Into<String>,
+        args: impl IntoIterator<Item = CodecExpr>,
+    ) -> CodecExpr {
+        CodecExpr::call(CodecExpr::type_ref(rust_name), args)
+    }
+    /// A generic parameter of the enclosing type, bound as the factory argument.
+    pub fn param(rust_name: impl Into<String>) -> CodecExpr {
+        CodecExpr::type_param(rust_name)
+    }
 }
 
 /// Generate the import block for a set of expressions.
@@ -420,6 +720,27 @@ mod tests {
         assert_eq!(render(&codec::string()), "r.string");
     }
 
+    #[test]
+    fn ts_types_follow_the_runtime_value_types() {
+        let ts = |expr: &CodecExpr| expr.ts_type(&BTreeMap::new(), "r.Codec");
+        assert_eq!(ts(&codec::array(codec::option(codec::u64()), 2)), "(bigint | null)[]");
+        assert_eq!(
+            ts(&CodecExpr::call(
+                CodecExpr::import_from("rkyv-js/lib/hashmap", "hashMap"),
+                [codec::string(), codec::tuple([codec::char_(), codec::unit()])],
+            )),
+            "Map<string, [string, null]>"
+        );
+        assert_eq!(
+            ts(&CodecExpr::call(
+                CodecExpr::import_from("my-codecs", "sorted"),
+                [codec::i32()],
+            )),
+            "r.Infer<ReturnType<typeof sorted<r.Codec<number>>>>"
+        );
+        assert_eq!(ts(&CodecExpr::raw("custom()")), "unknown");
+    }
+
     #[test]
     fn renders_containers() {
         assert_eq!(render(&codec::vec(codec::u32())), "r.vec(r.u32)");
@@ -573,6 +894,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn renders_generic_instantiation_and_params() {
+        let mut names = BTreeMap::new();
+        names.insert("Page".to_string(), "ArchivedPage".to_string());
+        names.insert("User".to_string(), "ArchivedUser".to_string());
+        let expr = codec::instantiate("Page", [codec::named("User")]);
+        assert_eq!(expr.render(&names).unwrap(), "ArchivedPage(ArchivedUser)");
+        assert_eq!(render(&codec::vec(codec::param("T"))), "r.vec(t)");
+        assert_eq!(render(&codec::param("Item")), "item");
+        // Never shadow the namespace import or a reserved word.
+        assert_eq!(render(&codec::param("R")), "r_");
+        assert_eq!(render(&codec::param("In")), "in_");
+    }
+
+    #[test]
+    fn type_ref_arities_count_call_args() {
+        let expr = codec::vec(codec::instantiate(
+            "Pair",
+            [codec::named("A"), codec::instantiate("Page", [codec::u8()])],
+        ));
+        let mut arities = Vec::new();
+        expr.collect_type_ref_arities(&mut arities);
+        assert_eq!(
+            arities,
+            vec![
+                ("Pair".to_string(), 2),
+                ("A".to_string(), 0),
+                ("Page".to_string(), 1),
+            ]
+        );
+    }
+
     #[test]
     #[should_panic(expected = "escaped template instantiation")]
     fn rendering_a_param_panics() {
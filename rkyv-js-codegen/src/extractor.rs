@@ -1,4 +1,4 @@
-//! Rust source extraction: parses files with `syn` and adds every type
+//! Rust source extraction: parses files (or whole crates, through their `mod` tree) with `syn` and adds every type
 //! marked with a recognized derive (default `rkyv::Archive`) to the [`CodeGenerator`].
 //!
 //! ## Marker detection
@@ -10,14 +10,40 @@
 //! - it is a bare ident and a glob import (`use rkyv::*`) brings a marker path into scope, or
 //! - it matches a path registered via [`add_marker_path`](CodeGenerator::add_marker_path).
 //!
+//! `#[cfg_attr(..., derive(...))]` counts once expanded: sources are stripped against the generator's
+//! [`CfgSet`] before extraction, so inactive items, fields, variants and attributes are never seen.
+//!
 //! ## Use-item analysis
 //!
 //! `use` trees are flattened into a local-name → fully-qualified-path map:
 //!
 //! - `use std::collections::BTreeMap` maps `BTreeMap` to `std::collections::BTreeMap`
 //! - `use rkyv::Archive as Rkyv` maps `Rkyv` to `rkyv::Archive`
-//! - `type HashMap<K, V> = std::collections::HashMap<K, V, S>` maps `HashMap` to `std::collections::HashMap` 
-//!   (the alias *path* only; extra RHS arguments surface as trailing type arguments at the use site)
+//! - `type Hasher = std::hash::BuildHasherDefault<H>` maps `Hasher` to `std::hash::BuildHasherDefault`
+//!   (the alias *path* only, for with-wrapper and registry lookups by path)
+//!
+//! ## Type aliases
+//!
+//! Every `type` alias is also recorded with its generic parameters and expanded where it is used, in codec form:
+//! `type Pair<T> = (T, T)` makes a `Pair<u32>` field `r.tuple(r.u32, r.u32)`. Aliases resolve across modules and
+//! sources like any other item; a reference to an alias added by a later source is expanded once that source is added.
+//! With [`set_named_aliases`](CodeGenerator::set_named_aliases), referenced aliases are emitted as types instead.
+//!
+//! ## Constants
+//!
+//! `const` items, free or associated with a type through an `impl` block, are collected from each batch of
+//! sources and evaluated with the generator's known constants, so `[u8; HASH_LEN]`, `[f32; 3 * 4]`,
+//! `[u8; Self::SIZE]` and `A = BASE + 1` resolve as rustc would. Paths resolve through `use` imports like types;
+//! constants of other crates are registered with [`register_const`](CodeGenerator::register_const).
+//! A constant is only visible to sources added with or after its own.
+//!
+//! ## With-wrappers
+//!
+//! Registered wrappers transform the field's codec. rkyv's type-reshaping wrappers are applied to the
+//! field *type* instead: `Map<W>`/`MapKV<K, V>` recurse into elements, keys and values with the inner
+//! wrappers; `Lock`, `Unsafe`, `Unshare` and `AsOwned` archive the type argument; `AsVec` and
+//! `AtomicLoad` swap the container for a vector or plain integer; the niching wrappers (`Niche`, `NicheInto<N>`,
+//! `DefaultNiche`, `MapNiche<W, N>`) emit `r.niched` when the value offers the requested niche.
 //!
 //! ## Remote proxies
 //!
@@ -25,7 +51,7 @@
 //! Instead, the proxy itself is auto-registered as a with-wrapper whose template is the proxy's own codec expression,
 //! so fields annotated `#[rkyv(with = ProxyDef)]` resolve to it (rkyv 0.8 semantics).
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -39,22 +65,64 @@ use syn::{
 };
 use walkdir::WalkDir;
 
+use crate::casing::Casing;
+use crate::cfg::CfgSet;
+use crate::consts;
 use crate::error::{Diagnostic, DiagnosticKind, Error, SourceLocation};
-use crate::expr::{CodecExpr, codec};
-use crate::generator::{CodeGenerator, EnumVariant, OnUnknown, TypeKind};
-use crate::registry::WithWrapper;
+use crate::expr::{CodecExpr, Niching, codec};
+use crate::generator::{CodeGenerator, EnumVariant, OnUnknown, TypeAlias, TypeKind, VariantDef};
+use crate::registry::{WithWrapper, WithWrapperKind};
 
-/// Per-file context built from `use` items and type aliases.
-struct SourceContext {
-    /// Maps local name → fully-qualified path.
+/// The names one module brings into scope.
+#[derive(Clone, Default)]
+struct ModuleScope {
+    /// Maps local name → path as written; `crate::`/`self::`/`super::` prefixes are resolved on lookup.
     imports: HashMap<String, String>,
     /// Glob import prefixes (`use rkyv::*` → `"rkyv"`).
     globs: Vec<String>,
+    /// The structs and enums declared directly in the module.
+    declared: HashSet<String>,
+    /// The type aliases declared directly in the module.
+    aliases: HashSet<String>,
+    /// The `const` items declared directly in the module.
+    consts: HashSet<String>,
+}
+
+/// Every module of a parsed source, keyed by its path relative to the crate root
+/// (`""` for the root itself, `"api::v1"` for a nested module).
+type ModuleScopes = BTreeMap<String, ModuleScope>;
+
+/// Guards import resolution against `use` cycles (`use self::a as b; use self::b as a;`).
+const MAX_RESOLVE_DEPTH: usize = 16;
+
+/// `module::name`, or just `name` at the crate root.
+fn join_path(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{module}::{name}")
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split("::").map(str::to_string).collect()
+}
+
+/// Per-item extraction context: the enclosing module, plus the module table
+/// for resolving paths that leave it.
+struct SourceContext<'a> {
+    scopes: &'a ModuleScopes,
+    /// The module declaring the item being extracted.
+    module: String,
     /// The file being parsed, if known.
     file: Option<PathBuf>,
+    /// Generic type parameters of the item being extracted.
+    type_params: Vec<String>,
+    /// The crate-relative path `Self` stands for, inside a type or `impl` block.
+    self_type: Option<String>,
 }
 
-impl SourceContext {
+impl SourceContext<'_> {
     fn location(&self, span: proc_macro2::Span) -> SourceLocation {
         let start = span.start();
         SourceLocation {
@@ -63,6 +131,192 @@ impl SourceContext {
             column: start.column + 1,
         }
     }
+
+    fn scope(&self) -> &ModuleScope {
+        &self.scopes[&self.module]
+    }
+
+    /// The path a single-segment name is imported as in the current module.
+    ///
+    /// Imports of local items are re-anchored at the crate root (`use super::User` → `crate::User`),
+    /// and local glob imports (`use super::*`) are followed.
+    fn import(&self, name: &str) -> Option<String> {
+        self.import_in(&self.module, name, 0)
+    }
+
+    fn import_in(&self, module: &str, name: &str, depth: usize) -> Option<String> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        let scope = self.scopes.get(module)?;
+        if let Some(path) = scope.imports.get(name) {
+            let Some(local) = self.local_path_in(module, &split_path(path), depth + 1) else {
+                return Some(path.clone());
+            };
+            // A local import that names another module's import (a re-export or
+            // a type alias) resolves through it.
+            let (target, item) = local.rsplit_once("::").unwrap_or(("", &local));
+            if self
+                .scopes
+                .get(target)
+                .is_some_and(|scope| !scope.declared.contains(item))
+                && let Some(path) = self.import_in(target, item, depth + 1)
+            {
+                return Some(path);
+            }
+            return Some(join_path("crate", &local));
+        }
+        for glob in &scope.globs {
+            if let Some(target) = self.local_path_in(module, &split_path(glob), depth + 1)
+                && let Some(path) = self.import_in(&target, name, depth + 1)
+            {
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    /// The crate-relative path of `segments` if they name a local item: a `crate::`, `self::` or `super::` path,
+    /// a path through a child module, or through an import of a local module. `None` for external paths.
+    fn local_path(&self, segments: &[String]) -> Option<String> {
+        self.local_path_in(&self.module, segments, 0)
+    }
+
+    fn local_path_in(&self, module: &str, segments: &[String], depth: usize) -> Option<String> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        let (first, rest) = segments.split_first()?;
+        let mut base: Vec<&str> = if module.is_empty() {
+            Vec::new()
+        } else {
+            module.split("::").collect()
+        };
+        let mut rest = rest;
+        match first.as_str() {
+            "crate" => base.clear(),
+            "self" => {}
+            "super" => {
+                base.pop();
+                while let Some((next, tail)) = rest.split_first()
+                    && next == "super"
+                {
+                    base.pop();
+                    rest = tail;
+                }
+            }
+            _ => {
+                if self.scopes.contains_key(&join_path(module, first)) {
+                    base.push(first);
+                } else {
+                    let imported = self.scopes.get(module)?.imports.get(first)?;
+                    let mut through = split_path(imported);
+                    through.extend(rest.iter().cloned());
+                    return self.local_path_in(module, &through, depth + 1);
+                }
+            }
+        }
+        let mut path: Vec<&str> = base;
+        path.extend(rest.iter().map(String::as_str));
+        Some(path.join("::"))
+    }
+
+    /// The crate-relative path of the type alias `segments` name, if they do: declared in
+    /// the current module, imported, or reached through a module path or local glob import.
+    fn alias(&self, segments: &[String]) -> Option<String> {
+        match segments {
+            [name] => self.alias_in(&self.module, name, 0),
+            _ => {
+                let local = self.local_path(segments)?;
+                let (target, item) = local.rsplit_once("::").unwrap_or(("", &local));
+                self.alias_in(target, item, 0)
+            }
+        }
+    }
+
+    fn alias_in(&self, module: &str, name: &str, depth: usize) -> Option<String> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        let scope = self.scopes.get(module)?;
+        if scope.aliases.contains(name) {
+            return Some(join_path(module, name));
+        }
+        if let Some(path) = scope.imports.get(name) {
+            let local = self.local_path_in(module, &split_path(path), depth + 1)?;
+            let (target, item) = local.rsplit_once("::").unwrap_or(("", &local));
+            return self.alias_in(target, item, depth + 1);
+        }
+        scope.globs.iter().find_map(|glob| {
+            let target = self.local_path_in(module, &split_path(glob), depth + 1)?;
+            self.alias_in(&target, name, depth + 1)
+        })
+    }
+
+    /// The lookup key of a constant: its crate-relative path for local constants,
+    /// `module::Type::NAME` for associated ones (`Self::SIZE`, `Digest::SIZE`), and the
+    /// fully-qualified path for imported external ones.
+    fn constant(&self, path: &syn::Path) -> String {
+        let segments = path_segments(path);
+        match &segments[..] {
+            [name] => self
+                .const_in(&self.module, name, 0)
+                .unwrap_or_else(|| join_path(&self.module, name)),
+            [first, rest @ ..] if first == "Self" && self.self_type.is_some() => {
+                let self_type = self.self_type.as_deref().unwrap_or_default();
+                join_path(self_type, &rest.join("::"))
+            }
+            [first, ..] => self.local_path(&segments).unwrap_or_else(|| {
+                if self.scope().declared.contains(first) {
+                    join_path(&self.module, &segments.join("::"))
+                } else {
+                    segments.join("::")
+                }
+            }),
+            [] => String::new(),
+        }
+    }
+
+    fn const_in(&self, module: &str, name: &str, depth: usize) -> Option<String> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        let scope = self.scopes.get(module)?;
+        if scope.consts.contains(name) {
+            return Some(join_path(module, name));
+        }
+        if let Some(path) = scope.imports.get(name) {
+            let Some(local) = self.local_path_in(module, &split_path(path), depth + 1) else {
+                return Some(path.clone());
+            };
+            let (target, item) = local.rsplit_once("::").unwrap_or(("", &local));
+            return self.const_in(target, item, depth + 1).or(Some(local));
+        }
+        scope.globs.iter().find_map(|glob| {
+            let target = self.local_path_in(module, &split_path(glob), depth + 1)?;
+            self.const_in(&target, name, depth + 1)
+        })
+    }
+
+    /// The crate-relative path of a type named by a bare, unimported ident:
+    /// declared in the current module, or brought into scope by a local glob import.
+    /// Defaults to the current module, so a missing type surfaces as an unresolved reference.
+    fn local_type(&self, name: &str) -> String {
+        let scope = self.scope();
+        if !scope.declared.contains(name) {
+            for glob in &scope.globs {
+                if let Some(target) = self.local_path(&split_path(glob))
+                    && self
+                        .scopes
+                        .get(&target)
+                        .is_some_and(|scope| scope.declared.contains(name))
+                {
+                    return join_path(&target, name);
+                }
+            }
+        }
+        join_path(&self.module, name)
+    }
 }
 
 /// Recursively flatten a `UseTree` into import entries and glob prefixes.
@@ -114,38 +368,115 @@ fn path_segments(path: &syn::Path) -> Vec<String> {
     path.segments.iter().map(|s| s.ident.to_string()).collect()
 }
 
-/// Build a `SourceContext` from all `use` items and type aliases in a file.
-fn build_source_context(file: &syn::File, source_file: Option<PathBuf>) -> SourceContext {
-    let mut imports = HashMap::new();
-    let mut globs = Vec::new();
+/// A `const` item: declared in a module, or associated with a type by an `impl` block.
+struct ConstItem<'a> {
+    module: String,
+    /// The type an associated constant belongs to, as named in the `impl` header.
+    self_type: Option<String>,
+    name: String,
+    /// The declared type, when it is a primitive integer.
+    ty: Option<consts::IntType>,
+    expr: &'a syn::Expr,
+}
+
+impl ConstItem<'_> {
+    /// The key [`SourceContext::constant`] resolves references to this constant to.
+    fn key(&self) -> String {
+        match &self.self_type {
+            Some(ty) => join_path(&self.module, &format!("{ty}::{}", self.name)),
+            None => join_path(&self.module, &self.name),
+        }
+    }
+}
+
+/// Record the scope of `module` and of every inline module nested in it,
+/// and collect their struct, enum, type alias and `const` items.
+fn collect_modules<'a>(
+    items: &'a [syn::Item],
+    module: &str,
+    scopes: &mut ModuleScopes,
+    type_items: &mut Vec<(String, TypeItem<'a>)>,
+    alias_items: &mut Vec<(String, &'a syn::ItemType)>,
+    const_items: &mut Vec<ConstItem<'a>>,
+) {
+    let mut scope = ModuleScope::default();
 
-    for item in &file.items {
+    for item in items {
         match item {
             syn::Item::Use(item_use) => {
-                collect_imports(&item_use.tree, &[], &mut imports, &mut globs);
+                collect_imports(&item_use.tree, &[], &mut scope.imports, &mut scope.globs);
             }
-            // `type Foo<..> = some::path::Bar<..>` maps `Foo` to
-            // `some::path::Bar`. Only the path is resolved; generic
-            // parameters on either side are handled at the use site.
+            // Aliases are expanded at their use sites. `type Foo<..> = some::path::Bar<..>`
+            // also maps `Foo` to `some::path::Bar`, for lookups by path (with-wrappers).
             syn::Item::Type(item_type) => {
                 if let Type::Path(TypePath { path, .. }) = &*item_type.ty
                     && path.segments.len() > 1
                 {
-                    imports.insert(
+                    scope.imports.insert(
                         item_type.ident.to_string(),
                         path_segments(path).join("::"),
                     );
                 }
+                scope.aliases.insert(item_type.ident.to_string());
+                alias_items.push((module.to_string(), item_type));
+            }
+            syn::Item::Struct(s) => {
+                scope.declared.insert(s.ident.to_string());
+                type_items.push((module.to_string(), TypeItem::Struct(s)));
+            }
+            syn::Item::Enum(e) => {
+                scope.declared.insert(e.ident.to_string());
+                type_items.push((module.to_string(), TypeItem::Enum(e)));
+            }
+            syn::Item::Mod(item_mod) => {
+                if let Some((_, content)) = &item_mod.content {
+                    let child = join_path(module, &item_mod.ident.unraw().to_string());
+                    collect_modules(
+                        content,
+                        &child,
+                        scopes,
+                        type_items,
+                        alias_items,
+                        const_items,
+                    );
+                }
+            }
+            syn::Item::Const(item_const) => {
+                scope.consts.insert(item_const.ident.to_string());
+                const_items.push(ConstItem {
+                    module: module.to_string(),
+                    self_type: None,
+                    name: item_const.ident.to_string(),
+                    ty: consts::IntType::of(&item_const.ty),
+                    expr: &item_const.expr,
+                });
+            }
+            // Associated constants, reachable as `Type::NAME` and `Self::NAME`.
+            syn::Item::Impl(item_impl) => {
+                let Type::Path(TypePath { qself: None, path }) = &*item_impl.self_ty else {
+                    continue;
+                };
+                let [self_type] = &path.segments.iter().collect::<Vec<_>>()[..] else {
+                    continue;
+                };
+                let self_type = &self_type.ident;
+                for impl_item in &item_impl.items {
+                    if let syn::ImplItem::Const(item_const) = impl_item {
+                        const_items.push(ConstItem {
+                            module: module.to_string(),
+                            self_type: Some(self_type.to_string()),
+                            name: item_const.ident.to_string(),
+                            ty: consts::IntType::of(&item_const.ty),
+                            expr: &item_const.expr,
+                        });
+                    }
+                }
             }
             _ => {}
         }
     }
 
-    SourceContext {
-        imports,
-        globs,
-        file: source_file,
-    }
+    scopes.insert(module.to_string(), scope);
 }
 
 /// Type-level `#[rkyv(...)]` attributes the extractor understands.
@@ -238,12 +569,13 @@ fn has_marker_derive(attrs: &[Attribute], ctx: &SourceContext, codegen: &CodeGen
                 if markers.contains(ident) {
                     return true;
                 }
-                // Resolve through imports (incl. renames).
-                if ctx.imports.get(ident).is_some_and(|fq| markers.contains(fq)) {
+                // Resolve through imports (incl. renames and local globs).
+                if ctx.import(ident).is_some_and(|fq| markers.contains(&fq)) {
                     return true;
                 }
                 // Resolve through glob imports.
                 if ctx
+                    .scope()
                     .globs
                     .iter()
                     .any(|glob| markers.contains(&format!("{glob}::{ident}")))
@@ -279,16 +611,39 @@ fn type_to_expr(
             let segment = path.segments.last().expect("type paths are non-empty");
             let raw_ident = segment.ident.to_string();
 
-            // Multi-segment paths are already fully qualified; single-segment
-            // idents resolve through the file's imports.
-            let full_path = if path.segments.len() > 1 {
-                path_segments(path).join("::")
-            } else {
-                ctx.imports
-                    .get(&raw_ident)
-                    .cloned()
-                    .unwrap_or_else(|| raw_ident.clone())
-            };
+            // A generic parameter of the enclosing type becomes a codec argument
+            // of the emitted factory; it shadows any import of the same name.
+            if path.segments.len() == 1
+                && segment.arguments.is_none()
+                && ctx.type_params.contains(&raw_ident)
+            {
+                return Ok(codec::param(raw_ident));
+            }
+
+            let segments = path_segments(path);
+
+            // A type alias expands to its target, unless it is emitted by name.
+            if let Some(alias) = ctx.alias(&segments) {
+                let args = local_type_args(segment)
+                    .map(|arg| type_to_expr(arg, codegen, ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let reference = if args.is_empty() {
+                    codec::named(alias)
+                } else {
+                    codec::instantiate(alias, args)
+                };
+                return if codegen.named_aliases {
+                    Ok(reference)
+                } else {
+                    expand_type_aliases(&reference, &codegen.type_aliases, 0)
+                };
+            }
+
+            let mut full_path = resolve_type_path(&segments, ctx);
+            // `Result` is in the prelude, unless the module declares its own.
+            if full_path == "Result" && !ctx.scope().declared.contains("Result") {
+                full_path = "std::result::Result".to_string();
+            }
 
             match full_path.as_str() {
                 "u8" => Ok(codec::u8()),
@@ -299,11 +654,19 @@ fn type_to_expr(
                 "i32" => Ok(codec::i32()),
                 "u64" => Ok(codec::u64()),
                 "i64" => Ok(codec::i64()),
+                "u128" => Ok(codec::u128()),
+                "i128" => Ok(codec::i128()),
+                "usize" => Ok(codec::usize()),
+                "isize" => Ok(codec::isize()),
                 "f32" => Ok(codec::f32()),
                 "f64" => Ok(codec::f64()),
                 "bool" => Ok(codec::bool_()),
                 "char" => Ok(codec::char_()),
                 "String" | "std::string::String" => Ok(codec::string()),
+                // rkyv archives `PhantomData<T>` as itself, a zero-sized type.
+                "PhantomData" | "std::marker::PhantomData" | "core::marker::PhantomData" => {
+                    Ok(codec::unit())
+                }
                 "Vec" | "std::vec::Vec" => {
                     let inner = single_generic_arg(segment, ty)?;
                     Ok(codec::vec(type_to_expr(inner, codegen, ctx)?))
@@ -314,10 +677,35 @@ fn type_to_expr(
                 }
                 "Box" | "std::boxed::Box" => {
                     let inner = single_generic_arg(segment, ty)?;
-                    Ok(codec::boxed(type_to_expr(inner, codegen, ctx)?))
+                    pointer_expr(inner, codec::boxed, codegen, ctx)
+                }
+                // Sized pointees go through the registry like any other generic type.
+                "std::rc::Rc" | "std::sync::Arc" | "triomphe::Arc"
+                    if local_type_args(segment).next().is_some_and(is_unsized) =>
+                {
+                    let inner = single_generic_arg(segment, ty)?;
+                    pointer_expr(inner, codec::rc, codegen, ctx)
+                }
+                // `Cow` archives through `AsOwned`, in its owned form.
+                "std::borrow::Cow" => {
+                    let inner = local_type_args(segment).next().ok_or_else(|| {
+                        DiagnosticKind::UnsupportedFieldType {
+                            rust_type: type_to_string(ty),
+                        }
+                    })?;
+                    owned_expr(inner, codegen, ctx)
+                }
+                // `ArchivedNonZero*` shares its integer's layout; zero simply never
+                // appears on the wire. `Option<NonZero*>` stays rkyv's tagged option
+                // unless the field opts into the niche with `with = Niche`.
+                "NonZero" | "std::num::NonZero" | "core::num::NonZero" => {
+                    let inner = single_generic_arg(segment, ty)?;
+                    type_to_expr(inner, codegen, ctx)
                 }
                 _ => {
-                    if let Some(external) = codegen.registry.get_type(&full_path) {
+                    if let Some(integer) = nonzero_integer(&full_path) {
+                        Ok(integer)
+                    } else if let Some(external) = codegen.registry.get_type(&full_path) {
                         let raw_args = collect_type_args(segment);
                         // Trailing arguments (hashers, allocators) are discarded,
                         // so never try to resolve them to codecs.
@@ -341,10 +729,22 @@ fn type_to_expr(
                             },
                             other => other,
                         })
-                    } else if path.segments.len() == 1 && full_path == raw_ident {
-                        // A bare local ident: a reference to another generated type,
-                        // validated at generate time.
-                        Ok(codec::named(raw_ident))
+                    } else if let Some(local) = local_type_path(
+                        &full_path,
+                        segments.len() == 1 && full_path == raw_ident,
+                        ctx,
+                    ) {
+                        // A local type: a reference to another generated type by its
+                        // crate-relative path, validated at generate time. Type arguments
+                        // instantiate a generic type's factory; arity is checked then too.
+                        let args = local_type_args(segment)
+                            .map(|arg| type_to_expr(arg, codegen, ctx))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        if args.is_empty() {
+                            Ok(codec::named(local))
+                        } else {
+                            Ok(codec::instantiate(local, args))
+                        }
                     } else {
                         Err(DiagnosticKind::UnknownType {
                             suggestion: codegen.registry.suggest_type(&full_path),
@@ -356,17 +756,11 @@ fn type_to_expr(
         }
         Type::Array(TypeArray { elem, len, .. }) => {
             let elem_expr = type_to_expr(elem, codegen, ctx)?;
-            if let syn::Expr::Lit(syn::ExprLit {
-                lit: syn::Lit::Int(lit_int),
-                ..
-            }) = len
-                && let Ok(len_val) = lit_int.base10_parse::<u64>()
-            {
-                Ok(codec::array(elem_expr, len_val))
-            } else {
-                Err(DiagnosticKind::UnsupportedFieldType {
-                    rust_type: type_to_string(ty),
-                })
+            match array_len(len, codegen, ctx) {
+                Some(len_val) => Ok(codec::array(elem_expr, len_val)),
+                None => Err(DiagnosticKind::UnsupportedArrayLength {
+                    expr: len.to_token_stream().to_string(),
+                }),
             }
         }
         Type::Tuple(TypeTuple { elems, .. }) => {
@@ -376,14 +770,7 @@ fn type_to_expr(
                 .collect::<Result<Vec<_>, _>>()?;
             Ok(codec::tuple(elem_exprs))
         }
-        Type::Reference(reference) => {
-            if let Type::Path(TypePath { path, .. }) = &*reference.elem
-                && path.is_ident("str")
-            {
-                return Ok(codec::string());
-            }
-            type_to_expr(&reference.elem, codegen, ctx)
-        }
+        Type::Reference(reference) => owned_expr(&reference.elem, codegen, ctx),
         Type::Paren(paren) => type_to_expr(&paren.elem, codegen, ctx),
         Type::Group(group) => type_to_expr(&group.elem, codegen, ctx),
         other => Err(DiagnosticKind::UnsupportedFieldType {
@@ -392,6 +779,306 @@ fn type_to_expr(
     }
 }
 
+/// The lookup path of a type path's segments.
+///
+/// Single-segment idents resolve through the module's imports;
+/// multi-segment paths are fully qualified, unless they lead into
+/// the crate itself (`crate::`, `self::`, `super::`, a child module).
+fn resolve_type_path(segments: &[String], ctx: &SourceContext) -> String {
+    if segments.len() > 1 {
+        match ctx.local_path(segments) {
+            Some(local) => join_path("crate", &local),
+            None => segments.join("::"),
+        }
+    } else {
+        ctx.import(&segments[0])
+            .unwrap_or_else(|| segments[0].clone())
+    }
+}
+
+/// Inline every reference to a type alias in `expr`, binding generic parameters to the reference's arguments.
+///
+/// An alias whose target has no codec fails with the target's diagnostic. References to aliases
+/// that are unknown so far are left in place; [`resolve_type_aliases`] settles them as sources are added.
+fn expand_type_aliases(
+    expr: &CodecExpr,
+    aliases: &BTreeMap<String, TypeAlias>,
+    depth: usize,
+) -> Result<CodecExpr, DiagnosticKind> {
+    expr.try_rewrite(&mut |node| {
+        let (name, args) = match node {
+            CodecExpr::TypeRef(name) => (name, &[][..]),
+            CodecExpr::Call(callee, args) => match callee.as_ref() {
+                CodecExpr::TypeRef(name) => (name, args.as_slice()),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let Some(TypeAlias {
+            params,
+            defaults,
+            target,
+            ..
+        }) = aliases.get(name)
+        else {
+            return Ok(None);
+        };
+        let target = match target {
+            Ok(target) => target,
+            Err(diagnostics) => return Err(diagnostics[0].kind.clone()),
+        };
+        // Rust rejects cyclic aliases; the depth guard only keeps a bad source from recursing forever.
+        if depth > MAX_RESOLVE_DEPTH {
+            return Ok(None);
+        }
+        // Omitted trailing arguments take the parameters' defaults (`type Ids<T = u64>`).
+        let mut args = args.to_vec();
+        for default in &defaults[args.len().min(defaults.len())..] {
+            let default = match default {
+                Some(Ok(default)) => default,
+                Some(Err(kind)) => return Err(kind.clone()),
+                None => break,
+            };
+            args.push(default.substitute_type_params(&params[..args.len()], &args));
+        }
+        if args.len() != params.len() {
+            return Err(DiagnosticKind::GenericArity {
+                rust_path: name.clone(),
+                expected: params.len(),
+                found: args.len(),
+            });
+        }
+        let args = args
+            .iter()
+            .map(|arg| expand_type_aliases(arg, aliases, depth))
+            .collect::<Result<Vec<_>, _>>()?;
+        let expanded = target.substitute_type_params(params, &args);
+        expand_type_aliases(&expanded, aliases, depth + 1).map(Some)
+    })
+}
+
+/// The codecs of the defaults of `generics`' type parameters, `None` for a parameter without one.
+/// A default may name the parameters before it (`struct Pair<A, B = A>`).
+fn param_defaults(
+    generics: &syn::Generics,
+    codegen: &CodeGenerator,
+    ctx: &SourceContext,
+) -> Vec<Option<Result<CodecExpr, DiagnosticKind>>> {
+    generics
+        .type_params()
+        .map(|param| {
+            let default = param.default.as_ref()?;
+            Some(type_to_expr(default, codegen, ctx))
+        })
+        .collect()
+}
+
+/// Settle the alias references extraction left behind, over every type added so far.
+///
+/// References to aliases added by a later source are expanded now.
+/// With named aliases, every referenced alias becomes a type instead.
+fn resolve_type_aliases(codegen: &mut CodeGenerator) {
+    /// The aliases `kind` references that are not types themselves.
+    fn alias_refs(codegen: &CodeGenerator, name: &str, kind: &TypeKind) -> BTreeSet<String> {
+        let mut refs = BTreeSet::new();
+        for (_, expr) in CodeGenerator::exprs_with_context(name, kind) {
+            expr.collect_type_refs(&mut refs);
+        }
+        refs.retain(|reference| {
+            !codegen.is_known_type(reference) && codegen.type_aliases.contains_key(reference)
+        });
+        refs
+    }
+
+    if codegen.named_aliases {
+        let mut pending: Vec<String> = codegen
+            .types
+            .iter()
+            .flat_map(|(name, kind)| alias_refs(codegen, name, kind))
+            .collect();
+        while let Some(name) = pending.pop() {
+            if codegen.is_known_type(&name) {
+                continue;
+            }
+            let alias = &codegen.type_aliases[&name];
+            let location = alias.location.clone();
+            match alias.target.clone() {
+                Ok(target) => {
+                    let params = alias.params.clone();
+                    let defaults = alias.defaults.clone();
+                    let kind = TypeKind::Alias(target);
+                    pending.extend(alias_refs(codegen, &name, &kind));
+                    for default in defaults.iter().flatten().flatten() {
+                        let kind = TypeKind::Alias(default.clone());
+                        pending.extend(alias_refs(codegen, &name, &kind));
+                    }
+                    if defaults.iter().any(Option::is_some) {
+                        codegen.type_param_defaults.insert(name.clone(), defaults);
+                    }
+                    codegen.add_type(name.clone(), kind, location);
+                    codegen.set_type_params(name, params);
+                }
+                Err(diagnostics) => codegen.add_failed_type(name, diagnostics, location),
+            }
+        }
+        return;
+    }
+
+    let pending: Vec<String> = codegen
+        .types
+        .iter()
+        .filter(|(name, kind)| !alias_refs(codegen, name, kind).is_empty())
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in pending {
+        let mut kind = codegen
+            .types
+            .remove(&name)
+            .expect("collected from the types");
+        let mut diagnostics = Vec::new();
+        for expr in kind.exprs_mut() {
+            match expand_type_aliases(expr, &codegen.type_aliases, 0) {
+                Ok(expanded) => *expr = expanded,
+                Err(error) => diagnostics.push(Diagnostic::new(error).referenced_by(name.clone())),
+            }
+        }
+        if diagnostics.is_empty() {
+            codegen.types.insert(name, kind);
+        } else {
+            codegen.failed.insert(name, diagnostics);
+        }
+    }
+}
+
+/// Complete every reference to a generic type that omits trailing arguments with the parameters' defaults,
+/// so `Defaulted` stands for `Defaulted<u32>` given `struct Defaulted<T = u32>`.
+///
+/// Runs over every type added so far, since the referenced type may come from a later source.
+/// References that still lack an argument without a default are left to the arity check of `generate`.
+fn fill_default_type_args(codegen: &mut CodeGenerator) {
+    fn fill(expr: &CodecExpr, codegen: &CodeGenerator) -> Result<CodecExpr, DiagnosticKind> {
+        expr.try_rewrite(&mut |node| {
+            let (name, args) = match node {
+                CodecExpr::TypeRef(name) => (name, &[][..]),
+                CodecExpr::Call(callee, args) => match callee.as_ref() {
+                    CodecExpr::TypeRef(name) => (name, args.as_slice()),
+                    _ => return Ok(None),
+                },
+                _ => return Ok(None),
+            };
+            // The arguments are rewritten here, so the callee is never mistaken for a bare reference.
+            let mut args = args
+                .iter()
+                .map(|arg| fill(arg, codegen))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let (Some(params), Some(defaults)) = (
+                codegen.type_params.get(name),
+                codegen.type_param_defaults.get(name),
+            ) {
+                for default in defaults.iter().skip(args.len()) {
+                    let Some(default) = default else {
+                        break;
+                    };
+                    let default = fill(&default.clone()?, codegen)?;
+                    args.push(default.substitute_type_params(&params[..args.len()], &args));
+                }
+            }
+            Ok(Some(if args.is_empty() {
+                codec::named(name.clone())
+            } else {
+                codec::instantiate(name.clone(), args)
+            }))
+        })
+    }
+
+    let pending: Vec<String> = codegen
+        .types
+        .iter()
+        .filter(|(name, kind)| {
+            CodeGenerator::exprs_with_context(name, kind)
+                .iter()
+                .any(|(_, expr)| {
+                    let mut arities = Vec::new();
+                    expr.collect_type_ref_arities(&mut arities);
+                    arities.iter().any(|(reference, found)| {
+                        codegen.type_param_defaults.contains_key(reference)
+                            && *found < codegen.type_arity(reference)
+                    })
+                })
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in pending {
+        let mut kind = codegen
+            .types
+            .remove(&name)
+            .expect("collected from the types");
+        let mut diagnostics = Vec::new();
+        for expr in kind.exprs_mut() {
+            match fill(expr, codegen) {
+                Ok(filled) => *expr = filled,
+                Err(error) => diagnostics.push(Diagnostic::new(error).referenced_by(name.clone())),
+            }
+        }
+        if diagnostics.is_empty() {
+            codegen.types.insert(name, kind);
+        } else {
+            codegen.failed.insert(name, diagnostics);
+        }
+    }
+}
+
+/// The length of an array type, evaluated like rustc does (see [`const_value`]).
+fn array_len(len: &syn::Expr, codegen: &CodeGenerator, ctx: &SourceContext) -> Option<u64> {
+    const_value(len, Some(consts::IntType::USIZE), codegen, ctx)
+        .and_then(|len| u64::try_from(len.value).ok())
+}
+
+/// The value of a constant expression (an array length or an explicit discriminant) of type `expected`,
+/// resolving paths to the constants of the added sources and [`register_const`](CodeGenerator::register_const).
+fn const_value(
+    expr: &syn::Expr,
+    expected: Option<consts::IntType>,
+    codegen: &CodeGenerator,
+    ctx: &SourceContext,
+) -> Option<consts::Value> {
+    consts::eval(expr, expected, &mut |path| {
+        codegen.constants.get(&ctx.constant(path)).copied()
+    })
+}
+
+/// The integer codec behind a `NonZero*` alias (`NonZeroU32`, `std::num::NonZeroI64`, ...).
+fn nonzero_integer(path: &str) -> Option<CodecExpr> {
+    let name = path
+        .strip_prefix("std::num::")
+        .or_else(|| path.strip_prefix("core::num::"))
+        .unwrap_or(path);
+    Some(match name.strip_prefix("NonZero")? {
+        "U8" => codec::u8(),
+        "I8" => codec::i8(),
+        "U16" => codec::u16(),
+        "I16" => codec::i16(),
+        "U32" => codec::u32(),
+        "I32" => codec::i32(),
+        "U64" => codec::u64(),
+        "I64" => codec::i64(),
+        "U128" => codec::u128(),
+        "I128" => codec::i128(),
+        "Usize" => codec::usize(),
+        "Isize" => codec::isize(),
+        _ => return None,
+    })
+}
+
+/// The crate-relative path of a type reference naming a local type: a path
+/// resolved into the crate, or a bare ident that is neither imported nor registered.
+fn local_type_path(full_path: &str, bare: bool, ctx: &SourceContext) -> Option<String> {
+    if let Some(local) = full_path.strip_prefix("crate::") {
+        return Some(local.to_string());
+    }
+    bare.then(|| ctx.local_type(full_path))
+}
+
 fn single_generic_arg<'a>(
     segment: &'a syn::PathSegment,
     whole: &Type,
@@ -428,22 +1115,25 @@ fn collect_type_args(segment: &syn::PathSegment) -> Vec<&Type> {
     type_args
 }
 
+/// The type arguments of a reference to a generated type, verbatim.
+/// Lifetimes and const generics are skipped.
+fn local_type_args(segment: &syn::PathSegment) -> impl Iterator<Item = &Type> {
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => Some(&args.args),
+        _ => None,
+    };
+    args.into_iter().flatten().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
 /// Resolve the `W` of `#[rkyv(with = W)]` to a registry lookup key.
 fn resolve_wrapper_path(ty: &syn::Type, ctx: &SourceContext) -> Option<String> {
     let Type::Path(TypePath { qself: None, path }) = ty else {
         return None;
     };
-    let segments = path_segments(path);
-    if segments.len() == 1 {
-        Some(
-            ctx.imports
-                .get(&segments[0])
-                .cloned()
-                .unwrap_or_else(|| segments[0].clone()),
-        )
-    } else {
-        Some(segments.join("::"))
-    }
+    Some(resolve_type_path(&path_segments(path), ctx))
 }
 
 /// Resolve a field to its codec expression.
@@ -455,64 +1145,336 @@ fn field_expr(
     codegen: &CodeGenerator,
     ctx: &SourceContext,
 ) -> Result<Option<CodecExpr>, Diagnostic> {
-    if let Some(with_type) = parse_rkyv_field_with(&field.attrs) {
-        let location = ctx.location(with_type.span());
-        let resolved = resolve_wrapper_path(&with_type, ctx);
-        let wrapper = resolved
-            .as_deref()
-            .and_then(|path| lookup_wrapper(codegen, ctx, path));
-        let Some(wrapper) = wrapper else {
-            return Err(Diagnostic::new(DiagnosticKind::UnknownWithWrapper {
-                wrapper_path: resolved.unwrap_or_else(|| type_to_string(&with_type)),
-            })
+    let expr = match parse_rkyv_field_with(&field.attrs) {
+        Some(with_type) => wrapped_expr(&with_type, &field.ty, codegen, ctx),
+        None => type_to_expr(&field.ty, codegen, ctx)
+            .map(Some)
+            .map_err(|kind| (kind, field.ty.span())),
+    };
+    expr.map_err(|(kind, span)| {
+        Diagnostic::new(kind)
             .referenced_by(context)
-            .at(Some(location)));
-        };
-        let underlying = if wrapper.needs_underlying() {
-            let expr = type_to_expr(&field.ty, codegen, ctx).map_err(|kind| {
-                Diagnostic::new(kind)
-                    .referenced_by(context)
-                    .at(Some(ctx.location(field.ty.span())))
-            })?;
-            Some(expr)
-        } else {
-            None
-        };
-        return Ok(wrapper.apply(underlying));
-    }
-
-    type_to_expr(&field.ty, codegen, ctx)
-        .map(Some)
-        .map_err(|kind| {
-            Diagnostic::new(kind)
-                .referenced_by(context)
-                .at(Some(ctx.location(field.ty.span())))
-        })
+            .at(Some(ctx.location(span)))
+    })
 }
 
-/// Look up a with-wrapper by resolved path, trying glob prefixes for bare idents
-/// (`use rkyv::with::*` + `with = AsBox`).
-fn lookup_wrapper(
+/// A diagnostic with the span it points at.
+type Located = (DiagnosticKind, proc_macro2::Span);
+
+/// Apply the with-wrapper `with_type` to a field of type `ty`.
+///
+/// Wrappers that reshape the field type (`Map<W>`, `MapKV<K, V>`, `Lock`, ...) are applied here;
+/// whether the wrapper can archive the type at all is rustc's call, so only the shape is checked.
+/// `Ok(None)` means the field is omitted (a `Skip` wrapper).
+fn wrapped_expr(
+    with_type: &Type,
+    ty: &Type,
     codegen: &CodeGenerator,
     ctx: &SourceContext,
-    path: &str,
-) -> Option<WithWrapper> {
-    if let Some(wrapper) = codegen.registry.get_wrapper(path) {
-        return Some(wrapper.clone());
-    }
-    if !path.contains("::") {
-        for glob in &ctx.globs {
-            if let Some(wrapper) = codegen.registry.get_wrapper(&format!("{glob}::{path}")) {
-                return Some(wrapper.clone());
+) -> Result<Option<CodecExpr>, Located> {
+    let resolved = resolve_wrapper_path(with_type, ctx);
+    let wrapper_path = resolved
+        .clone()
+        .unwrap_or_else(|| type_to_string(with_type));
+    let Some(wrapper) = resolved
+        .as_deref()
+        .and_then(|path| lookup_wrapper(codegen, ctx, path))
+    else {
+        return Err((
+            DiagnosticKind::UnknownWithWrapper { wrapper_path },
+            with_type.span(),
+        ));
+    };
+    let at_type = |kind| (kind, ty.span());
+    let mismatch = || {
+        at_type(DiagnosticKind::UnsupportedWrapperTarget {
+            wrapper_path: wrapper_path.clone(),
+            rust_type: type_to_string(ty),
+        })
+    };
+    // A wrapper applied inside `Map`/`MapKV` must produce a codec.
+    let element = |with_type: &Type, ty: &Type| {
+        wrapped_expr(with_type, ty, codegen, ctx)?.ok_or_else(mismatch)
+    };
+
+    match wrapper.kind() {
+        WithWrapperKind::Replace(_) | WithWrapperKind::Skip => Ok(wrapper.apply(None)),
+        WithWrapperKind::AsBox => {
+            // `InlineAsBox` archives the pointee of a reference.
+            let pointee = match ty {
+                Type::Reference(reference) => &reference.elem,
+                ty => ty,
+            };
+            pointer_expr(pointee, codec::boxed, codegen, ctx)
+                .map(Some)
+                .map_err(at_type)
+        }
+        WithWrapperKind::Map(_) | WithWrapperKind::Identity => {
+            let underlying = type_to_expr(ty, codegen, ctx).map_err(at_type)?;
+            Ok(wrapper.apply(Some(underlying)))
+        }
+        WithWrapperKind::Unwrap => {
+            let (_, segment) = type_path_of(ty, ctx).ok_or_else(mismatch)?;
+            let inner = local_type_args(segment).next().ok_or_else(mismatch)?;
+            owned_expr(inner, codegen, ctx).map(Some).map_err(at_type)
+        }
+        WithWrapperKind::MapElements => {
+            let [inner_with] = wrapper_type_args(with_type)[..] else {
+                return Err(mismatch());
+            };
+            if let Type::Array(TypeArray { elem, len, .. }) = ty {
+                let len = array_len(len, codegen, ctx).ok_or_else(mismatch)?;
+                return Ok(Some(codec::array(element(inner_with, elem)?, len)));
+            }
+            let (path, segment) = type_path_of(ty, ctx).ok_or_else(mismatch)?;
+            let inner = local_type_args(segment).next().ok_or_else(mismatch)?;
+            let inner = element(inner_with, inner)?;
+            match path.as_str() {
+                "Option" | "std::option::Option" => Ok(Some(codec::option(inner))),
+                "Vec" | "std::vec::Vec" => Ok(Some(codec::vec(inner))),
+                _ => Err(mismatch()),
             }
         }
-    }
-    None
-}
-
-/// A named field's identifier, with any raw-identifier escape dropped:
-/// `r#type` is the field `type`, and `r#` is not valid in a JavaScript key.
-fn field_name(field: &syn::Field) -> String {
+        WithWrapperKind::MapEntries => {
+            let [key_with, value_with] = wrapper_type_args(with_type)[..] else {
+                return Err(mismatch());
+            };
+            let (path, segment) = type_path_of(ty, ctx).ok_or_else(mismatch)?;
+            let map = codegen
+                .registry
+                .get_type(&path)
+                .filter(|external| external.arity() == 2)
+                .ok_or_else(mismatch)?;
+            let [key, value, ..] = collect_type_args(segment)[..] else {
+                return Err(mismatch());
+            };
+            let args = vec![element(key_with, key)?, element(value_with, value)?];
+            map.instantiate(args).map(Some).map_err(at_type)
+        }
+        WithWrapperKind::AsVec => {
+            if let Type::Reference(reference) = ty
+                && let Type::Slice(slice) = &*reference.elem
+            {
+                let elem = type_to_expr(&slice.elem, codegen, ctx).map_err(at_type)?;
+                return Ok(Some(codec::vec(elem)));
+            }
+            let (path, segment) = type_path_of(ty, ctx).ok_or_else(mismatch)?;
+            let args = collect_type_args(segment);
+            let resolve = |ty: &Type| type_to_expr(ty, codegen, ctx).map_err(at_type);
+            match (path.as_str(), &args[..]) {
+                (
+                    "std::collections::HashMap" | "hashbrown::HashMap" | "std::collections::BTreeMap",
+                    [key, value, ..],
+                ) => {
+                    // `Entry { key, value }`, a plain `#[repr(C)]`-like archived struct.
+                    let entry = struct_expr(vec![
+                        ("key".to_string(), resolve(key)?),
+                        ("value".to_string(), resolve(value)?),
+                    ]);
+                    Ok(Some(codec::vec(entry)))
+                },
+                (
+                    "std::collections::HashSet" | "hashbrown::HashSet" | "std::collections::BTreeSet",
+                    [elem, ..],
+                ) => Ok(Some(codec::vec(resolve(elem)?))),
+                _ => Err(mismatch()),
+            }
+        }
+        WithWrapperKind::Niche | WithWrapperKind::NicheInto | WithWrapperKind::MapNiche => {
+            let (path, segment) = type_path_of(ty, ctx).ok_or_else(mismatch)?;
+            if !matches!(path.as_str(), "Option" | "std::option::Option") {
+                return Err(mismatch());
+            }
+            let inner = local_type_args(segment).next().ok_or_else(mismatch)?;
+            let args = wrapper_type_args(with_type);
+            let (expr, requested, by_type) = match wrapper.kind() {
+                WithWrapperKind::MapNiche => {
+                    let [map_with, rest @ ..] = &args[..] else {
+                        return Err(mismatch());
+                    };
+                    (element(map_with, inner)?, rest.first().copied(), None)
+                }
+                _ => {
+                    let expr = type_to_expr(inner, codegen, ctx).map_err(at_type)?;
+                    (expr, args.first().copied(), type_niching(inner, ctx))
+                }
+            };
+            let natural = natural_niching(&expr, by_type).ok_or_else(mismatch)?;
+            let niching = match wrapper.kind() {
+                // `Niche` only covers the dedicated `ArchivedOptionNonZero*`/`ArchivedOptionBox` layouts.
+                WithWrapperKind::Niche => {
+                    matches!(natural, Niching::Zero | Niching::Null).then_some(natural)
+                }
+                _ => requested_niching(requested, natural, ctx),
+            };
+            Ok(Some(codec::niched(expr, niching.ok_or_else(mismatch)?)))
+        }
+        WithWrapperKind::AtomicLoad => {
+            let (path, _) = type_path_of(ty, ctx).ok_or_else(mismatch)?;
+            let name = path.rsplit("::").next().unwrap_or(&path);
+            atomic_integer(name).map(Some).ok_or_else(mismatch)
+        }
+    }
+}
+
+/// The resolved lookup path and last segment of a plain type path.
+fn type_path_of<'a>(ty: &'a Type, ctx: &SourceContext) -> Option<(String, &'a syn::PathSegment)> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    Some((resolve_type_path(&path_segments(path), ctx), segment))
+}
+
+/// The type arguments of a parameterised wrapper (`Map<AsBox>`, `MapKV<Inline, AsBox>`).
+fn wrapper_type_args(with_type: &Type) -> Vec<&Type> {
+    match with_type {
+        Type::Path(TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .map(|segment| local_type_args(segment).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// The codec of a `ToOwned` target in its owned form: `str` archives as a
+/// `String` and `[T]` as a `Vec<T>`; sized types archive as themselves.
+fn owned_expr(
+    ty: &Type,
+    codegen: &CodeGenerator,
+    ctx: &SourceContext,
+) -> Result<CodecExpr, DiagnosticKind> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) if path.is_ident("str") => Ok(codec::string()),
+        Type::Slice(slice) => Ok(codec::vec(type_to_expr(&slice.elem, codegen, ctx)?)),
+        _ => type_to_expr(ty, codegen, ctx),
+    }
+}
+
+/// The codec of a pointer to `ty`. Pointers to `str` and slices carry the length next to the offset,
+/// laid out like a `Vec`; sized pointees are wrapped with `wrap` (`r.box`, `r.rc`).
+fn pointer_expr(
+    ty: &Type,
+    wrap: fn(CodecExpr) -> CodecExpr,
+    codegen: &CodeGenerator,
+    ctx: &SourceContext,
+) -> Result<CodecExpr, DiagnosticKind> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) if path.is_ident("str") => {
+            Ok(codec::boxed_str())
+        }
+        Type::Slice(slice) => Ok(codec::vec(type_to_expr(&slice.elem, codegen, ctx)?)),
+        _ => type_to_expr(ty, codegen, ctx).map(wrap),
+    }
+}
+
+/// Whether `ty` is `str` or a slice, which only appear behind pointers.
+fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => path.is_ident("str"),
+        Type::Slice(_) => true,
+        _ => false,
+    }
+}
+
+/// The niche a value offers by its Rust type, where its codec does not tell:
+/// zero for `NonZero*` integers (which share their integer's codec) and the null
+/// pointer for `Box<T>` (a boxed slice shares `Vec<T>`'s codec).
+fn type_niching(ty: &Type, ctx: &SourceContext) -> Option<Niching> {
+    let (path, _) = type_path_of(ty, ctx)?;
+    if nonzero_integer(&path).is_some()
+        || matches!(path.as_str(), "NonZero" | "std::num::NonZero" | "core::num::NonZero")
+    {
+        Some(Niching::Zero)
+    } else if matches!(path.as_str(), "Box" | "std::boxed::Box") {
+        Some(Niching::Null)
+    } else {
+        None
+    }
+}
+
+/// The niche an archived value offers, judged by its Rust type (see [`type_niching`])
+/// or else its codec: NaN for floats, an out-of-range byte for `bool` and the null
+/// pointer for boxes.
+fn natural_niching(expr: &CodecExpr, by_type: Option<Niching>) -> Option<Niching> {
+    match expr {
+        _ if by_type.is_some() => by_type,
+        CodecExpr::Runtime("bool") => Some(Niching::Bool),
+        CodecExpr::Runtime("f32" | "f64") => Some(Niching::NaN),
+        CodecExpr::Runtime("boxedStr") => Some(Niching::Null),
+        CodecExpr::Call(callee, _) if **callee == CodecExpr::Runtime("box") => Some(Niching::Null),
+        _ => None,
+    }
+}
+
+/// The niching a `NicheInto<N>`/`MapNiche<W, N>` asks for, if the value offers it.
+/// No `N` (or `DefaultNiche`) picks the value's default niche, which floats lack.
+fn requested_niching(
+    requested: Option<&Type>,
+    natural: Niching,
+    ctx: &SourceContext,
+) -> Option<Niching> {
+    let name = match requested {
+        Some(ty) => {
+            let (path, _) = type_path_of(ty, ctx)?;
+            path.rsplit("::").next().unwrap_or(&path).to_string()
+        }
+        None => "DefaultNiche".to_string(),
+    };
+    let niching = match name.as_str() {
+        "DefaultNiche" if natural == Niching::NaN => return None,
+        "DefaultNiche" => natural,
+        "Zero" => Niching::Zero,
+        "NaN" => Niching::NaN,
+        "Bool" => Niching::Bool,
+        "Null" => Niching::Null,
+        _ => return None,
+    };
+    (niching == natural).then_some(niching)
+}
+
+/// The plain integer an atomic loads as (`AtomicU32` -> `u32`).
+fn atomic_integer(name: &str) -> Option<CodecExpr> {
+    Some(match name.strip_prefix("Atomic")? {
+        "Bool" => codec::bool_(),
+        "U8" => codec::u8(),
+        "I8" => codec::i8(),
+        "U16" => codec::u16(),
+        "I16" => codec::i16(),
+        "U32" => codec::u32(),
+        "I32" => codec::i32(),
+        "U64" => codec::u64(),
+        "I64" => codec::i64(),
+        "Usize" => codec::usize(),
+        "Isize" => codec::isize(),
+        _ => return None,
+    })
+}
+
+/// Look up a with-wrapper by resolved path, trying glob prefixes for bare idents
+/// (`use rkyv::with::*` + `with = AsBox`).
+fn lookup_wrapper(
+    codegen: &CodeGenerator,
+    ctx: &SourceContext,
+    path: &str,
+) -> Option<WithWrapper> {
+    if let Some(wrapper) = codegen.registry.get_wrapper(path) {
+        return Some(wrapper.clone());
+    }
+    if !path.contains("::") {
+        for glob in &ctx.scope().globs {
+            if let Some(wrapper) = codegen.registry.get_wrapper(&format!("{glob}::{path}")) {
+                return Some(wrapper.clone());
+            }
+        }
+    }
+    None
+}
+
+/// A named field's identifier, with any raw-identifier escape dropped:
+/// `r#type` is the field `type`, and `r#` is not valid in a JavaScript key.
+fn field_name(field: &syn::Field) -> String {
     field
         .ident
         .as_ref()
@@ -588,14 +1550,43 @@ fn extract_enum_variants(
     variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
     codegen: &CodeGenerator,
     ctx: &SourceContext,
-) -> Result<Vec<EnumVariant>, Vec<Diagnostic>> {
+) -> Result<Vec<VariantDef>, Vec<Diagnostic>> {
     let mut out = Vec::new();
     let mut diagnostics = Vec::new();
+    let mut next_discriminant: i128 = 0;
 
     for variant in variants {
         let variant_name = variant.ident.unraw().to_string();
-        match &variant.fields {
-            Fields::Unit => out.push(EnumVariant::Unit(variant_name)),
+
+        // rkyv copies explicit discriminants onto its `#[repr(u8)]` archived tag;
+        // the rest count up from the previous variant as in Rust.
+        let explicit = variant.discriminant.as_ref().map(|(_, expr)| expr);
+        let span = explicit.map_or(variant.ident.span(), |expr| expr.span());
+        let discriminant = match explicit {
+            Some(expr) => const_value(expr, None, codegen, ctx)
+                .map(|value| value.value)
+                .ok_or_else(|| DiagnosticKind::UnsupportedDiscriminant {
+                    expr: expr.to_token_stream().to_string(),
+                }),
+            None => Ok(next_discriminant),
+        }
+        .and_then(|value| {
+            next_discriminant = value.saturating_add(1);
+            u8::try_from(value).map_err(|_| DiagnosticKind::DiscriminantOutOfRange {
+                value: value.to_string(),
+            })
+        })
+        .unwrap_or_else(|kind| {
+            diagnostics.push(
+                Diagnostic::new(kind)
+                    .referenced_by(format!("{type_name}::{variant_name}"))
+                    .at(Some(ctx.location(span))),
+            );
+            0
+        });
+
+        let variant = match &variant.fields {
+            Fields::Unit => EnumVariant::Unit(variant_name),
             Fields::Unnamed(unnamed) => {
                 let mut exprs = Vec::new();
                 for (index, field) in unnamed.unnamed.iter().enumerate() {
@@ -606,7 +1597,7 @@ fn extract_enum_variants(
                         Err(diagnostic) => diagnostics.push(diagnostic),
                     }
                 }
-                let variant = if unnamed.unnamed.len() == 1 {
+                if unnamed.unnamed.len() == 1 {
                     // A newtype variant decodes as the bare inner value;
                     // a fully skipped one degenerates to a unit variant.
                     match exprs.pop() {
@@ -615,8 +1606,7 @@ fn extract_enum_variants(
                     }
                 } else {
                     EnumVariant::Tuple(variant_name, exprs)
-                };
-                out.push(variant);
+                }
             }
             Fields::Named(named) => {
                 let mut fields = Vec::new();
@@ -629,9 +1619,13 @@ fn extract_enum_variants(
                         Err(diagnostic) => diagnostics.push(diagnostic),
                     }
                 }
-                out.push(EnumVariant::Struct(variant_name, fields));
+                EnumVariant::Struct(variant_name, fields)
             }
-        }
+        };
+        out.push(VariantDef {
+            variant,
+            discriminant,
+        });
     }
 
     if diagnostics.is_empty() {
@@ -641,6 +1635,68 @@ fn extract_enum_variants(
     }
 }
 
+/// The text of an item's `///` and `/** */` comments, one line per doc line.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let syn::Meta::NameValue(meta) = &attr.meta else {
+            continue;
+        };
+        if !meta.path.is_ident("doc") {
+            continue;
+        }
+        if let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(text),
+            ..
+        }) = &meta.value
+        {
+            // rustdoc strips the one space that follows `///`.
+            let text = text.value();
+            lines.extend(
+                text.split('\n')
+                    .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end().to_string()),
+            );
+        }
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    let start = lines.iter().position(|line| !line.is_empty())?;
+    Some(lines[start..].join("\n"))
+}
+
+/// Record the doc comments of a type and its named fields and variants, keyed
+/// by the same paths diagnostics use.
+fn record_docs(codegen: &mut CodeGenerator, name: &str, item: &TypeItem<'_>) {
+    fn named_fields<'a>(prefix: &str, fields: &'a Fields) -> Vec<(String, &'a [Attribute])> {
+        match fields {
+            Fields::Named(named) => named
+                .named
+                .iter()
+                .map(|field| (format!("{prefix}.{}", field_name(field)), &field.attrs[..]))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    let mut docs = vec![(name.to_string(), item.attrs())];
+    match item {
+        TypeItem::Struct(s) => docs.extend(named_fields(name, &s.fields)),
+        TypeItem::Enum(e) => {
+            for variant in &e.variants {
+                let path = format!("{name}::{}", variant.ident.unraw());
+                docs.push((path.clone(), &variant.attrs));
+                docs.extend(named_fields(&path, &variant.fields));
+            }
+        }
+    }
+    for (path, attrs) in docs {
+        if let Some(doc) = doc_comment(attrs) {
+            codegen.set_doc(path, doc);
+        }
+    }
+}
+
 /// The inline codec expression for a struct (used for remote proxies).
 fn struct_expr(fields: Vec<(String, CodecExpr)>) -> CodecExpr {
     CodecExpr::call(
@@ -650,8 +1706,9 @@ fn struct_expr(fields: Vec<(String, CodecExpr)>) -> CodecExpr {
 }
 
 /// The inline codec expression for an enum (used for remote proxies).
-fn enum_expr(variants: Vec<EnumVariant>) -> CodecExpr {
-    let entries = variants.into_iter().map(|variant| match variant {
+fn enum_expr(variants: Vec<VariantDef>) -> CodecExpr {
+    let options = VariantDef::options_expr(&variants, Casing::Preserve);
+    let entries = variants.into_iter().map(|def| match def.variant {
         EnumVariant::Unit(name) => (name, CodecExpr::raw("null")),
         EnumVariant::Newtype(name, expr) => (name, expr),
         EnumVariant::Tuple(name, exprs) => (name, CodecExpr::array(exprs)),
@@ -659,11 +1716,11 @@ fn enum_expr(variants: Vec<EnumVariant>) -> CodecExpr {
     });
     CodecExpr::call(
         CodecExpr::runtime("taggedEnum"),
-        [CodecExpr::object(entries)],
+        std::iter::once(CodecExpr::object(entries)).chain(options),
     )
 }
 
-/// A top-level struct or enum item.
+/// A struct or enum item.
 enum TypeItem<'a> {
     Struct(&'a syn::ItemStruct),
     Enum(&'a syn::ItemEnum),
@@ -683,34 +1740,299 @@ impl TypeItem<'_> {
             TypeItem::Enum(item) => &item.ident,
         }
     }
+
+    fn generics(&self) -> &syn::Generics {
+        match self {
+            TypeItem::Struct(item) => &item.generics,
+            TypeItem::Enum(item) => &item.generics,
+        }
+    }
+
+    /// The names of the item's generic type parameters, in declaration order.
+    fn type_params(&self) -> Vec<String> {
+        self.generics()
+            .type_params()
+            .map(|param| param.ident.to_string())
+            .collect()
+    }
 }
 
-fn type_items(file: &syn::File) -> Vec<TypeItem<'_>> {
-    file.items
-        .iter()
-        .filter_map(|item| match item {
-            syn::Item::Struct(s) => Some(TypeItem::Struct(s)),
-            syn::Item::Enum(e) => Some(TypeItem::Enum(e)),
-            _ => None,
-        })
-        .collect()
+/// A parsed source file and the module it defines.
+struct SourceUnit {
+    /// The file the source was read from; `None` for in-memory sources.
+    file: Option<PathBuf>,
+    /// The module path of the file relative to the crate root (`""` for the root).
+    module: String,
+    parsed: syn::File,
 }
 
-fn parse_source(
-    codegen: &mut CodeGenerator,
+fn parse_unit(
     source: &str,
     file: Option<PathBuf>,
-) -> Result<(), Error> {
-    let parsed = syn::parse_file(source).map_err(|source| Error::Parse {
+    module: String,
+    cfg: &CfgSet,
+) -> Result<SourceUnit, Error> {
+    let mut parsed = syn::parse_file(source).map_err(|source| Error::Parse {
         file: file.clone(),
         source,
     })?;
-    let ctx = build_source_context(&parsed, file);
-    let items = type_items(&parsed);
+    cfg.strip_file(&mut parsed);
+    Ok(SourceUnit {
+        file,
+        module,
+        parsed,
+    })
+}
+
+/// The string of a `#[path = "..."]` attribute, if present.
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Where a module looks up the files of its `mod foo;` children.
+struct ModuleDirs<'a> {
+    /// The file declaring the children, for error reporting.
+    file: &'a Path,
+    /// The directory of that file; `#[path]` on a top-level `mod` is relative to it.
+    file_dir: &'a Path,
+    /// The directory holding the children's `foo.rs` / `foo/mod.rs`.
+    dir: PathBuf,
+    /// Whether the children are declared inside an inline `mod { ... }` block,
+    /// which makes `#[path]` relative to `dir` instead of `file_dir`.
+    inline: bool,
+}
+
+/// Load every file reachable from `items` through `mod foo;` declarations, following rustc's lookup rules:
+/// `foo.rs` or `foo/mod.rs` next to a `lib.rs`/`main.rs`/`mod.rs`, under `bar/` for a non-`mod.rs` file `bar.rs`,
+/// and `#[path = "..."]` overrides.
+fn load_child_modules(
+    items: &[syn::Item],
+    module: &str,
+    dirs: &ModuleDirs,
+    cfg: &CfgSet,
+    units: &mut Vec<SourceUnit>,
+) -> Result<(), Error> {
+    for item in items {
+        let syn::Item::Mod(item_mod) = item else {
+            continue;
+        };
+        let name = item_mod.ident.unraw().to_string();
+        let child = join_path(module, &name);
+        let explicit = path_attr(&item_mod.attrs);
+
+        if let Some((_, content)) = &item_mod.content {
+            let nested = ModuleDirs {
+                file: dirs.file,
+                file_dir: dirs.file_dir,
+                dir: dirs.dir.join(explicit.as_deref().unwrap_or(&name)),
+                inline: true,
+            };
+            load_child_modules(content, &child, &nested, cfg, units)?;
+            continue;
+        }
+
+        // A file named by `#[path]` owns its directory, like a `mod.rs`.
+        let (path, child_dir) = match explicit {
+            Some(explicit) => {
+                let base = if dirs.inline { &dirs.dir } else { dirs.file_dir };
+                let path = base.join(explicit);
+                let child_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                (path, child_dir)
+            }
+            None => {
+                let child_dir = dirs.dir.join(&name);
+                let flat = dirs.dir.join(format!("{name}.rs"));
+                let path = if flat.is_file() {
+                    flat
+                } else {
+                    child_dir.join("mod.rs")
+                };
+                (path, child_dir)
+            }
+        };
+        if !path.is_file() {
+            return Err(Error::Parse {
+                file: Some(dirs.file.to_path_buf()),
+                source: syn::Error::new_spanned(
+                    &item_mod.ident,
+                    format!("file not found for module `{name}` (looked for {})", path.display()),
+                ),
+            });
+        }
+        load_module_file(path, child, child_dir, cfg, units)?;
+    }
+    Ok(())
+}
+
+/// Parse the file of `module` and, recursively, the files of its child modules.
+fn load_module_file(
+    path: PathBuf,
+    module: String,
+    dir: PathBuf,
+    cfg: &CfgSet,
+    units: &mut Vec<SourceUnit>,
+) -> Result<(), Error> {
+    let source = fs::read_to_string(&path)?;
+    let unit = parse_unit(&source, Some(path.clone()), module, cfg)?;
+    let file_dir = path.parent().unwrap_or(Path::new(""));
+    let dirs = ModuleDirs {
+        file: &path,
+        file_dir,
+        dir,
+        inline: false,
+    };
+    // Parents precede their children, in declaration order.
+    let index = units.len();
+    load_child_modules(&unit.parsed.items, &unit.module, &dirs, cfg, units)?;
+    units.insert(index, unit);
+    Ok(())
+}
+
+fn extract_units(codegen: &mut CodeGenerator, units: &[SourceUnit]) {
+    let mut unit_scopes = Vec::new();
+    let mut items: Vec<(usize, String, TypeItem)> = Vec::new();
+    let mut aliases: Vec<(usize, String, &syn::ItemType)> = Vec::new();
+    let mut const_items = Vec::new();
+    for (index, unit) in units.iter().enumerate() {
+        let mut own_scopes = ModuleScopes::new();
+        let mut unit_items = Vec::new();
+        let mut unit_aliases = Vec::new();
+        let mut unit_consts = Vec::new();
+        collect_modules(
+            &unit.parsed.items,
+            &unit.module,
+            &mut own_scopes,
+            &mut unit_items,
+            &mut unit_aliases,
+            &mut unit_consts,
+        );
+        unit_scopes.push(own_scopes);
+        items.extend(
+            unit_items
+                .into_iter()
+                .map(|(module, item)| (index, module, item)),
+        );
+        aliases.extend(
+            unit_aliases
+                .into_iter()
+                .map(|(module, item)| (index, module, item)),
+        );
+        const_items.extend(unit_consts.into_iter().map(|item| (index, item)));
+    }
+
+    // Files of a source directory all sit at the crate root: they share their items,
+    // but each keeps its own `use` imports.
+    let mut scopes = ModuleScopes::new();
+    for own_scopes in &unit_scopes {
+        for (module, scope) in own_scopes {
+            let merged = scopes.entry(module.clone()).or_default();
+            merged.imports.extend(scope.imports.clone());
+            merged.globs.extend(scope.globs.iter().cloned());
+            merged.declared.extend(scope.declared.iter().cloned());
+            merged.aliases.extend(scope.aliases.iter().cloned());
+            merged.consts.extend(scope.consts.iter().cloned());
+        }
+    }
+    let mut unit_count: BTreeMap<&str, usize> = BTreeMap::new();
+    for module in unit_scopes.iter().flat_map(BTreeMap::keys) {
+        *unit_count.entry(module).or_default() += 1;
+    }
+    let unit_views: Vec<Option<ModuleScopes>> = unit_scopes
+        .iter()
+        .map(|own_scopes| {
+            let shared: Vec<_> = own_scopes
+                .iter()
+                .filter(|(module, _)| unit_count[module.as_str()] > 1)
+                .collect();
+            if shared.is_empty() {
+                return None;
+            }
+            let mut view = scopes.clone();
+            for (module, scope) in shared {
+                let merged = view.get_mut(module).expect("merged above");
+                merged.imports = scope.imports.clone();
+                merged.globs = scope.globs.clone();
+            }
+            Some(view)
+        })
+        .collect();
+    let context = |module: &str, unit: usize| SourceContext {
+        scopes: unit_views[unit].as_ref().unwrap_or(&scopes),
+        module: module.to_string(),
+        file: units[unit].file.clone(),
+        type_params: Vec::new(),
+        self_type: None,
+    };
+
+    // Constants, which array lengths and discriminants in every later pass may use.
+    // Each round evaluates the constants whose operands are known; the rest wait for the next.
+    loop {
+        let before = const_items.len();
+        const_items.retain(|(unit, item)| {
+            let mut ctx = context(&item.module, *unit);
+            ctx.self_type = item
+                .self_type
+                .as_ref()
+                .map(|ty| join_path(&item.module, ty));
+            match const_value(item.expr, item.ty, codegen, &ctx) {
+                Some(value) => {
+                    codegen.constants.insert(item.key(), value);
+                    false
+                }
+                None => true,
+            }
+        });
+        if const_items.len() == before {
+            break;
+        }
+    }
+
+    // Pass 0: type aliases, recorded before any use site so references expand in place.
+    // An alias whose target has no codec only fails the types that use it.
+    for (unit, module, item) in &aliases {
+        let mut ctx = context(module, *unit);
+        ctx.type_params = item
+            .generics
+            .type_params()
+            .map(|param| param.ident.to_string())
+            .collect();
+        let name = join_path(module, &item.ident.to_string());
+        let target = type_to_expr(&item.ty, codegen, &ctx).map_err(|kind| {
+            vec![
+                Diagnostic::new(kind)
+                    .referenced_by(name.clone())
+                    .at(Some(ctx.location(item.ty.span()))),
+            ]
+        });
+        if let Some(doc) = doc_comment(&item.attrs) {
+            codegen.docs.insert(name.clone(), doc);
+        }
+        let defaults = param_defaults(&item.generics, codegen, &ctx);
+        let location = Some(ctx.location(item.ident.span()));
+        codegen.type_aliases.insert(
+            name,
+            TypeAlias {
+                params: ctx.type_params,
+                defaults,
+                target,
+                location,
+            },
+        );
+    }
 
     // Pass 1: remote proxies. `#[rkyv(remote = T)]` types register themselves as with-wrappers and emit no top-level export.
-    // Running this pass first makes proxy usage order-independent within a file.
-    for item in &items {
+    // Running this pass first makes proxy usage order-independent within a crate.
+    for (unit, module, item) in &items {
+        let mut ctx = context(module, *unit);
         if !has_marker_derive(item.attrs(), &ctx, codegen) {
             continue;
         }
@@ -719,6 +2041,7 @@ fn parse_source(
             continue;
         }
         let name = item.ident().to_string();
+        ctx.self_type = Some(join_path(module, &name));
         let built = match item {
             TypeItem::Struct(s) => {
                 extract_struct_shape(&name, &s.fields, codegen, &ctx).map(|shape| match shape {
@@ -732,11 +2055,17 @@ fn parse_source(
         };
         match built {
             Ok(expr) => {
-                if let Some(fq_path) = ctx.imports.get(&name) {
+                // Reachable as written (`with = NaiveDateDef`), through an import of the
+                // same name, and by crate path (`with = super::NaiveDateDef`).
+                if let Some(fq_path) = ctx.import(&name) {
                     codegen
                         .registry
-                        .register_wrapper(fq_path.clone(), WithWrapper::replace(expr.clone()));
+                        .register_wrapper(fq_path, WithWrapper::replace(expr.clone()));
                 }
+                codegen.registry.register_wrapper(
+                    join_path("crate", &join_path(module, &name)),
+                    WithWrapper::replace(expr.clone()),
+                );
                 codegen
                     .registry
                     .register_wrapper(name, WithWrapper::replace(expr));
@@ -755,8 +2084,9 @@ fn parse_source(
         }
     }
 
-    // Pass 2: regular types.
-    for item in &items {
+    // Pass 2: regular types, keyed by their crate-relative path (`api::User`).
+    for (unit, module, item) in &items {
+        let mut ctx = context(module, *unit);
         if !has_marker_derive(item.attrs(), &ctx, codegen) {
             continue;
         }
@@ -764,8 +2094,10 @@ fn parse_source(
         if attrs.remote.is_some() {
             continue;
         }
-        let name = item.ident().to_string();
+        let name = join_path(module, &item.ident().to_string());
         let location = Some(ctx.location(item.ident().span()));
+        ctx.type_params = item.type_params();
+        ctx.self_type = Some(name.clone());
         let extracted = match item {
             TypeItem::Struct(s) => {
                 extract_struct_shape(&name, &s.fields, codegen, &ctx).map(|shape| match shape {
@@ -778,7 +2110,15 @@ fn parse_source(
             }
         };
         match extracted {
-            Ok(kind) => codegen.add_type(name.clone(), kind, location),
+            Ok(kind) => {
+                let defaults = param_defaults(item.generics(), codegen, &ctx);
+                if defaults.iter().any(Option::is_some) {
+                    codegen.type_param_defaults.insert(name.clone(), defaults);
+                }
+                codegen.add_type(name.clone(), kind, location);
+                record_docs(codegen, &name, item);
+                codegen.set_type_params(name.clone(), ctx.type_params.drain(..));
+            }
             Err(diagnostics) => codegen.add_failed_type(name.clone(), diagnostics, location),
         }
         if let Some(archived) = attrs.archived {
@@ -786,7 +2126,8 @@ fn parse_source(
         }
     }
 
-    Ok(())
+    resolve_type_aliases(codegen);
+    fill_default_type_args(codegen);
 }
 
 impl CodeGenerator {
@@ -806,20 +2147,59 @@ impl CodeGenerator {
     /// ```
     pub fn add_source_file(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
         let path = path.as_ref();
+        self.tracked_paths.insert(path.to_path_buf());
         let source = fs::read_to_string(path)?;
-        parse_source(self, &source, Some(path.to_path_buf()))?;
+        let unit = parse_unit(&source, Some(path.to_path_buf()), String::new(), &self.cfg)?;
+        extract_units(self, &[unit]);
         Ok(self)
     }
 
     /// Parse Rust source from a string and extract every type with a marker derive.
     pub fn add_source_str(&mut self, source: &str) -> Result<&mut Self, Error> {
-        parse_source(self, source, None)?;
+        let unit = parse_unit(source, None, String::new(), &self.cfg)?;
+        extract_units(self, &[unit]);
+        Ok(self)
+    }
+
+    /// Extract every type with a marker derive from a crate, starting at its root file
+    /// (`src/lib.rs` or `src/main.rs`) and following `mod foo;` declarations the way rustc does,
+    /// `#[path = "..."]` included.
+    ///
+    /// Types are keyed by their module path, so two modules may declare the same type name;
+    /// see [`archived_name_of`](CodeGenerator::archived_name_of) for how their exports are named.
+    /// Files outside the module tree (integration tests, benches, examples) are never read.
+    ///
+    /// A declared module whose file does not exist is reported as [`Error::Parse`] at the declaration.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// fn main() -> Result<(), rkyv_js_codegen::Error> {
+    ///     CodeGenerator::new()
+    ///         .add_crate_root("src/lib.rs")?
+    ///         .write_to_file("generated/bindings.ts")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_crate_root(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut units = Vec::new();
+        self.tracked_paths.insert(path.to_path_buf());
+        load_module_file(path.to_path_buf(), String::new(), dir, &self.cfg, &mut units)?;
+        self.tracked_paths.extend(units.iter().filter_map(|unit| unit.file.clone()));
+        extract_units(self, &units);
         Ok(self)
     }
 
     /// Recursively scan a directory for `.rs` files and extract every type with a marker derive.
-    /// Files are processed in path order.
+    /// Files are processed in path order, together, so a type may use a constant
+    /// or type alias defined in another file of the directory.
     pub fn add_source_dir(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        // The directory itself, so a file added to it later is picked up too.
+        self.tracked_paths.insert(path.as_ref().to_path_buf());
         let mut files: Vec<PathBuf> = Vec::new();
         for entry in WalkDir::new(path) {
             let entry = entry.map_err(io::Error::other)?;
@@ -829,11 +2209,44 @@ impl CodeGenerator {
             }
         }
         files.sort();
+        let mut units = Vec::new();
         for file in files {
-            self.add_source_file(&file)?;
+            self.tracked_paths.insert(file.clone());
+            let source = fs::read_to_string(&file)?;
+            units.push(parse_unit(&source, Some(file), String::new(), &self.cfg)?);
         }
+        extract_units(self, &units);
         Ok(self)
     }
+
+    /// Every file and directory read by `add_source_*`, [`add_crate_root`](Self::add_crate_root)
+    /// and [`add_config_file`](Self::add_config_file), in path order.
+    pub fn tracked_paths(&self) -> impl Iterator<Item = &Path> {
+        self.tracked_paths.iter().map(PathBuf::as_path)
+    }
+
+    /// Print a `cargo:rerun-if-changed` directive for every [tracked path](Self::tracked_paths),
+    /// so a build script reruns exactly when a schema file or config changes,
+    /// or a file is added to a scanned directory.
+    ///
+    /// ```no_run
+    /// // build.rs
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// fn main() -> Result<(), rkyv_js_codegen::Error> {
+    ///     CodeGenerator::new()
+    ///         .add_crate_root("src/lib.rs")?
+    ///         .emit_cargo_rerun_directives()
+    ///         .write_to_file("generated/bindings.ts")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn emit_cargo_rerun_directives(&self) -> &Self {
+        for path in self.tracked_paths() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -841,6 +2254,7 @@ mod tests {
     use super::*;
     use crate::error::DiagnosticKind;
     use crate::registry::ExternalType;
+    use crate::test_util::TempDir;
 
     fn generate(source: &str) -> String {
         let mut codegen = CodeGenerator::new();
@@ -899,6 +2313,44 @@ mod tests {
         assert!(code.contains("unit: r.unit,"));
     }
 
+    #[test]
+    fn wide_and_pointer_sized_integers() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct Counters { total: u128, delta: i128, len: usize, offset: isize }
+        "#,
+        );
+        assert!(code.contains("total: r.u128,"));
+        assert!(code.contains("delta: r.i128,"));
+        assert!(code.contains("len: r.usize,"));
+        assert!(code.contains("offset: r.isize,"));
+    }
+
+    #[test]
+    fn nonzero_integers_use_the_integer_layout() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use std::num::{NonZeroU32, NonZero};
+            #[derive(Archive)]
+            struct Ids {
+                id: NonZeroU32,
+                parent: Option<NonZeroU32>,
+                wide: core::num::NonZeroI128,
+                index: std::num::NonZeroUsize,
+                generic: NonZero<u16>,
+            }
+        "#,
+        );
+        assert!(code.contains("id: r.u32,"));
+        assert!(code.contains("parent: r.option(r.u32),"));
+        assert!(code.contains("wide: r.i128,"));
+        assert!(code.contains("index: r.usize,"));
+        assert!(code.contains("generic: r.u16,"));
+    }
+
     #[test]
     fn extracts_enum_with_new_variant_shapes() {
         let code = generate(
@@ -924,19 +2376,232 @@ mod tests {
     }
 
     #[test]
-    fn extracts_tuple_struct() {
+    fn explicit_discriminants_become_tagged_enum_options() {
         let code = generate(
             r#"
             use rkyv::Archive;
             #[derive(Archive)]
-            struct Pair(u32, String);
+            #[repr(u16)]
+            enum Status {
+                Pending = 1,
+                Active,
+                Banned { until: u64 } = 0x10,
+                Deleted,
+            }
+            #[derive(Archive)]
+            enum Plain { A = 0, B }
         "#,
         );
-        assert!(code.contains("export const ArchivedPair = r.tuple(r.u32, r.string);"));
+        assert!(code.contains(
+            "  Deleted: null,\n\
+             }, { discriminants: { Pending: 1, Active: 2, Banned: 16, Deleted: 17 } });"
+        ));
+        // Discriminants matching the declaration order need no options.
+        assert!(code.contains("  B: null,\n});"));
     }
 
     #[test]
-    fn cross_references_resolve_to_archived_names() {
+    fn doc_comments_become_jsdoc() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            /// A registered user.
+            ///
+            /// Created on sign-up.
+            #[derive(Archive)]
+            struct Person {
+                /// Primary contact address.
+                email: String,
+                #[doc = " Age in years."]
+                age: u8,
+            }
+            #[derive(Archive)]
+            enum Shape {
+                /** A round shape. */
+                Circle {
+                    /// In metres.
+                    radius: f64,
+                },
+                Empty,
+            }
+        "#,
+        );
+        assert!(code.contains(
+            "/**\n * A registered user.\n *\n * Created on sign-up.\n */\n\
+             export const ArchivedPerson = r.struct({\n\
+             \x20 /** Primary contact address. */\n\
+             \x20 email: r.string,\n\
+             \x20 /** Age in years. */\n\
+             \x20 age: r.u8,\n\
+             });"
+        ));
+        assert!(code.contains("Created on sign-up.\n */\nexport type Person = "));
+        assert!(code.contains(
+            "export const ArchivedShape = r.taggedEnum({\n\
+             \x20 /** A round shape. */\n\
+             \x20 Circle: {\n\
+             \x20   /** In metres. */\n\
+             \x20   radius: r.f64,\n\
+             \x20 },\n\
+             \x20 Empty: null,\n\
+             });"
+        ));
+    }
+
+    #[test]
+    fn discriminants_outside_the_u8_tag_are_diagnostics() {
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            const BASE: i16 = 3;
+            #[derive(Archive)]
+            #[repr(i16)]
+            enum Code {
+                Negative = -1,
+                Wide = 255,
+                Overflow,
+                Named = BASE * 100,
+                Unknown = MISSING,
+            }
+        "#,
+        );
+        let kinds: Vec<_> = diagnostics.iter().map(|d| &d.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &DiagnosticKind::DiscriminantOutOfRange {
+                    value: "-1".to_string()
+                },
+                &DiagnosticKind::DiscriminantOutOfRange {
+                    value: "256".to_string()
+                },
+                &DiagnosticKind::DiscriminantOutOfRange {
+                    value: "300".to_string()
+                },
+                &DiagnosticKind::UnsupportedDiscriminant {
+                    expr: "MISSING".to_string()
+                },
+            ]
+        );
+        assert_eq!(diagnostics[1].referenced_by.as_deref(), Some("Code::Overflow"));
+    }
+
+    #[test]
+    fn constant_expressions_resolve_array_lengths_and_discriminants() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            pub const HASH_LEN: usize = DOUBLE / 2;
+            const DOUBLE: usize = 64;
+
+            pub mod shapes {
+                pub const DIMS: usize = 3;
+            }
+            use shapes::DIMS as D;
+
+            #[derive(Archive)]
+            pub struct Digest {
+                pub hash: [u8; HASH_LEN],
+                pub matrix: [f32; D * 4],
+                pub tail: [u8; shapes::DIMS - 1],
+                pub block: [u8; Self::SIZE],
+                pub nested: [[u8; 2]; { Digest::SIZE / 8 }],
+            }
+
+            impl Digest {
+                pub const SIZE: usize = 1 << 4;
+            }
+
+            #[derive(Archive)]
+            #[repr(u8)]
+            pub enum Level {
+                Low = BASE,
+                High = BASE + 10,
+            }
+            const BASE: u8 = D as u8;
+        "#,
+        );
+        assert!(code.contains(
+            "export const ArchivedDigest = r.struct({\n\
+             \x20 hash: r.array(r.u8, 32),\n\
+             \x20 matrix: r.array(r.f32, 12),\n\
+             \x20 tail: r.array(r.u8, 2),\n\
+             \x20 block: r.array(r.u8, 16),\n\
+             \x20 nested: r.array(r.array(r.u8, 2), 2),\n\
+             });"
+        ));
+        assert!(code.contains("Low: 3"), "{code}");
+        assert!(code.contains("High: 13"), "{code}");
+    }
+
+    #[test]
+    fn registered_constants_resolve_external_paths() {
+        let mut codegen = CodeGenerator::new();
+        codegen
+            .register_const("blake3::OUT_LEN", 32)
+            .register_const("crate::config::WIDTH", 4);
+        codegen
+            .add_source_str(
+                r#"
+                use rkyv::Archive;
+                use blake3::OUT_LEN;
+                #[derive(Archive)]
+                pub struct Entry {
+                    pub hash: [u8; OUT_LEN],
+                    pub row: [u16; crate::config::WIDTH],
+                }
+            "#,
+            )
+            .unwrap();
+        let code = codegen.generate().unwrap();
+        assert!(code.contains("hash: r.array(r.u8, 32),\n  row: r.array(r.u16, 4),"));
+    }
+
+    #[test]
+    fn unresolved_array_lengths_are_diagnostics() {
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            const SMALL: u8 = 200;
+            #[derive(Archive)]
+            pub struct Buffer {
+                pub data: [u8; other_crate::LEN],
+                pub sized: [u8; size_of::<u64>()],
+                pub overflowing: [u8; (SMALL + 100) as usize],
+            }
+        "#,
+        );
+        let kinds: Vec<_> = diagnostics.iter().map(|d| &d.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &DiagnosticKind::UnsupportedArrayLength {
+                    expr: "other_crate :: LEN".to_string()
+                },
+                &DiagnosticKind::UnsupportedArrayLength {
+                    expr: "size_of :: < u64 > ()".to_string()
+                },
+                &DiagnosticKind::UnsupportedArrayLength {
+                    expr: "(SMALL + 100) as usize".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_tuple_struct() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct Pair(u32, String);
+        "#,
+        );
+        assert!(code.contains("export const ArchivedPair = r.tuple(r.u32, r.string);"));
+    }
+
+    #[test]
+    fn cross_references_resolve_to_archived_names() {
         let code = generate(
             r#"
             use rkyv::Archive;
@@ -1149,6 +2814,82 @@ mod tests {
         assert!(code.contains("arc: r.rc(r.string),"));
     }
 
+    #[test]
+    fn results_ranges_and_bounds_keep_their_rust_shape() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use std::ops::{Bound, Range, RangeInclusive};
+            #[derive(Archive)]
+            struct Job {
+                outcome: Result<u64, String>,
+                span: Range<u32>,
+                lines: RangeInclusive<usize>,
+                tail: core::ops::RangeFrom<u32>,
+                lower: Bound<i64>,
+                whole: std::ops::RangeFull,
+            }
+        "#,
+        );
+        assert!(code.contains("outcome: r.taggedEnum({ Ok: r.u64, Err: r.string }),"));
+        assert!(code.contains("span: r.struct({ start: r.u32, end: r.u32 }),"));
+        assert!(code.contains("lines: r.struct({ start: r.usize, end: r.usize }),"));
+        assert!(code.contains("tail: r.struct({ start: r.u32 }),"));
+        assert!(code.contains(
+            "lower: r.taggedEnum({ Included: r.i64, Excluded: r.i64, Unbounded: null }),"
+        ));
+        assert!(code.contains("whole: r.unit,"));
+    }
+
+    #[test]
+    fn local_result_types_shadow_the_prelude() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            enum Result { Pass, Fail }
+            type Fallible<T> = core::result::Result<T, Result>;
+            #[derive(Archive)]
+            struct Report { last: Result, next: Fallible<u8> }
+        "#,
+        );
+        assert!(code.contains("last: ArchivedResult,"), "{code}");
+        assert!(code.contains("next: r.taggedEnum({ Ok: r.u8, Err: ArchivedResult }),"));
+    }
+
+    #[test]
+    fn unsized_pointees_point_at_their_data() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use std::borrow::Cow;
+            use std::rc::Rc;
+            use std::sync::Arc;
+            #[derive(Archive)]
+            struct Compact<'a> {
+                name: Box<str>,
+                tags: Box<[String]>,
+                shared_name: Arc<str>,
+                shared_ids: Rc<[u32]>,
+                points: triomphe::Arc<[(f32, f32)]>,
+                borrowed: &'a [u16],
+                label: Cow<'a, str>,
+                bytes: Cow<'a, [u8]>,
+                owned: Cow<'a, u64>,
+            }
+        "#,
+        );
+        assert!(code.contains("name: r.boxedStr,"));
+        assert!(code.contains("tags: r.vec(r.string),"));
+        assert!(code.contains("shared_name: r.boxedStr,"));
+        assert!(code.contains("shared_ids: r.vec(r.u32),"));
+        assert!(code.contains("points: r.vec(r.tuple(r.f32, r.f32)),"));
+        assert!(code.contains("borrowed: r.vec(r.u16),"));
+        assert!(code.contains("label: r.string,"));
+        assert!(code.contains("bytes: r.vec(r.u8),"));
+        assert!(code.contains("owned: r.u64,"));
+    }
+
     #[test]
     fn renamed_imports_resolve() {
         let code = generate(
@@ -1182,6 +2923,129 @@ mod tests {
         assert!(code.contains("m: hashMap(r.string, r.u32),"));
     }
 
+    #[test]
+    fn type_aliases_expand_at_use_sites() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            pub type UserId = u64;
+            pub type Pair<T> = (T, T);
+            pub type Grid = [[u8; 8]; 8];
+            pub type Ids<T = UserId> = Vec<T>;
+            pub type Named<T> = Pair<Ids<T>>;
+            pub type Origin = Point;
+            #[derive(Archive)]
+            pub struct Point { pub x: f64 }
+            #[derive(Archive)]
+            pub struct Board {
+                pub owner: UserId,
+                pub corners: Pair<u32>,
+                pub cells: Grid,
+                pub players: Named<UserId>,
+                pub origin: Origin,
+                pub watchers: Ids,
+            }
+        "#,
+        );
+        assert!(code.contains("owner: r.u64,"));
+        assert!(code.contains("corners: r.tuple(r.u32, r.u32),"));
+        assert!(code.contains("cells: r.array(r.array(r.u8, 8), 8),"));
+        assert!(code.contains("players: r.tuple(r.vec(r.u64), r.vec(r.u64)),"));
+        assert!(code.contains("origin: ArchivedPoint,"));
+        assert!(code.contains("watchers: r.vec(r.u64),"));
+        assert!(!code.contains("ArchivedUserId"));
+    }
+
+    #[test]
+    fn type_aliases_resolve_across_modules_and_sources() {
+        let mut codegen = CodeGenerator::new();
+        codegen
+            .add_source_str(
+                r#"
+                use rkyv::Archive;
+                use crate::ids::*;
+                #[derive(Archive)]
+                pub struct Session { pub user: UserId, pub token: Token, pub org: ids::OrgId }
+                mod ids {
+                    pub type UserId = u32;
+                    pub type OrgId = UserId;
+                }
+            "#,
+            )
+            .unwrap();
+        codegen
+            .add_source_str("pub type Token = [u8; 16];")
+            .unwrap();
+        let code = codegen.generate().unwrap();
+        assert!(code.contains("user: r.u32,\n  token: r.array(r.u8, 16),\n  org: r.u32,"));
+    }
+
+    #[test]
+    fn named_aliases_emit_referenced_aliases() {
+        let mut codegen = CodeGenerator::new();
+        codegen.set_named_aliases(true);
+        codegen
+            .add_source_str(
+                r#"
+                use rkyv::Archive;
+                /// A user's primary key.
+                pub type UserId = u64;
+                pub type Pair<T> = (T, T);
+                pub type Unused = String;
+                pub type Ids<T = UserId> = Vec<T>;
+                #[derive(Archive)]
+                pub struct Edge { pub ends: Pair<UserId>, pub hops: Ids }
+            "#,
+            )
+            .unwrap();
+        let code = codegen.generate().unwrap();
+        assert!(
+            code.contains("/** A user's primary key. */\nexport const ArchivedUserId = r.u64;")
+        );
+        assert!(code.contains(
+            "export const ArchivedPair = <T extends r.AnyCodec>(t: T) => r.tuple(t, t);"
+        ));
+        assert!(code.contains("ends: ArchivedPair(ArchivedUserId),"));
+        assert!(code.contains("hops: ArchivedIds(ArchivedUserId),"));
+        assert!(!code.contains("Unused"));
+    }
+
+    #[test]
+    fn alias_without_codec_fails_only_its_users() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            pub type Callback = fn(u32);
+            #[derive(Archive)]
+            pub struct Fine { pub id: u32 }
+        "#,
+        );
+        assert!(code.contains("ArchivedFine"));
+
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            pub type Callback = fn(u32);
+            pub type Pair<T> = (T, T);
+            #[derive(Archive)]
+            pub struct Handlers { pub on_click: Callback, pub pair: Pair<u8, u8> }
+        "#,
+        );
+        assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+        assert!(matches!(
+            &diagnostics[0].kind,
+            DiagnosticKind::UnsupportedFieldType { rust_type } if rust_type == "fn (u32)"
+        ));
+        assert_eq!(
+            diagnostics[0].referenced_by.as_deref(),
+            Some("Handlers.on_click")
+        );
+        assert!(matches!(
+            &diagnostics[1].kind,
+            DiagnosticKind::GenericArity { rust_path, expected: 1, found: 2 } if rust_path == "Pair"
+        ));
+    }
+
     #[test]
     fn inline_trailing_hasher_is_allowed() {
         let code = generate(
@@ -1195,6 +3059,51 @@ mod tests {
         assert!(code.contains("m: hashMap(r.string, r.u32),"));
     }
 
+    #[test]
+    fn phantom_data_is_a_unit() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use std::marker::PhantomData;
+            #[derive(Archive)]
+            struct Id<T> { raw: u64, _m: PhantomData<T> }
+            #[derive(Archive)]
+            struct Tagged<T> { _m: core::marker::PhantomData<fn() -> T> }
+            #[derive(Archive)]
+            struct User { id: Id<User> }
+        "#,
+        );
+        assert!(code.contains("  raw: r.u64,\n  _m: r.unit,\n"));
+        assert!(code.contains("id: ArchivedId(r.lazy((): r.AnyCodec => ArchivedUser)),"));
+        assert!(code.contains("export const ArchivedTagged = <T extends r.AnyCodec>(t: T) => r.struct({\n  _m: r.unit,\n});"));
+    }
+
+    #[test]
+    fn omitted_generic_arguments_take_their_defaults() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct Defaulted<T = u32> { value: T }
+            #[derive(Archive)]
+            struct Pair<A, B = Vec<A>> { a: A, b: B }
+            #[derive(Archive)]
+            struct Data {
+                d: Defaulted,
+                explicit: Defaulted<String>,
+                pair: Pair<u8>,
+                nested: Option<Pair<Defaulted>>,
+            }
+        "#,
+        );
+        assert!(code.contains("d: ArchivedDefaulted(r.u32),"));
+        assert!(code.contains("explicit: ArchivedDefaulted(r.string),"));
+        assert!(code.contains("pair: ArchivedPair(r.u8, r.vec(r.u8)),"));
+        assert!(code.contains(
+            "nested: r.option(ArchivedPair(ArchivedDefaulted(r.u32), r.vec(ArchivedDefaulted(r.u32)))),"
+        ));
+    }
+
     #[test]
     fn generic_arity_too_few_args() {
         let diagnostics = generate_diagnostics(
@@ -1378,12 +3287,18 @@ mod tests {
                 #[rkyv(with = rkyv::with::Inline)]
                 value: u32,
                 #[rkyv(with = rkyv::with::InlineAsBox)]
-                other: u64,
+                other: &'static u64,
+                #[rkyv(with = rkyv::with::InlineAsBox)]
+                text: &'static str,
+                #[rkyv(with = rkyv::with::InlineAsBox)]
+                items: &'static [u8],
             }
         "#,
         );
         assert!(code.contains("value: r.u32,"));
         assert!(code.contains("other: r.box(r.u64),"));
+        assert!(code.contains("text: r.boxedStr,"));
+        assert!(code.contains("items: r.vec(r.u8),"));
     }
 
     #[test]
@@ -1404,6 +3319,257 @@ mod tests {
         assert!(!code.contains("dropped"));
     }
 
+    #[test]
+    fn durations_and_unix_times_use_the_duration_codecs() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use rkyv::with::AsUnixTime;
+            use std::time::{Duration, SystemTime};
+            #[derive(Archive)]
+            struct Event {
+                #[rkyv(with = AsUnixTime)]
+                at: SystemTime,
+                took: Duration,
+                timeout: Option<core::time::Duration>,
+            }
+        "#,
+        );
+        assert!(code.contains("import { duration, unixTime } from 'rkyv-js/lib/duration';"));
+        assert!(code.contains("at: unixTime,"));
+        assert!(code.contains("took: duration,"));
+        assert!(code.contains("timeout: r.option(duration),"));
+    }
+
+    #[test]
+    fn net_addresses_and_c_strings_use_the_lib_codecs() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use std::collections::HashMap;
+            use std::ffi::CString;
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+            #[derive(Archive)]
+            struct Peer {
+                ip: IpAddr,
+                gateway: Option<Ipv4Addr>,
+                addr: SocketAddr,
+                local: core::net::SocketAddrV6,
+                hits: HashMap<IpAddr, u32>,
+                name: CString,
+            }
+        "#,
+        );
+        assert!(code.contains(
+            "import { ipAddr, ipv4Addr, socketAddr, socketAddrV6 } from 'rkyv-js/lib/net';"
+        ));
+        assert!(code.contains("import { cString } from 'rkyv-js/lib/ffi';"));
+        assert!(code.contains("ip: ipAddr,"));
+        assert!(code.contains("gateway: r.option(ipv4Addr),"));
+        assert!(code.contains("addr: socketAddr,"));
+        assert!(code.contains("local: socketAddrV6,"));
+        assert!(code.contains("hits: hashMap(ipAddr, r.u32),"));
+        assert!(code.contains("name: cString,"));
+    }
+
+    #[test]
+    fn with_map_wrappers_apply_to_elements_keys_and_values() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use rkyv::with::{AsBox, Identity, Map, MapKV};
+            use std::collections::{BTreeMap, HashMap};
+            #[derive(Archive)]
+            struct Data {
+                #[rkyv(with = Map<AsBox>)]
+                maybe: Option<String>,
+                #[rkyv(with = Map<AsBox>)]
+                list: Vec<u32>,
+                #[rkyv(with = Map<AsBox>)]
+                fixed: [u8; 2],
+                #[rkyv(with = Map<Map<AsBox>>)]
+                nested: Vec<Option<u16>>,
+                #[rkyv(with = MapKV<Identity, AsBox>)]
+                hashed: HashMap<String, u64>,
+                #[rkyv(with = MapKV<AsBox, Identity>)]
+                sorted: BTreeMap<u8, bool>,
+            }
+        "#,
+        );
+        assert!(code.contains("maybe: r.option(r.box(r.string)),"));
+        assert!(code.contains("list: r.vec(r.box(r.u32)),"));
+        assert!(code.contains("fixed: r.array(r.box(r.u8), 2),"));
+        assert!(code.contains("nested: r.vec(r.option(r.box(r.u16))),"));
+        assert!(code.contains("hashed: hashMap(r.string, r.box(r.u64)),"));
+        assert!(code.contains("sorted: btreeMap(r.box(r.u8), r.bool),"));
+    }
+
+    #[test]
+    fn with_unwrapping_wrappers_archive_the_inner_type() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use rkyv::with::{AsOwned, AsString, Lock, Unsafe, Unshare};
+            use std::borrow::Cow;
+            use std::cell::Cell;
+            use std::path::PathBuf;
+            use std::rc::Rc;
+            use std::sync::{Arc, Mutex, RwLock};
+            #[derive(Archive)]
+            struct Data<'a> {
+                #[rkyv(with = Lock)]
+                guarded: Mutex<u32>,
+                #[rkyv(with = Lock)]
+                shared: RwLock<Vec<u8>>,
+                #[rkyv(with = Unsafe)]
+                cell: Cell<i16>,
+                #[rkyv(with = Unshare)]
+                counted: Rc<String>,
+                #[rkyv(with = Unshare)]
+                atomic: Arc<u64>,
+                #[rkyv(with = AsOwned)]
+                text: Cow<'a, str>,
+                #[rkyv(with = AsOwned)]
+                bytes: Cow<'a, [u8]>,
+                #[rkyv(with = AsString)]
+                path: PathBuf,
+            }
+        "#,
+        );
+        assert!(code.contains("guarded: r.u32,"));
+        assert!(code.contains("shared: r.vec(r.u8),"));
+        assert!(code.contains("cell: r.i16,"));
+        assert!(code.contains("counted: r.string,"));
+        assert!(code.contains("atomic: r.u64,"));
+        assert!(code.contains("text: r.string,"));
+        assert!(code.contains("bytes: r.vec(r.u8),"));
+        assert!(code.contains("path: r.string,"));
+    }
+
+    #[test]
+    fn with_as_vec_and_atomic_load() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use rkyv::with::{AsVec, AtomicLoad, Relaxed};
+            use std::collections::{BTreeSet, HashMap};
+            use std::sync::atomic::{AtomicBool, AtomicU32};
+            #[derive(Archive)]
+            struct Data<'a> {
+                #[rkyv(with = AsVec)]
+                map: HashMap<String, u32>,
+                #[rkyv(with = AsVec)]
+                set: BTreeSet<u8>,
+                #[rkyv(with = AsVec)]
+                slice: &'a [u16],
+                #[rkyv(with = AtomicLoad<Relaxed>)]
+                count: AtomicU32,
+                #[rkyv(with = AtomicLoad<Relaxed>)]
+                flag: AtomicBool,
+            }
+        "#,
+        );
+        assert!(code.contains("map: r.vec(r.struct({ key: r.string, value: r.u32 })),"));
+        assert!(code.contains("set: r.vec(r.u8),"));
+        assert!(code.contains("slice: r.vec(r.u16),"));
+        assert!(code.contains("count: r.u32,"));
+        assert!(code.contains("flag: r.bool,"));
+        assert!(!code.contains("hashMap"));
+    }
+
+    #[test]
+    fn with_wrapper_on_an_unsupported_shape_is_a_diagnostic() {
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            use rkyv::with::{AsBox, Map};
+            #[derive(Archive)]
+            struct Data {
+                #[rkyv(with = Map<AsBox>)]
+                value: u32,
+            }
+        "#,
+        );
+        assert!(matches!(
+            &diagnostics[0].kind,
+            DiagnosticKind::UnsupportedWrapperTarget { wrapper_path, rust_type }
+                if wrapper_path == "rkyv::with::Map" && rust_type == "u32"
+        ));
+        assert_eq!(diagnostics[0].referenced_by.as_deref(), Some("Data.value"));
+    }
+
+    #[test]
+    fn niche_wrappers_emit_niched_options() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use rkyv::niche::niching::{Bool, NaN, Null, Zero};
+            use rkyv::with::{AsBox, DefaultNiche, MapNiche, Niche, NicheInto};
+            use std::num::{NonZeroU32, NonZeroI64};
+            #[derive(Archive)]
+            struct Tight {
+                #[rkyv(with = Niche)]
+                id: Option<NonZeroU32>,
+                #[rkyv(with = Niche)]
+                next: Option<Box<Tight>>,
+                #[rkyv(with = NicheInto<Zero>)]
+                delta: Option<NonZeroI64>,
+                #[rkyv(with = NicheInto<NaN>)]
+                ratio: Option<f32>,
+                #[rkyv(with = NicheInto<Bool>)]
+                flag: Option<bool>,
+                #[rkyv(with = NicheInto<Null>)]
+                label: Option<Box<String>>,
+                #[rkyv(with = DefaultNiche)]
+                enabled: Option<bool>,
+                #[rkyv(with = MapNiche<AsBox>)]
+                payload: Option<u64>,
+                #[rkyv(with = Niche)]
+                note: Option<Box<str>>,
+                #[rkyv(with = Niche)]
+                blob: Option<Box<[u8]>>,
+            }
+        "#,
+        );
+        assert!(code.contains("id: r.niched(r.u32, r.niching.zero),"));
+        assert!(code.contains("next: r.niched(r.box(r.lazy((): r.AnyCodec => ArchivedTight)), r.niching.null),"));
+        assert!(code.contains("delta: r.niched(r.i64, r.niching.zero),"));
+        assert!(code.contains("ratio: r.niched(r.f32, r.niching.nan),"));
+        assert!(code.contains("flag: r.niched(r.bool, r.niching.bool),"));
+        assert!(code.contains("label: r.niched(r.box(r.string), r.niching.null),"));
+        assert!(code.contains("enabled: r.niched(r.bool, r.niching.bool),"));
+        assert!(code.contains("payload: r.niched(r.box(r.u64), r.niching.null),"));
+        assert!(code.contains("note: r.niched(r.boxedStr, r.niching.null),"));
+        assert!(code.contains("blob: r.niched(r.vec(r.u8), r.niching.null),"));
+    }
+
+    #[test]
+    fn niche_without_a_matching_niche_is_a_diagnostic() {
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            use rkyv::niche::niching::Zero;
+            use rkyv::with::{DefaultNiche, Niche, NicheInto};
+            #[derive(Archive)]
+            struct Data {
+                #[rkyv(with = Niche)]
+                plain: Option<u32>,
+                #[rkyv(with = NicheInto<Zero>)]
+                float: Option<f64>,
+                #[rkyv(with = DefaultNiche)]
+                other: Option<f32>,
+            }
+        "#,
+        );
+        assert_eq!(diagnostics.len(), 3);
+        for diagnostic in &diagnostics {
+            assert!(matches!(
+                diagnostic.kind,
+                DiagnosticKind::UnsupportedWrapperTarget { .. }
+            ));
+        }
+    }
+
     #[test]
     fn with_unknown_wrapper_is_a_diagnostic() {
         let diagnostics = generate_diagnostics(
@@ -1589,6 +3755,491 @@ mod tests {
         assert!(!code.contains("r#"));
     }
 
+    #[test]
+    fn generic_types_become_codec_factories() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct User { id: u32 }
+            #[derive(Archive)]
+            struct Page<T> { items: Vec<T>, total: u64 }
+            #[derive(Archive)]
+            enum Either<L, R> { Left(L), Right(Box<R>) }
+            #[derive(Archive)]
+            struct Response { users: Page<User>, names: Page<String>, pick: Either<u8, User> }
+        "#,
+        );
+        assert!(code.contains(
+            "export const ArchivedPage = <T extends r.AnyCodec>(t: T) => r.struct({\n\
+             \x20 items: r.vec(t),\n\
+             \x20 total: r.u64,\n\
+             });"
+        ));
+        assert!(code.contains(
+            "export type Page<T> = r.Infer<ReturnType<typeof ArchivedPage<r.Codec<T>>>>;"
+        ));
+        assert!(code.contains(
+            "export const ArchivedEither = <L extends r.AnyCodec, R extends r.AnyCodec>(l: L, r_: R) => r.taggedEnum({"
+        ));
+        assert!(code.contains("Right: r.box(r_),"));
+        assert!(code.contains("users: ArchivedPage(ArchivedUser),"));
+        assert!(code.contains("names: ArchivedPage(r.string),"));
+        assert!(code.contains("pick: ArchivedEither(r.u8, ArchivedUser),"));
+        // Factories are emitted before their instantiations.
+        assert!(code.find("ArchivedPage =").unwrap() < code.find("ArchivedResponse =").unwrap());
+    }
+
+    #[test]
+    fn type_params_shadow_imports() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            use uuid::Uuid as T;
+            #[derive(Archive)]
+            struct Wrapper<T> { value: T }
+            #[derive(Archive)]
+            struct Plain { id: T }
+        "#,
+        );
+        assert!(code.contains("value: t,"));
+        assert!(code.contains("id: uuid,"));
+    }
+
+    #[test]
+    fn lifetimes_and_const_params_are_not_codec_params() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct Tagged<'a, T, const N: usize> { value: T, name: &'a str }
+        "#,
+        );
+        assert!(code.contains("export const ArchivedTagged = <T extends r.AnyCodec>(t: T) =>"));
+    }
+
+    #[test]
+    fn generic_use_site_arity_is_checked() {
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct Page<T> { items: Vec<T> }
+            #[derive(Archive)]
+            struct Point { x: f64 }
+            #[derive(Archive)]
+            struct Feed { bare: Page, extra: Point<u8> }
+        "#,
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().any(|diagnostic| matches!(
+            &diagnostic.kind,
+            DiagnosticKind::GenericArity { rust_path, expected: 1, found: 0 }
+                if rust_path == "Page"
+        ) && diagnostic.referenced_by.as_deref() == Some("Feed.bare")));
+        assert!(diagnostics.iter().any(|diagnostic| matches!(
+            &diagnostic.kind,
+            DiagnosticKind::GenericArity { rust_path, expected: 0, found: 1 }
+                if rust_path == "Point"
+        )));
+    }
+
+    #[test]
+    fn inline_modules_resolve_qualified_paths() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            mod geo {
+                use rkyv::Archive;
+                #[derive(Archive)]
+                pub struct Point { x: f64, y: f64 }
+                #[derive(Archive)]
+                pub struct Line { from: self::Point, to: Point }
+                pub mod shapes {
+                    use rkyv::Archive;
+                    use super::*;
+                    #[derive(Archive)]
+                    pub struct Polygon { points: Vec<Point>, edges: Vec<super::Line> }
+                }
+            }
+            #[derive(Archive)]
+            struct Drawing {
+                origin: geo::Point,
+                outline: crate::geo::shapes::Polygon,
+            }
+        "#,
+        );
+        assert!(code.contains("export const ArchivedPoint = r.struct({"));
+        assert!(code.contains("  from: ArchivedPoint,\n  to: ArchivedPoint,\n"));
+        assert!(code.contains("  points: r.vec(ArchivedPoint),\n  edges: r.vec(ArchivedLine),\n"));
+        assert!(code.contains("  origin: ArchivedPoint,\n  outline: ArchivedPolygon,\n"));
+        assert!(code.contains("export type Polygon = r.Infer<typeof ArchivedPolygon>;"));
+    }
+
+    #[test]
+    fn same_named_types_in_different_modules_are_disambiguated() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            mod api {
+                use rkyv::Archive;
+                #[derive(Archive)]
+                pub struct User { name: String }
+            }
+            mod admin {
+                use rkyv::Archive;
+                use crate::api;
+                #[derive(Archive)]
+                pub struct User { inner: api::User, level: u8 }
+            }
+            #[derive(Archive)]
+            struct Session { user: admin::User }
+        "#,
+        );
+        assert!(code.contains("export const ArchivedApiUser = r.struct({"));
+        assert!(code.contains("export type ApiUser = r.Infer<typeof ArchivedApiUser>;"));
+        assert!(code.contains("  inner: ArchivedApiUser,\n"));
+        assert!(code.contains("export type AdminUser = r.Infer<typeof ArchivedAdminUser>;"));
+        assert!(code.contains("  user: ArchivedAdminUser,\n"));
+        assert!(!code.contains("ArchivedUser "));
+    }
+
+    #[test]
+    fn disambiguated_names_that_still_collide_are_reported() {
+        let diagnostics = generate_diagnostics(
+            r#"
+            use rkyv::Archive;
+            mod api {
+                use rkyv::Archive;
+                #[derive(Archive)]
+                pub struct User { name: String }
+            }
+            #[derive(Archive)]
+            struct User { id: u32 }
+            #[derive(Archive)]
+            struct ApiUser { id: u32 }
+        "#,
+        );
+        assert!(diagnostics.iter().any(|diagnostic| matches!(
+            &diagnostic.kind,
+            DiagnosticKind::NameCollision { emitted, originals }
+                if emitted == "ArchivedApiUser" && originals.len() == 2
+        )));
+    }
+
+    #[test]
+    fn remote_proxies_resolve_through_module_paths() {
+        let code = generate(
+            r#"
+            use rkyv::Archive;
+            mod proxies {
+                use rkyv::Archive;
+                #[derive(Archive)]
+                #[rkyv(remote = remote::Coord)]
+                pub struct CoordDef { x: f32, y: f32 }
+            }
+            mod model {
+                use rkyv::Archive;
+                #[derive(Archive)]
+                pub struct Event {
+                    #[rkyv(with = super::proxies::CoordDef)]
+                    location: remote::Coord,
+                }
+            }
+        "#,
+        );
+        assert!(code.contains("location: r.struct({ x: r.f32, y: r.f32 }),"));
+    }
+
+    fn generate_with_cfg(cfg: CfgSet, source: &str) -> String {
+        let mut codegen = CodeGenerator::new();
+        codegen.set_cfg(cfg);
+        codegen.add_source_str(source).unwrap();
+        codegen.generate().unwrap()
+    }
+
+    #[test]
+    fn cfg_attr_derive_marks_types_only_when_active() {
+        let source = r#"
+            use rkyv::Archive;
+            #[cfg_attr(feature = "rkyv", derive(Archive))]
+            #[cfg_attr(feature = "rkyv", rkyv(archived = WirePoint))]
+            struct Point { x: f64 }
+        "#;
+        let mut cfg = CfgSet::new();
+        cfg.enable_feature("rkyv");
+        let code = generate_with_cfg(cfg, source);
+        assert!(code.contains("export const WirePoint = r.struct({"));
+
+        let code = generate_with_cfg(CfgSet::new(), source);
+        assert!(!code.contains("Point"));
+    }
+
+    #[test]
+    fn cfg_drops_inactive_fields_variants_and_items() {
+        let mut cfg = CfgSet::new();
+        cfg.enable("unix");
+        let code = generate_with_cfg(
+            cfg,
+            r#"
+            use rkyv::Archive;
+            #[derive(Archive)]
+            struct Handle {
+                id: u32,
+                #[cfg(unix)]
+                fd: i32,
+                #[cfg(windows)]
+                socket: u64,
+                #[cfg_attr(unix, rkyv(with = rkyv::with::AsBox))]
+                name: String,
+            }
+            #[derive(Archive)]
+            enum Event {
+                Open,
+                #[cfg(windows)]
+                Suspend,
+                Close(u8, #[cfg(not(unix))] u16),
+            }
+            #[cfg(windows)]
+            #[derive(Archive)]
+            struct Handle { broken: Unknown }
+            #[cfg(test)]
+            mod tests {
+                use rkyv::Archive;
+                #[derive(Archive)]
+                struct Fixture { broken: Unknown }
+            }
+        "#,
+        );
+        assert!(code.contains("  id: r.u32,\n  fd: r.i32,\n  name: r.box(r.string),\n})"));
+        assert!(code.contains("  Open: null,\n  Close: r.u8,\n})"));
+        assert!(!code.contains("Fixture"));
+    }
+
+    #[test]
+    fn crate_root_follows_mod_declarations() {
+        let root = TempDir::with_files(
+            "follow",
+            &[
+                (
+                    "src/lib.rs",
+                    r#"
+                    use rkyv::Archive;
+                    mod api;
+                    mod admin;
+                    #[path = "legacy/schema.rs"]
+                    mod old;
+                    mod inline {
+                        #[path = "deep.rs"]
+                        pub mod deep;
+                    }
+                    #[derive(Archive)]
+                    struct Session {
+                        user: admin::User,
+                        token: api::v1::Token,
+                        legacy: old::nested::Legacy,
+                        deep: inline::deep::Deep,
+                    }
+                "#,
+                ),
+                ("src/api.rs", "pub mod v1;"),
+                (
+                    "src/api/v1.rs",
+                    r#"
+                    use rkyv::Archive;
+                    #[derive(Archive)]
+                    pub struct Token { value: u64 }
+                    #[derive(Archive)]
+                    pub struct User { name: String }
+                "#,
+                ),
+                (
+                    "src/admin/mod.rs",
+                    r#"
+                    use rkyv::Archive;
+                    #[derive(Archive)]
+                    pub struct User { inner: crate::api::v1::User, level: u8 }
+                "#,
+                ),
+                ("src/legacy/schema.rs", "pub mod nested;"),
+                (
+                    "src/legacy/nested.rs",
+                    r#"
+                    use rkyv::Archive;
+                    #[derive(Archive)]
+                    pub struct Legacy { id: u32 }
+                "#,
+                ),
+                (
+                    "src/inline/deep.rs",
+                    r#"
+                    use rkyv::Archive;
+                    #[derive(Archive)]
+                    pub struct Deep { id: u32 }
+                "#,
+                ),
+                // Never declared as a module: must not be read.
+                (
+                    "tests/it.rs",
+                    r#"
+                    use rkyv::Archive;
+                    #[derive(Archive)]
+                    struct Session { broken: Unknown }
+                "#,
+                ),
+            ],
+        );
+        let mut codegen = CodeGenerator::new();
+        codegen.add_crate_root(root.join("src/lib.rs")).unwrap();
+        let code = codegen.generate().unwrap();
+
+        assert!(code.contains("  inner: ArchivedApiV1User,\n"));
+        assert!(code.contains("export type AdminUser = r.Infer<typeof ArchivedAdminUser>;"));
+        assert!(code.contains(
+            "  user: ArchivedAdminUser,\n  token: ArchivedToken,\n  legacy: ArchivedLegacy,\n  \
+             deep: ArchivedDeep,\n"
+        ));
+        assert!(!code.contains("broken"));
+    }
+
+    #[test]
+    fn read_files_and_directories_are_tracked() {
+        let root = TempDir::with_files(
+            "track",
+            &[
+                ("src/lib.rs", "mod api;
+#[cfg(test)]
+mod tests;
+"),
+                ("src/api/mod.rs", "#[derive(rkyv::Archive)]
+pub struct User { id: u32 }
+"),
+                ("schema/a.rs", "#[derive(rkyv::Archive)]
+pub struct A { id: u32 }
+"),
+                ("schema/nested/b.rs", "#[derive(rkyv::Archive)]
+pub struct B { id: u32 }
+"),
+                ("schema/README.md", "not a source"),
+            ],
+        );
+        let mut codegen = CodeGenerator::new();
+        codegen
+            .add_crate_root(root.join("src/lib.rs"))
+            .unwrap()
+            .add_source_dir(root.join("schema"))
+            .unwrap();
+        let tracked: Vec<String> = codegen
+            .tracked_paths()
+            .map(|path| path.strip_prefix(&root).unwrap().display().to_string())
+            .collect();
+
+        assert_eq!(
+            tracked,
+            [
+                "schema",
+                "schema/a.rs",
+                "schema/nested/b.rs",
+                "src/api/mod.rs",
+                "src/lib.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn source_dir_files_share_constants_and_aliases() {
+        let root = TempDir::with_files(
+            "shared",
+            &[
+                (
+                    "schema/a.rs",
+                    "#[derive(rkyv::Archive)]\npub struct Token { id: Id, bytes: [u8; TOKEN_LEN] }\n",
+                ),
+                (
+                    "schema/b.rs",
+                    "pub const TOKEN_LEN: usize = 16;\npub type Id = u64;\n",
+                ),
+            ],
+        );
+        let mut codegen = CodeGenerator::new();
+        codegen.add_source_dir(root.join("schema")).unwrap();
+
+        let output = codegen.generate().unwrap();
+        assert!(output.contains("id: r.u64,"), "{output}");
+        assert!(output.contains("bytes: r.array(r.u8, 16),"), "{output}");
+    }
+
+    #[test]
+    fn source_dir_files_keep_their_own_imports() {
+        let root = TempDir::with_files(
+            "imports",
+            &[
+                (
+                    "schema/a.rs",
+                    "use std::collections::HashMap as Map;\nuse std::sync::Arc;\n\
+                     #[derive(rkyv::Archive)]\npub struct A { m: Map<String, u32>, s: Arc<str> }\n",
+                ),
+                (
+                    "schema/b.rs",
+                    "use std::collections::BTreeMap as Map;\n\
+                     #[derive(rkyv::Archive)]\npub struct B { m: Map<String, u32> }\n",
+                ),
+            ],
+        );
+        let mut codegen = CodeGenerator::new();
+        codegen.add_source_dir(root.join("schema")).unwrap();
+
+        let output = codegen.generate().unwrap();
+        assert!(output.contains("m: hashMap(r.string, r.u32),"), "{output}");
+        assert!(output.contains("m: btreeMap(r.string, r.u32),"), "{output}");
+        assert!(output.contains("s: r.boxedStr,"), "{output}");
+    }
+
+    #[test]
+    fn crate_root_skips_files_of_inactive_modules() {
+        let root = TempDir::with_files(
+            "cfg",
+            &[(
+                "src/lib.rs",
+                "#[cfg(test)]\nmod tests;\n#[cfg_attr(feature = \"legacy\", path = \"old.rs\")]\nmod schema;\n",
+            ), (
+                "src/old.rs",
+                "#[derive(rkyv::Archive)]\npub struct Old { id: u32 }\n",
+            ), (
+                "src/schema.rs",
+                "#[derive(rkyv::Archive)]\npub struct New { id: u64 }\n",
+            )],
+        );
+        let mut cfg = CfgSet::new();
+        cfg.enable_feature("legacy");
+        let mut codegen = CodeGenerator::new();
+        codegen.set_cfg(cfg).add_crate_root(root.join("src/lib.rs")).unwrap();
+        let code = codegen.generate().unwrap();
+
+        // `tests.rs` does not exist; the module is never looked up.
+        assert!(code.contains("export const ArchivedOld"));
+        assert!(!code.contains("ArchivedNew"));
+    }
+
+    #[test]
+    fn crate_root_reports_missing_module_files() {
+        let root = TempDir::with_files(
+            "missing",
+            &[("src/lib.rs", "mod present;\nmod absent;\n"), ("src/present.rs", "")],
+        );
+        let error = CodeGenerator::new()
+            .add_crate_root(root.join("src/lib.rs"))
+            .map(|_| ())
+            .unwrap_err();
+
+        let Error::Parse { file, source } = error else {
+            panic!("expected a parse error, got {error:?}");
+        };
+        assert_eq!(file, Some(root.join("src/lib.rs")));
+        assert!(source.to_string().contains("file not found for module `absent`"));
+        assert_eq!(source.span().start().line, 2);
+    }
+
     #[test]
     fn custom_external_type() {
         let mut codegen = CodeGenerator::new();
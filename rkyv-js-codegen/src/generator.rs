@@ -2,11 +2,13 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::casing::Casing;
+use crate::cfg::CfgSet;
+use crate::consts;
 use crate::error::{Diagnostic, DiagnosticKind, Error, SourceLocation};
-use crate::expr::{CodecExpr, generate_import_block};
+use crate::expr::{CodecExpr, generate_import_block, param_ident};
 use crate::registry::{ExternalType, Registry, WithWrapper};
 
 /// How to handle a field whose type cannot be mapped to a codec.
@@ -20,6 +22,42 @@ pub enum OnUnknown {
     SkipContainingType,
 }
 
+/// How the value type of each codec is exported; see [`CodeGenerator::set_type_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TypeStyle {
+    /// `export type X = r.Infer<typeof ArchivedX>`: the type follows from the codec.
+    #[default]
+    Inferred,
+    /// A written-out `interface` or discriminated-union `type` per type,
+    /// referencing other generated types by name, with each codec annotated as `r.Codec<X>`.
+    Declared,
+}
+
+/// How a field-less enum is emitted; see [`CodeGenerator::set_unit_enum_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitEnumStyle {
+    /// `r.taggedEnum({ A: null, ... })`, decoding to `{ tag: 'A', value: null }` objects.
+    #[default]
+    Tagged,
+    /// `r.stringEnum(...)`, decoding to the variant name, plus an exported
+    /// `as const` object mapping each variant to its name.
+    String,
+    /// `r.numberEnum(...)`, decoding to the discriminant, plus an exported
+    /// `as const` object mapping each variant to its discriminant.
+    Number,
+}
+
+/// How address and C string types are decoded; see [`CodeGenerator::set_net_style`]
+/// and [`CodeGenerator::set_c_string_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextStyle {
+    /// Their text: `192.168.0.1` or `2001:db8::1` for addresses, the UTF-8 contents for C strings.
+    #[default]
+    String,
+    /// Their bytes as a `Uint8Array`: the address octets, or the C string without its nul.
+    Bytes,
+}
+
 /// An enum variant for [`CodeGenerator::add_enum`].
 #[derive(Debug, Clone)]
 pub enum EnumVariant {
@@ -46,14 +84,120 @@ impl EnumVariant {
     }
 }
 
+/// An enum variant together with its discriminant, the value rkyv writes in the archived `u8` tag.
+#[derive(Debug, Clone)]
+pub(crate) struct VariantDef {
+    pub(crate) variant: EnumVariant,
+    pub(crate) discriminant: u8,
+}
+
+impl VariantDef {
+    /// Variants tagged by declaration index, Rust's default.
+    pub(crate) fn indexed(variants: impl IntoIterator<Item = EnumVariant>) -> Vec<VariantDef> {
+        variants
+            .into_iter()
+            .enumerate()
+            .map(|(index, variant)| VariantDef {
+                variant,
+                discriminant: index as u8,
+            })
+            .collect()
+    }
+
+    /// The `{ discriminants: { ... } }` options of `r.taggedEnum`, when any
+    /// variant's tag differs from its declaration index.
+    pub(crate) fn options_expr(variants: &[VariantDef], casing: Casing) -> Option<CodecExpr> {
+        let indexed = variants
+            .iter()
+            .enumerate()
+            .all(|(index, def)| usize::from(def.discriminant) == index);
+        if indexed {
+            return None;
+        }
+        let discriminants = variants.iter().map(|def| {
+            (
+                casing.apply(def.variant.name()),
+                CodecExpr::LitInt(u64::from(def.discriminant)),
+            )
+        });
+        Some(CodecExpr::object([(
+            "discriminants",
+            CodecExpr::object(discriminants),
+        )]))
+    }
+}
+
 /// The kind-specific payload of a generated type.
 #[derive(Debug, Clone)]
 pub(crate) enum TypeKind {
     Struct(Vec<(String, CodecExpr)>),
-    Enum(Vec<EnumVariant>),
+    Enum(Vec<VariantDef>),
     Alias(CodecExpr),
 }
 
+impl TypeKind {
+    /// Every codec expression of the type, for rewriting in place.
+    pub(crate) fn exprs_mut(&mut self) -> Vec<&mut CodecExpr> {
+        match self {
+            TypeKind::Struct(fields) => fields.iter_mut().map(|(_, expr)| expr).collect(),
+            TypeKind::Enum(variants) => variants
+                .iter_mut()
+                .flat_map(|VariantDef { variant, .. }| match variant {
+                    EnumVariant::Unit(_) => Vec::new(),
+                    EnumVariant::Newtype(_, expr) => vec![expr],
+                    EnumVariant::Tuple(_, exprs) => exprs.iter_mut().collect(),
+                    EnumVariant::Struct(_, fields) => {
+                        fields.iter_mut().map(|(_, expr)| expr).collect()
+                    }
+                })
+                .collect(),
+            TypeKind::Alias(expr) => vec![expr],
+        }
+    }
+}
+
+/// A `type` alias declared in an extracted source.
+#[derive(Debug)]
+pub(crate) struct TypeAlias {
+    /// Generic type parameters, bound to the use site's type arguments.
+    pub(crate) params: Vec<String>,
+    /// Codecs of the parameter defaults (`T = u64`); `None` for a parameter without one.
+    pub(crate) defaults: Vec<Option<Result<CodecExpr, DiagnosticKind>>>,
+    /// The codec of the aliased type, or why it has none.
+    pub(crate) target: Result<CodecExpr, Vec<Diagnostic>>,
+    pub(crate) location: Option<SourceLocation>,
+}
+
+/// Per type, the references to wrap in `r.lazy`.
+type Deferred = BTreeMap<String, BTreeSet<String>>;
+
+/// The validated output plan shared by [`generate`](CodeGenerator::generate)
+/// and [`generate_modules`](CodeGenerator::generate_modules).
+struct Emission {
+    /// Dependencies before dependents.
+    order: Vec<String>,
+    deferred: Deferred,
+    /// Rust name to exported codec name.
+    archived_names: BTreeMap<String, String>,
+    /// Rust name to the name codec expressions reference: the raw `$` codec under JIT.
+    codec_names: BTreeMap<String, String>,
+    /// Rust name to exported type name.
+    type_names: BTreeMap<String, String>,
+    /// Whether the raw `$` codecs are exported for other modules to import.
+    raw_exported: bool,
+}
+
+impl Emission {
+    /// The declaration keyword of a raw `$` codec under JIT.
+    fn raw_const(&self) -> &'static str {
+        if self.raw_exported {
+            "export const"
+        } else {
+            "const"
+        }
+    }
+}
+
 /// The non-default wire format configured via [`set_format`](CodeGenerator::set_format).
 #[derive(Debug, Clone)]
 struct FormatSpec {
@@ -107,17 +251,50 @@ pub struct CodeGenerator {
     pub(crate) add_diagnostics: Vec<Diagnostic>,
     /// `set_archived_name` overrides, applied at generate time.
     overrides: BTreeMap<String, String>,
+    /// Generic parameters of generic types, keyed by Rust type name.
+    pub(crate) type_params: BTreeMap<String, Vec<String>>,
+    /// Codecs of the parameter defaults of extracted generic types (`T = u32`), keyed like
+    /// `type_params`; `None` for a parameter without one. Filled in for omitted trailing arguments.
+    pub(crate) type_param_defaults:
+        BTreeMap<String, Vec<Option<Result<CodecExpr, DiagnosticKind>>>>,
     header: Option<String>,
     allow_typescript_syntax: bool,
     pub(crate) on_unknown: OnUnknown,
     /// Derive paths that mark a type for extraction.
     pub(crate) marker_paths: BTreeSet<String>,
+    /// Active cfg options sources are stripped against.
+    pub(crate) cfg: CfgSet,
     pub(crate) registry: Registry,
     format: Option<FormatSpec>,
     direction: Direction,
     jit: bool,
     field_casing: Casing,
     variant_casing: Casing,
+    unit_enum_style: UnitEnumStyle,
+    /// `set_unit_enum_style_for` overrides, keyed by Rust type name.
+    unit_enum_styles: BTreeMap<String, UnitEnumStyle>,
+    /// Doc comments keyed by item path (`User`, `User.email`, `Shape::Circle.radius`).
+    pub(crate) docs: BTreeMap<String, String>,
+    doc_comments: bool,
+    type_style: TypeStyle,
+    /// `set_output_module` assignments, keyed by type or module path.
+    output_modules: BTreeMap<String, String>,
+    barrel: bool,
+    /// `set_roots` types; empty emits everything selected.
+    roots: BTreeSet<String>,
+    /// `allow_path` patterns; empty allows everything.
+    allowed: BTreeSet<String>,
+    /// `deny_path` patterns.
+    denied: BTreeSet<String>,
+    /// Every source file, source directory and config file read so far.
+    pub(crate) tracked_paths: BTreeSet<PathBuf>,
+    /// Source `type` aliases, keyed by crate-relative path.
+    pub(crate) type_aliases: BTreeMap<String, TypeAlias>,
+    /// Whether referenced aliases are emitted as exports of their own instead of inlined.
+    pub(crate) named_aliases: bool,
+    /// Integer constants for array lengths and discriminants, keyed like types:
+    /// crate-relative for local constants, fully qualified for external ones.
+    pub(crate) constants: BTreeMap<String, consts::Value>,
 }
 
 /// Which half of the codec surface the generated bindings target.
@@ -153,6 +330,16 @@ impl Direction {
         }
     }
 
+    /// The type-level bound of a codec argument and the codec type inferring to a given value type,
+    /// as exported by this direction's `rkyv-js` entry point.
+    fn codec_types(self) -> (&'static str, &'static str) {
+        match self {
+            Direction::Full => ("r.AnyCodec", "r.Codec"),
+            Direction::Decode => ("r.AnyDecoder", "r.Decoder"),
+            Direction::Encode => ("r.AnyEncoder", "r.Encoder"),
+        }
+    }
+
     /// Rewrite an emitted import block's `rkyv-js` specifiers for this direction.
     /// Non-`rkyv-js` specifiers (user `register_external` modules) are left untouched.
     /// Hand-written codecs must provide their own direction-appropriate exports.
@@ -195,16 +382,33 @@ impl CodeGenerator {
             failed: BTreeMap::new(),
             add_diagnostics: Vec::new(),
             overrides: BTreeMap::new(),
+            type_params: BTreeMap::new(),
+            type_param_defaults: BTreeMap::new(),
             header: None,
             allow_typescript_syntax: true,
             on_unknown: OnUnknown::Error,
             marker_paths: BTreeSet::from(["rkyv::Archive".to_string()]),
+            cfg: CfgSet::from_env(),
             registry: Registry::with_builtins(),
             format: None,
             direction: Direction::Full,
             jit: false,
             field_casing: Casing::Preserve,
             variant_casing: Casing::Preserve,
+            unit_enum_style: UnitEnumStyle::Tagged,
+            unit_enum_styles: BTreeMap::new(),
+            docs: BTreeMap::new(),
+            doc_comments: true,
+            type_style: TypeStyle::Inferred,
+            output_modules: BTreeMap::new(),
+            barrel: true,
+            roots: BTreeSet::new(),
+            allowed: BTreeSet::new(),
+            denied: BTreeSet::new(),
+            tracked_paths: BTreeSet::new(),
+            type_aliases: BTreeMap::new(),
+            named_aliases: false,
+            constants: BTreeMap::new(),
         }
     }
 
@@ -290,6 +494,346 @@ impl CodeGenerator {
         self
     }
 
+    /// Emit every field-less enum in `style` instead of as an `r.taggedEnum`.
+    ///
+    /// With [`UnitEnumStyle::String`] or [`UnitEnumStyle::Number`], an enum whose
+    /// variants all carry no data decodes to a primitive, and an `as const`
+    /// object named after the type lists its values, so JS code can write
+    /// `status === Status.Active`. Enums with data keep the tagged form.
+    ///
+    /// Defaults to [`UnitEnumStyle::Tagged`].
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, EnumVariant, UnitEnumStyle};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.set_unit_enum_style(UnitEnumStyle::Number);
+    /// generator.add_enum(
+    ///     "Status",
+    ///     [EnumVariant::Unit("Active".to_string()), EnumVariant::Unit("Banned".to_string())],
+    /// );
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("export const Status = {\n  Active: 0,\n  Banned: 1,\n} as const;"));
+    /// assert!(code.contains("export const ArchivedStatus = r.numberEnum(Status);"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn set_unit_enum_style(&mut self, style: UnitEnumStyle) -> &mut Self {
+        self.unit_enum_style = style;
+        self
+    }
+
+    /// Override [`set_unit_enum_style`](Self::set_unit_enum_style) for one type.
+    ///
+    /// Order-independent like [`set_archived_name`](Self::set_archived_name).
+    /// A target that is not a field-less enum is reported as
+    /// [`DiagnosticKind::UnitEnumStyleTarget`] at generate time.
+    pub fn set_unit_enum_style_for(
+        &mut self,
+        type_name: impl Into<String>,
+        style: UnitEnumStyle,
+    ) -> &mut Self {
+        self.unit_enum_styles.insert(type_name.into(), style);
+        self
+    }
+
+    /// Attach documentation to a type, field or variant, rendered as a JSDoc block in the output.
+    ///
+    /// `path` is labelled the way diagnostics are: `User` for a type, `User.email` for a field,
+    /// `Shape::Circle` for a variant and `Shape::Circle.radius` for a variant's field.
+    /// Field and variant names are the Rust ones, before casing.
+    /// Types extracted from source get their `///` comments this way automatically.
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, codec};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.add_struct("Person", [("email", codec::string())]);
+    /// generator.set_doc("Person.email", "Primary contact address.");
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("  /** Primary contact address. */\n  email: r.string,"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn set_doc(&mut self, path: impl Into<String>, doc: impl Into<String>) -> &mut Self {
+        self.docs.insert(path.into(), doc.into());
+        self
+    }
+
+    /// When `false`, doc comments are left out of the output.
+    ///
+    /// Defaults to `true`.
+    pub fn set_doc_comments(&mut self, enabled: bool) -> &mut Self {
+        self.doc_comments = enabled;
+        self
+    }
+
+    /// The JSDoc block for `path`, one line per doc line at `indent`, or an
+    /// empty string when it has none.
+    fn jsdoc(&self, path: &str, indent: &str) -> String {
+        let Some(doc) = self.docs.get(path).filter(|_| self.doc_comments) else {
+            return String::new();
+        };
+        // `*/` inside the text would close the comment early.
+        let doc = doc.replace("*/", "*\\/");
+        let lines: Vec<&str> = doc.lines().map(str::trim_end).collect();
+        match lines.as_slice() {
+            [] => String::new(),
+            [line] => format!("{indent}/** {line} */\n"),
+            lines => {
+                let mut block = format!("{indent}/**\n");
+                for line in lines {
+                    if line.is_empty() {
+                        block.push_str(&format!("{indent} *\n"));
+                    } else {
+                        block.push_str(&format!("{indent} * {line}\n"));
+                    }
+                }
+                block.push_str(&format!("{indent} */\n"));
+                block
+            }
+        }
+    }
+
+    /// Choose how each codec's value type is exported.
+    ///
+    /// [`TypeStyle::Declared`] writes the types out instead of inferring them from the codecs,
+    /// which keeps editor hovers and compiler errors readable and spares `tsc` the inference
+    /// on large schemas. The codecs are then checked against the declarations:
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, TypeStyle, codec};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.set_type_style(TypeStyle::Declared);
+    /// generator.add_struct("Person", [("name", codec::string()), ("age", codec::option(codec::u8()))]);
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("export interface Person {\n  name: string;\n  age: number | null;\n}"));
+    /// assert!(code.contains("export const ArchivedPerson: r.Codec<Person> = r.struct({"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    ///
+    /// Has no effect when [`allow_typescript_syntax`](Self::allow_typescript_syntax) is off.
+    /// Defaults to [`TypeStyle::Inferred`].
+    pub fn set_type_style(&mut self, style: TypeStyle) -> &mut Self {
+        self.type_style = style;
+        self
+    }
+
+    /// Emit the `type` aliases of extracted sources as exports of their own instead of inlining them,
+    /// so the TypeScript side keeps the domain names:
+    ///
+    /// ```
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.set_named_aliases(true);
+    /// generator.add_source_str(r#"
+    ///     pub type UserId = u64;
+    ///
+    ///     #[derive(rkyv::Archive)]
+    ///     pub struct User { pub id: UserId }
+    /// "#)?;
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("export const ArchivedUserId = r.u64;"));
+    /// assert!(code.contains("id: ArchivedUserId,"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    ///
+    /// Only aliases a generated type references are emitted; generic aliases become codec factories.
+    /// Applies to sources added afterwards. Defaults to `false`.
+    pub fn set_named_aliases(&mut self, enabled: bool) -> &mut Self {
+        self.named_aliases = enabled;
+        self
+    }
+
+    /// Choose how the `std::net` address types decode: `Ipv4Addr`, `Ipv6Addr` and `IpAddr` as text
+    /// or octets, and the socket addresses as `{ ip, port }` objects holding either.
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, TextStyle};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.set_net_style(TextStyle::Bytes);
+    /// generator.add_source_str(r#"
+    ///     use std::net::{IpAddr, SocketAddr};
+    ///
+    ///     #[derive(rkyv::Archive)]
+    ///     pub struct Peer { pub ip: IpAddr, pub addr: SocketAddr }
+    /// "#)?;
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("import { ipAddrBytes, socketAddrBytes } from 'rkyv-js/lib/net';"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    ///
+    /// Replaces the built-in mappings, so it applies to sources added afterwards.
+    /// Defaults to [`TextStyle::String`].
+    pub fn set_net_style(&mut self, style: TextStyle) -> &mut Self {
+        self.registry.register_net_types(style);
+        self
+    }
+
+    /// Choose whether `CString` decodes as UTF-8 text or as its bytes, for strings that need not be UTF-8.
+    ///
+    /// Replaces the built-in mapping, so it applies to sources added afterwards.
+    /// Defaults to [`TextStyle::String`].
+    pub fn set_c_string_style(&mut self, style: TextStyle) -> &mut Self {
+        self.registry.register_c_string(style);
+        self
+    }
+
+    /// Assign a type, or every type under a module path, to an output module of
+    /// [`generate_modules`](Self::generate_modules).
+    ///
+    /// `path` is a Rust type name (`api::User`) or module path (`api`); the longest
+    /// matching assignment wins, and `""` matches every type. `module` is a file path
+    /// relative to the output directory, without extension (`features/chat`).
+    pub fn set_output_module(
+        &mut self,
+        path: impl Into<String>,
+        module: impl Into<String>,
+    ) -> &mut Self {
+        self.output_modules.insert(path.into(), module.into());
+        self
+    }
+
+    /// When `false`, [`generate_modules`](Self::generate_modules) writes no `index.ts` barrel.
+    ///
+    /// Defaults to `true`.
+    pub fn set_barrel(&mut self, enabled: bool) -> &mut Self {
+        self.barrel = enabled;
+        self
+    }
+
+    /// The output module `type_name` is emitted to by `generate_modules`.
+    fn output_module(&self, type_name: &str) -> String {
+        if let Some(module) = self.assigned_output_module(type_name) {
+            return module.clone();
+        }
+        match type_name.rsplit_once("::") {
+            Some((module, _)) => module.replace("::", "/"),
+            None => "bindings".to_string(),
+        }
+    }
+
+    /// The output module [`set_output_module`](Self::set_output_module) assigns `type_name` to, if any.
+    fn assigned_output_module(&self, type_name: &str) -> Option<&String> {
+        self.output_modules
+            .iter()
+            .filter(|(path, _)| path_matches(path, type_name))
+            .max_by_key(|(path, _)| path.len())
+            .map(|(_, module)| module)
+    }
+
+    /// Emit only `roots` and the types they reference, transitively.
+    ///
+    /// Types outside that closure are neither validated nor emitted, so an internal type
+    /// that fails extraction does not fail generation. A root that was never added is
+    /// reported as [`DiagnosticKind::UnknownRoot`].
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, codec};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.add_struct("User", [("id", codec::u32())]);
+    /// generator.add_struct("ApiResponse", [("user", codec::named("User"))]);
+    /// generator.add_struct("AuditLog", [("entries", codec::vec(codec::string()))]);
+    /// generator.set_roots(["ApiResponse"]);
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("export const ArchivedUser = "));
+    /// assert!(!code.contains("AuditLog"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn set_roots(&mut self, roots: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.roots = roots.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restrict the emitted types to those matching an allowed path: a type name
+    /// (`api::User`) or a module path (`api`). May be called repeatedly.
+    ///
+    /// With no allowed paths, every type is allowed.
+    pub fn allow_path(&mut self, path: impl Into<String>) -> &mut Self {
+        self.allowed.insert(path.into());
+        self
+    }
+
+    /// Never emit types matching `path`, a type name or a module path; overrides
+    /// [`allow_path`](Self::allow_path). May be called repeatedly.
+    ///
+    /// An emitted type referencing an excluded one is reported as [`DiagnosticKind::ExcludedType`]
+    /// rather than leaking it.
+    pub fn deny_path(&mut self, path: impl Into<String>) -> &mut Self {
+        self.denied.insert(path.into());
+        self
+    }
+
+    /// Whether the allow and deny lists let `type_name` through.
+    fn is_allowed(&self, type_name: &str) -> bool {
+        let matches = |path: &String| path_matches(path, type_name);
+        (self.allowed.is_empty() || self.allowed.iter().any(matches))
+            && !self.denied.iter().any(matches)
+    }
+
+    /// The types to validate and emit: the allowed ones reachable from the roots,
+    /// or every allowed one without roots.
+    fn selection(&self, diagnostics: &mut Vec<Diagnostic>) -> BTreeSet<&String> {
+        let known = |name: &str| {
+            let failed = || self.failed.get_key_value(name).map(|(name, _)| name);
+            let added = self.types.get_key_value(name).map(|(name, _)| name);
+            added.or_else(failed)
+        };
+        let mut pending: Vec<&String> = if self.roots.is_empty() {
+            let all = self.types.keys().chain(self.failed.keys());
+            all.filter(|name| self.is_allowed(name)).collect()
+        } else {
+            let mut roots = Vec::new();
+            for root in &self.roots {
+                match known(root) {
+                    Some(name) if self.is_allowed(name) => roots.push(name),
+                    Some(name) => diagnostics.push(
+                        Diagnostic::new(DiagnosticKind::ExcludedType { name: name.clone() })
+                            .referenced_by("set_roots".to_string()),
+                    ),
+                    None => diagnostics.push(Diagnostic::new(DiagnosticKind::UnknownRoot {
+                        type_name: root.clone(),
+                    })),
+                }
+            }
+            roots
+        };
+
+        let mut selected = BTreeSet::new();
+        let mut excluded = BTreeSet::new();
+        while let Some(name) = pending.pop() {
+            if !selected.insert(name) {
+                continue;
+            }
+            let Some(kind) = self.types.get(name) else {
+                continue;
+            };
+            for (context, expr) in Self::exprs_with_context(name, kind) {
+                let mut refs = BTreeSet::new();
+                expr.collect_type_refs(&mut refs);
+                // Unknown references are left to the reference validation.
+                for reference in refs {
+                    let Some(reference) = known(&reference) else {
+                        continue;
+                    };
+                    if self.is_allowed(reference) {
+                        pending.push(reference);
+                    } else if excluded.insert((reference, context.clone())) {
+                        diagnostics.push(
+                            Diagnostic::new(DiagnosticKind::ExcludedType {
+                                name: reference.clone(),
+                            })
+                            .referenced_by(context.clone()),
+                        );
+                    }
+                }
+            }
+        }
+        selected
+    }
+
     /// When `false`, `export type ... = r.Infer<...>` lines are dropped so the output is valid plain JavaScript.
     ///
     /// Defaults to `true`.
@@ -313,6 +857,18 @@ impl CodeGenerator {
         self
     }
 
+    /// Set the cfg options `#[cfg(...)]` and `#[cfg_attr(...)]` are evaluated against in sources added afterwards.
+    ///
+    /// Items, fields and variants whose `cfg` does not hold are dropped before extraction,
+    /// and `cfg_attr`-guarded attributes (`#[cfg_attr(feature = "rkyv", derive(Archive))]`) apply only when active.
+    ///
+    /// Defaults to [`CfgSet::from_env`]: the target's options and enabled features inside a build script,
+    /// and nothing at all elsewhere.
+    pub fn set_cfg(&mut self, cfg: CfgSet) -> &mut Self {
+        self.cfg = cfg;
+        self
+    }
+
     /// Register (or replace) an external type mapping for a fully-qualified Rust path.
     ///
     /// ```
@@ -341,6 +897,36 @@ impl CodeGenerator {
         self
     }
 
+    /// Register (or replace) an integer constant for array lengths and enum discriminants,
+    /// such as one imported from another crate.
+    ///
+    /// `const` items of the added sources are collected on their own, inherent and trait `impl`
+    /// constants included. Local constants are keyed by their crate-relative path (`"proto::HASH_LEN"`,
+    /// `"Digest::SIZE"` for an associated one), external ones by their fully-qualified path.
+    /// Register a constant before adding the sources that use it.
+    ///
+    /// ```
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.register_const("blake3::OUT_LEN", 32);
+    /// generator.add_source_str(r#"
+    ///     use blake3::OUT_LEN;
+    ///
+    ///     #[derive(rkyv::Archive)]
+    ///     pub struct Digest { pub bytes: [u8; OUT_LEN] }
+    /// "#)?;
+    ///
+    /// assert!(generator.generate()?.contains("bytes: r.array(r.u8, 32),"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn register_const(&mut self, path: impl Into<String>, value: i128) -> &mut Self {
+        let path = path.into();
+        let key = path.strip_prefix("crate::").unwrap_or(&path).to_string();
+        self.constants.insert(key, consts::Value::untyped(value));
+        self
+    }
+
     /// Remove an external type mapping (e.g. to disable a builtin).
     pub fn unregister_external(&mut self, path: &str) -> &mut Self {
         self.registry.unregister_type(path);
@@ -362,12 +948,50 @@ impl CodeGenerator {
     }
 
     /// Add an enum definition.
+    ///
+    /// Variants are tagged by declaration index; see
+    /// [`add_enum_with_discriminants`](Self::add_enum_with_discriminants) for explicit values.
     pub fn add_enum(
         &mut self,
         name: impl Into<String>,
         variants: impl IntoIterator<Item = EnumVariant>,
     ) -> &mut Self {
-        let variants = variants.into_iter().collect();
+        self.add_type(name.into(), TypeKind::Enum(VariantDef::indexed(variants)), None);
+        self
+    }
+
+    /// Add an enum definition whose variants carry explicit discriminants,
+    /// like `Active = 2` in Rust.
+    ///
+    /// rkyv archives every enum tag as a `u8` regardless of the Rust `#[repr]`,
+    /// so the discriminant is the byte written for the variant.
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, EnumVariant};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.add_enum_with_discriminants(
+    ///     "Status",
+    ///     [
+    ///         (EnumVariant::Unit("Active".to_string()), 1),
+    ///         (EnumVariant::Unit("Banned".to_string()), 4),
+    ///     ],
+    /// );
+    /// assert!(generator.generate()?.contains("{ discriminants: { Active: 1, Banned: 4 } }"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn add_enum_with_discriminants(
+        &mut self,
+        name: impl Into<String>,
+        variants: impl IntoIterator<Item = (EnumVariant, u8)>,
+    ) -> &mut Self {
+        let variants = variants
+            .into_iter()
+            .map(|(variant, discriminant)| VariantDef {
+                variant,
+                discriminant,
+            })
+            .collect();
         self.add_type(name.into(), TypeKind::Enum(variants), None);
         self
     }
@@ -410,7 +1034,7 @@ impl CodeGenerator {
         self.failed.insert(name, diagnostics);
     }
 
-    fn is_known_type(&self, name: &str) -> bool {
+    pub(crate) fn is_known_type(&self, name: &str) -> bool {
         self.types.contains_key(name) || self.failed.contains_key(name)
     }
 
@@ -427,8 +1051,73 @@ impl CodeGenerator {
         self
     }
 
+    /// Declare the generic type parameters of a type, making it a codec factory:
+    /// `export const ArchivedPage = <T extends r.AnyCodec>(t: T) => r.struct({ ... })`.
+    ///
+    /// Inside the type, [`codec::param`](crate::codec::param) refers to a parameter;
+    /// elsewhere, [`codec::instantiate`](crate::codec::instantiate) applies the factory.
+    /// Every use site must pass exactly as many arguments as there are parameters,
+    /// otherwise generation reports [`DiagnosticKind::GenericArity`]. For types extracted from source,
+    /// omitted trailing arguments are first filled in with the parameters' defaults (`struct Page<T = u32>`).
+    ///
+    /// Order-independent like [`set_archived_name`](Self::set_archived_name).
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, codec};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.add_struct("Page", [("items", codec::vec(codec::param("T")))]);
+    /// generator.set_type_params("Page", ["T"]);
+    /// generator.add_struct("Feed", [("page", codec::instantiate("Page", [codec::string()]))]);
+    /// let code = generator.generate()?;
+    /// assert!(code.contains("export const ArchivedPage = <T extends r.AnyCodec>(t: T) => r.struct({"));
+    /// assert!(code.contains("page: ArchivedPage(r.string),"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn set_type_params(
+        &mut self,
+        type_name: impl Into<String>,
+        params: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        let params: Vec<String> = params.into_iter().map(Into::into).collect();
+        let type_name = type_name.into();
+        if params.is_empty() {
+            self.type_params.remove(&type_name);
+        } else {
+            self.type_params.insert(type_name, params);
+        }
+        self
+    }
+
+    pub(crate) fn type_arity(&self, type_name: &str) -> usize {
+        self.type_params.get(type_name).map_or(0, Vec::len)
+    }
+
+    /// Whether `kind` can be emitted as a primitive-valued enum: non-generic,
+    /// non-empty, and without data in any variant.
+    fn is_unit_enum(&self, type_name: &str, kind: &TypeKind) -> bool {
+        matches!(kind, TypeKind::Enum(variants)
+            if !variants.is_empty()
+                && variants.iter().all(|def| matches!(def.variant, EnumVariant::Unit(_))))
+            && self.type_arity(type_name) == 0
+    }
+
+    /// The style `type_name` is emitted in; always `Tagged` for enums with data.
+    fn unit_enum_style_of(&self, type_name: &str, kind: &TypeKind) -> UnitEnumStyle {
+        if !self.is_unit_enum(type_name, kind) {
+            return UnitEnumStyle::Tagged;
+        }
+        self.unit_enum_styles
+            .get(type_name)
+            .copied()
+            .unwrap_or(self.unit_enum_style)
+    }
+
     /// The archived (exported) name a type will be emitted under, or `None`
     /// if no type with that name has been added.
+    ///
+    /// Types extracted from a module are named by their crate-relative path
+    /// (`api::User`).
     pub fn archived_name_of(&self, type_name: &str) -> Option<String> {
         if !self.is_known_type(type_name) {
             return None;
@@ -440,7 +1129,34 @@ impl CodeGenerator {
         self.overrides
             .get(type_name)
             .cloned()
-            .unwrap_or_else(|| format!("Archived{type_name}"))
+            .unwrap_or_else(|| format!("Archived{}", self.exported_type_name(type_name)))
+    }
+
+    /// The TypeScript name a type is exported under.
+    ///
+    /// Types are keyed by their crate-relative path (`api::v1::User`). The
+    /// last segment is used unless another known type shares it, in which case
+    /// the module path is folded in (`ApiV1User`).
+    fn exported_type_name(&self, type_name: &str) -> String {
+        let Some((module, ident)) = type_name.rsplit_once("::") else {
+            return type_name.to_string();
+        };
+        let shared = self
+            .types
+            .keys()
+            .chain(self.failed.keys())
+            .filter(|other| other.rsplit("::").next() == Some(ident))
+            .count()
+            > 1;
+        if !shared {
+            return ident.to_string();
+        }
+        let mut name: String = module
+            .split("::")
+            .map(|segment| Casing::Pascal.apply(segment))
+            .collect();
+        name.push_str(ident);
+        name
     }
 
     /// Configure the rkyv wire format of the generated bindings.
@@ -464,7 +1180,7 @@ impl CodeGenerator {
 
     /// Every codec expression of a type, labelled with its `Type.field`
     /// provenance for diagnostics.
-    fn exprs_with_context<'a>(
+    pub(crate) fn exprs_with_context<'a>(
         type_name: &str,
         kind: &'a TypeKind,
     ) -> Vec<(String, &'a CodecExpr)> {
@@ -475,7 +1191,7 @@ impl CodeGenerator {
                 .collect(),
             TypeKind::Enum(variants) => {
                 let mut out = Vec::new();
-                for variant in variants {
+                for VariantDef { variant, .. } in variants {
                     match variant {
                         EnumVariant::Unit(_) => {}
                         EnumVariant::Newtype(vname, expr) => {
@@ -541,11 +1257,11 @@ impl CodeGenerator {
                 TypeKind::Enum(variants) => {
                     diagnostics.extend(Self::casing_collisions(
                         name,
-                        variants.iter().map(|variant| variant.name().to_string()),
+                        variants.iter().map(|def| def.variant.name().to_string()),
                         self.variant_casing,
                     ));
-                    for variant in variants.iter() {
-                        if let EnumVariant::Struct(vname, fields) = variant {
+                    for def in variants.iter() {
+                        if let EnumVariant::Struct(vname, fields) = &def.variant {
                             diagnostics.extend(Self::casing_collisions(
                                 &format!("{name}::{vname}"),
                                 fields.iter().map(|(field, _)| field.clone()),
@@ -560,13 +1276,269 @@ impl CodeGenerator {
         diagnostics
     }
 
-    /// Generate the TypeScript bindings.
+    /// Tag values shared by two variants of one emitted enum.
     ///
-    /// Validation runs first; every problem is aggregated into a single [`Error::Codegen`].
-    pub fn generate(&self) -> Result<String, Error> {
-        let mut diagnostics: Vec<Diagnostic> = self.add_diagnostics.clone();
-
-        // Rename overrides must target a type that materialized.
+    /// Rust rejects these in source, but [`add_enum_with_discriminants`](Self::add_enum_with_discriminants)
+    /// and enums with more than 256 variants can still produce them.
+    fn discriminant_diagnostics(emitted: &BTreeMap<&String, &TypeKind>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (name, kind) in emitted {
+            let TypeKind::Enum(variants) = kind else {
+                continue;
+            };
+            let mut by_value: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+            for def in variants {
+                by_value
+                    .entry(def.discriminant)
+                    .or_default()
+                    .push(def.variant.name().to_string());
+            }
+            diagnostics.extend(
+                by_value
+                    .into_iter()
+                    .filter(|(_, variants)| variants.len() > 1)
+                    .map(|(value, variants)| {
+                        Diagnostic::new(DiagnosticKind::DuplicateDiscriminant { value, variants })
+                            .referenced_by(name.to_string())
+                    }),
+            );
+        }
+        diagnostics
+    }
+
+    /// Generate the TypeScript bindings.
+    ///
+    /// Validation runs first; every problem is aggregated into a single [`Error::Codegen`].
+    pub fn generate(&self) -> Result<String, Error> {
+        let emitted = self.validate()?;
+        let emission = self.plan(&emitted, None);
+
+        let mut blocks = vec![self.header_block()];
+        let import_block = generate_import_block(&self.codec_exprs(emitted.keys().copied(), &emitted))
+            .expect("import conflicts are validated before emission");
+        blocks.push(self.direction.rewrite_import_block(&import_block).trim_end().to_string());
+        if let Some(spec) = self.nondefault_format() {
+            blocks.push(format!("const FORMAT = r.format({{ {} }});", spec.options()));
+        }
+        for name in &emission.order {
+            blocks.push(self.emit_type(name, emitted[&name], &emission));
+        }
+
+        Ok(blocks.join("\n\n") + "\n")
+    }
+
+    /// Generate the bindings split into one module per output module, keyed by file path
+    /// relative to the output directory (`api/users.ts`).
+    ///
+    /// Types go to the module of their Rust module path (`api::users::User` to `api/users.ts`),
+    /// types of the crate root to `bindings.ts`, unless [`set_output_module`](Self::set_output_module)
+    /// assigns them elsewhere. Each module imports what it references from its siblings.
+    /// Unless [`set_barrel`](Self::set_barrel) turns it off, an `index.ts` re-exports every module.
+    /// Rust modules named `bindings`, or `index` under the barrel, must be assigned elsewhere;
+    /// otherwise they fail with [`DiagnosticKind::ReservedModuleName`].
+    ///
+    /// Files are `.js` when [`allow_typescript_syntax`](Self::allow_typescript_syntax) is off.
+    ///
+    /// ```
+    /// use rkyv_js_codegen::{CodeGenerator, codec};
+    ///
+    /// let mut generator = CodeGenerator::new();
+    /// generator.add_source_str(r#"
+    ///     #[derive(rkyv::Archive)]
+    ///     struct Session { user: users::User }
+    ///     mod users {
+    ///         #[derive(rkyv::Archive)]
+    ///         pub struct User { pub id: u32 }
+    ///     }
+    /// "#)?;
+    /// let modules = generator.generate_modules()?;
+    /// assert_eq!(modules.keys().collect::<Vec<_>>(), ["bindings.ts", "index.ts", "users.ts"]);
+    /// assert!(modules["bindings.ts"].contains("import { ArchivedUser } from './users.ts';"));
+    /// assert!(modules["index.ts"].contains("export * from './users.ts';"));
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn generate_modules(&self) -> Result<BTreeMap<String, String>, Error> {
+        let emitted = self.validate()?;
+        let extension = if self.allow_typescript_syntax { "ts" } else { "js" };
+
+        let mut members: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+        for name in emitted.keys() {
+            members.entry(self.output_module(name)).or_default().push(name);
+        }
+        let mut reserved = Vec::new();
+        if self.barrel && members.contains_key("index") {
+            reserved.push("index");
+        }
+        // A Rust module `bindings` would silently merge into the crate root's module.
+        let rust_bindings_module = members.get("bindings").is_some_and(|names| {
+            names
+                .iter()
+                .any(|name| name.contains("::") && self.assigned_output_module(name).is_none())
+        });
+        if rust_bindings_module {
+            reserved.push("bindings");
+        }
+        if !reserved.is_empty() {
+            return Err(Error::Codegen(
+                reserved
+                    .into_iter()
+                    .map(|module| {
+                        Diagnostic::new(DiagnosticKind::ReservedModuleName {
+                            module: module.to_string(),
+                        })
+                    })
+                    .collect(),
+            ));
+        }
+        let module_of: BTreeMap<&String, &str> = members
+            .iter()
+            .flat_map(|(module, names)| names.iter().map(move |name| (*name, module.as_str())))
+            .collect();
+        let emission = self.plan(&emitted, Some(&module_of));
+
+        let mut files = BTreeMap::new();
+        for (module, names) in &members {
+            let mut blocks = vec![self.header_block()];
+            let import_block = generate_import_block(&self.codec_exprs(names.iter().copied(), &emitted))
+                .expect("import conflicts are validated before emission");
+            let mut imports = self.direction.rewrite_import_block(&import_block);
+
+            // Sibling modules, by the names this module's codecs and declarations use.
+            let mut sibling_imports: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+            for name in names {
+                let mut refs = BTreeSet::new();
+                for (_, expr) in Self::exprs_with_context(name, emitted[name]) {
+                    expr.collect_type_refs(&mut refs);
+                }
+                for reference in refs {
+                    let target = module_of[&reference];
+                    if target == module {
+                        continue;
+                    }
+                    let imported = sibling_imports.entry(target).or_default();
+                    imported.insert(emission.codec_names[&reference].clone());
+                    if self.allow_typescript_syntax && self.type_style == TypeStyle::Declared {
+                        imported.insert(format!("type {}", emission.type_names[&reference]));
+                    }
+                }
+            }
+            for (target, imported) in sibling_imports {
+                let imported: Vec<String> = imported.into_iter().collect();
+                imports.push_str(&format!(
+                    "import {{ {} }} from '{}';\n",
+                    imported.join(", "),
+                    relative_specifier(module, target, extension)
+                ));
+            }
+            blocks.push(imports.trim_end().to_string());
+
+            if let Some(spec) = self.nondefault_format() {
+                blocks.push(format!("const FORMAT = r.format({{ {} }});", spec.options()));
+            }
+            for name in emission.order.iter().filter(|name| module_of[name] == module) {
+                blocks.push(self.emit_type(name, emitted[&name], &emission));
+            }
+            files.insert(format!("{module}.{extension}"), blocks.join("\n\n") + "\n");
+        }
+
+        if self.barrel {
+            let mut barrel = self.header_block() + "\n\n";
+            for module in members.keys() {
+                barrel.push_str(&format!(
+                    "export * from '{}';\n",
+                    relative_specifier("index", module, extension)
+                ));
+            }
+            files.insert(format!("index.{extension}"), barrel);
+        }
+        Ok(files)
+    }
+
+    /// The header comment opening every generated file.
+    fn header_block(&self) -> String {
+        let header = self
+            .header
+            .as_deref()
+            .unwrap_or("Auto-generated by rkyv-js-codegen\nDO NOT EDIT MANUALLY");
+        let mut header_block = String::from("/**\n");
+        for line in header.lines() {
+            if line.is_empty() {
+                header_block.push_str(" *\n");
+            } else {
+                header_block.push_str(" * ");
+                header_block.push_str(line);
+                header_block.push('\n');
+            }
+        }
+        header_block.push_str(" */");
+        header_block
+    }
+
+    /// The emission order and the names of the validated `emitted` types.
+    ///
+    /// With `module_of` set, references between two modules inside one
+    /// recursive component are deferred too: ES module cycles evaluate in
+    /// import order, so either side may run first.
+    fn plan(
+        &self,
+        emitted: &BTreeMap<&String, &TypeKind>,
+        module_of: Option<&BTreeMap<&String, &str>>,
+    ) -> Emission {
+        // Dependencies emit before dependents; references that close a cycle
+        // are deferred through `r.lazy`.
+        let (order, mut deferred, component_of) = Self::emission_order(emitted);
+        if let Some(module_of) = module_of {
+            for (name, kind) in emitted {
+                let mut refs = BTreeSet::new();
+                for (_, expr) in Self::exprs_with_context(name, kind) {
+                    expr.collect_type_refs(&mut refs);
+                }
+                refs.retain(|reference| {
+                    module_of[reference] != module_of[name]
+                        && component_of[reference] == component_of[*name]
+                });
+                if !refs.is_empty() {
+                    deferred.entry((*name).clone()).or_default().extend(refs);
+                }
+            }
+        }
+
+        let archived_names: BTreeMap<String, String> = emitted
+            .keys()
+            .map(|name| ((*name).clone(), self.resolved_archived_name(name)))
+            .collect();
+
+        let type_names: BTreeMap<String, String> = emitted
+            .keys()
+            .map(|name| ((*name).clone(), self.exported_type_name(name)))
+            .collect();
+
+        // With JIT enabled, cross-references resolve to the raw `$` codecs so
+        // every export is compiled over the uncompiled interpreter graph.
+        let codec_names: BTreeMap<String, String> = if self.jit {
+            archived_names
+                .iter()
+                .map(|(name, archived)| (name.clone(), format!("{archived}$")))
+                .collect()
+        } else {
+            archived_names.clone()
+        };
+
+        Emission {
+            order,
+            deferred,
+            archived_names,
+            codec_names,
+            type_names,
+            raw_exported: module_of.is_some(),
+        }
+    }
+
+    /// Run every check over the added types and return the set that will be emitted.
+    fn validate(&self) -> Result<BTreeMap<&String, &TypeKind>, Error> {
+        let mut diagnostics: Vec<Diagnostic> = self.add_diagnostics.clone();
+
+        // Rename overrides must target a type that materialized.
         for target in self.overrides.keys() {
             if !self.is_known_type(target) {
                 diagnostics.push(Diagnostic::new(DiagnosticKind::UnknownRenameTarget {
@@ -575,16 +1547,35 @@ impl CodeGenerator {
             }
         }
 
+        // Per-type enum styles must target a field-less enum; failed types
+        // are already reported on their own.
+        for target in self.unit_enum_styles.keys() {
+            let eligible = match self.types.get(target) {
+                Some(kind) => self.is_unit_enum(target, kind),
+                None => self.failed.contains_key(target),
+            };
+            if !eligible {
+                diagnostics.push(Diagnostic::new(DiagnosticKind::UnitEnumStyleTarget {
+                    type_name: target.clone(),
+                }));
+            }
+        }
+
+        // Roots and allow/deny paths: unselected types are neither checked nor emitted.
+        let selected = self.selection(&mut diagnostics);
+        let failed = self.failed.iter().filter(|(name, _)| selected.contains(name));
+        let types = || self.types.iter().filter(|(name, _)| selected.contains(name));
+
         // Extraction failures: hard errors, or skipped with a warning.
         let mut skipped: BTreeSet<String> = BTreeSet::new();
         match self.on_unknown {
             OnUnknown::Error => {
-                for failure_diagnostics in self.failed.values() {
+                for (_, failure_diagnostics) in failed {
                     diagnostics.extend(failure_diagnostics.iter().cloned());
                 }
             }
             OnUnknown::SkipContainingType => {
-                for (name, failure_diagnostics) in &self.failed {
+                for (name, failure_diagnostics) in failed {
                     skipped.insert(name.clone());
                     for diagnostic in failure_diagnostics {
                         eprintln!(
@@ -598,7 +1589,7 @@ impl CodeGenerator {
         // Validate type references.
         match self.on_unknown {
             OnUnknown::Error => {
-                for (name, kind) in &self.types {
+                for (name, kind) in types() {
                     for (context, expr) in Self::exprs_with_context(name, kind) {
                         let mut refs = BTreeSet::new();
                         expr.collect_type_refs(&mut refs);
@@ -619,7 +1610,7 @@ impl CodeGenerator {
                 // Transitively omit types referencing skipped or missing types.
                 loop {
                     let mut newly_skipped = Vec::new();
-                    for (name, kind) in &self.types {
+                    for (name, kind) in types() {
                         if skipped.contains(name) {
                             continue;
                         }
@@ -652,171 +1643,296 @@ impl CodeGenerator {
         }
 
         // The set of types actually emitted, in stable order.
-        let emitted: BTreeMap<&String, &TypeKind> = self
-            .types
-            .iter()
+        let emitted: BTreeMap<&String, &TypeKind> = types()
             .filter(|(name, _)| !skipped.contains(*name))
             .collect();
 
         diagnostics.extend(self.casing_diagnostics(&emitted));
-
-        // Import conflicts across everything emitted.
-        let (jit_module, jit_fn) = self.direction.jit_entry();
-        let jit_import = CodecExpr::import_from(jit_module, jit_fn);
-        let mut all_exprs: Vec<&CodecExpr> = emitted
-            .iter()
-            .flat_map(|(name, kind)| Self::exprs_with_context(name, kind))
-            .map(|(_, expr)| expr)
-            .collect();
-        if self.jit && !emitted.is_empty() {
-            // Through the shared path so it dedups and conflict-checks like
-            // any user import.
-            all_exprs.push(&jit_import);
+        diagnostics.extend(Self::discriminant_diagnostics(&emitted));
+
+        // Module-path disambiguation can still land two types on one export.
+        let mut by_export: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for name in emitted.keys() {
+            by_export
+                .entry(self.resolved_archived_name(name))
+                .or_default()
+                .push((*name).clone());
         }
-        let import_block = match generate_import_block(all_exprs.iter().copied()) {
-            Ok(block) => self.direction.rewrite_import_block(&block),
-            Err(conflicts) => {
-                diagnostics.extend(conflicts.into_iter().map(Diagnostic::new));
-                String::new()
+        for (emitted, originals) in by_export {
+            if originals.len() > 1 {
+                diagnostics.push(Diagnostic::new(DiagnosticKind::NameCollision {
+                    emitted,
+                    originals,
+                }));
             }
-        };
-
-        if !diagnostics.is_empty() {
-            return Err(Error::Codegen(diagnostics));
         }
 
-        // Topological sort (Kahn's) so dependencies emit before dependents;
-        // ties resolve in BTreeMap (name) order.
-        let order = Self::topological_sort(&emitted);
-
-        let archived_names: BTreeMap<String, String> = emitted
-            .keys()
-            .map(|name| ((*name).clone(), self.resolved_archived_name(name)))
-            .collect();
-
-        // With JIT enabled, cross-references resolve to the raw `$` codecs so
-        // every export is compiled over the uncompiled interpreter graph.
-        let codec_names: BTreeMap<String, String> = if self.jit {
-            archived_names
-                .iter()
-                .map(|(name, archived)| (name.clone(), format!("{archived}$")))
-                .collect()
-        } else {
-            archived_names.clone()
-        };
-
-        // Assemble the output.
-        let mut blocks: Vec<String> = Vec::new();
-
-        let header = self
-            .header
-            .as_deref()
-            .unwrap_or("Auto-generated by rkyv-js-codegen\nDO NOT EDIT MANUALLY");
-        let mut header_block = String::from("/**\n");
-        for line in header.lines() {
-            if line.is_empty() {
-                header_block.push_str(" *\n");
-            } else {
-                header_block.push_str(" * ");
-                header_block.push_str(line);
-                header_block.push('\n');
+        // Generic types are factories: every use site must apply exactly as
+        // many codec arguments as the type declares parameters.
+        for (name, kind) in &emitted {
+            for (context, expr) in Self::exprs_with_context(name, kind) {
+                let mut arities = Vec::new();
+                expr.collect_type_ref_arities(&mut arities);
+                for (reference, found) in arities {
+                    let expected = self.type_arity(&reference);
+                    if self.types.contains_key(&reference) && found != expected {
+                        diagnostics.push(
+                            Diagnostic::new(DiagnosticKind::GenericArity {
+                                rust_path: reference,
+                                expected,
+                                found,
+                            })
+                            .referenced_by(context.clone()),
+                        );
+                    }
+                }
             }
         }
-        header_block.push_str(" */");
-        blocks.push(header_block);
-
-        blocks.push(import_block.trim_end().to_string());
 
-        if let Some(spec) = self.nondefault_format() {
-            blocks.push(format!("const FORMAT = r.format({{ {} }});", spec.options()));
+        // Import conflicts across everything emitted.
+        if let Err(conflicts) =
+            generate_import_block(&self.codec_exprs(emitted.keys().copied(), &emitted))
+        {
+            diagnostics.extend(conflicts.into_iter().map(Diagnostic::new));
         }
 
-        for name in &order {
-            let kind = emitted.get(name).expect("ordered names come from emitted");
-            blocks.push(self.emit_type(name, kind, &archived_names, &codec_names));
+        if !diagnostics.is_empty() {
+            return Err(Error::Codegen(diagnostics));
         }
+        Ok(emitted)
+    }
 
-        Ok(blocks.join("\n\n") + "\n")
+    /// Every codec expression of the types `names`, plus the JIT entry point
+    /// when it is enabled and there is anything to compile.
+    fn codec_exprs<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a String>,
+        emitted: &BTreeMap<&'a String, &'a TypeKind>,
+    ) -> Vec<CodecExpr> {
+        let mut exprs = Vec::new();
+        let mut any = false;
+        for name in names {
+            any = true;
+            exprs.extend(
+                Self::exprs_with_context(name, emitted[name])
+                    .into_iter()
+                    .map(|(_, expr)| expr.clone()),
+            );
+        }
+        if self.jit && any {
+            // Through the shared path so it dedups and conflict-checks like
+            // any user import.
+            let (jit_module, jit_fn) = self.direction.jit_entry();
+            exprs.push(CodecExpr::import_from(jit_module, jit_fn));
+        }
+        exprs
     }
 
-    fn topological_sort(emitted: &BTreeMap<&String, &TypeKind>) -> Vec<String> {
+    /// The emission order of `emitted`, per type the references that must be deferred,
+    /// and the recursive component each type belongs to.
+    ///
+    /// Types are grouped into strongly-connected components of the reference graph (Tarjan's),
+    /// and the components are emitted in dependency order (Kahn's); ties resolve in name order.
+    /// Within a recursive component, members emit in name order and every reference to
+    /// the type itself or to a member emitted later is a back-edge: it would read a `const`
+    /// before initialization, so it is returned for wrapping in `r.lazy`.
+    fn emission_order(
+        emitted: &BTreeMap<&String, &TypeKind>,
+    ) -> (Vec<String>, Deferred, BTreeMap<String, usize>) {
         let mut deps: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
         for (name, kind) in emitted {
             let mut refs = BTreeSet::new();
             for (_, expr) in Self::exprs_with_context(name, kind) {
                 expr.collect_type_refs(&mut refs);
             }
-            refs.retain(|reference| {
-                emitted.contains_key(reference) && reference != name.as_str()
-            });
+            refs.retain(|reference| emitted.contains_key(reference));
             deps.insert(name.as_str(), refs);
         }
 
-        let mut dependents: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
-        let mut in_degree: BTreeMap<&str, usize> = BTreeMap::new();
+        let components = StronglyConnected::components(&deps);
+        let mut component_of: BTreeMap<&str, usize> = BTreeMap::new();
+        for (index, members) in components.iter().enumerate() {
+            for member in members {
+                component_of.insert(member, index);
+            }
+        }
+
+        let mut dependents: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); components.len()];
+        let mut in_degree: Vec<usize> = vec![0; components.len()];
         for (name, type_deps) in &deps {
-            in_degree.insert(name, type_deps.len());
+            let component = component_of[name];
             for dep in type_deps {
-                dependents.entry(dep.as_str()).or_default().push(name);
+                let dep_component = component_of[dep.as_str()];
+                if dep_component != component && dependents[dep_component].insert(component) {
+                    in_degree[component] += 1;
+                }
             }
         }
 
-        let mut ready: BTreeSet<&str> = in_degree
-            .iter()
-            .filter(|(_, degree)| **degree == 0)
-            .map(|(name, _)| *name)
+        // Keyed by the component's first member so ties resolve in name order.
+        let mut ready: BTreeSet<(&str, usize)> = (0..components.len())
+            .filter(|&index| in_degree[index] == 0)
+            .map(|index| (components[index][0], index))
             .collect();
         let mut order: Vec<String> = Vec::new();
-        let mut done: BTreeSet<&str> = BTreeSet::new();
-
-        while let Some(name) = ready.pop_first() {
-            order.push(name.to_string());
-            done.insert(name);
-            if let Some(children) = dependents.get(name) {
-                for child in children {
-                    let degree = in_degree.get_mut(child).unwrap();
-                    *degree -= 1;
-                    if *degree == 0 {
-                        ready.insert(child);
-                    }
+        while let Some((_, index)) = ready.pop_first() {
+            order.extend(components[index].iter().map(|name| name.to_string()));
+            for &child in &dependents[index] {
+                in_degree[child] -= 1;
+                if in_degree[child] == 0 {
+                    ready.insert((components[child][0], child));
                 }
             }
         }
 
-        // Cycles (only possible through user-provided Raw/TypeRef loops):
-        // append the remaining names in stable order.
-        for name in deps.keys() {
-            if !done.contains(name) {
-                order.push((*name).to_string());
+        let position: BTreeMap<&str, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), index))
+            .collect();
+        let mut deferred: Deferred = BTreeMap::new();
+        for (name, type_deps) in &deps {
+            let back_edges: BTreeSet<String> = type_deps
+                .iter()
+                .filter(|dep| position[dep.as_str()] >= position[name])
+                .cloned()
+                .collect();
+            if !back_edges.is_empty() {
+                deferred.insert(name.to_string(), back_edges);
             }
         }
 
-        order
+        let component_of = component_of
+            .into_iter()
+            .map(|(name, component)| (name.to_string(), component))
+            .collect();
+        (order, deferred, component_of)
+    }
+
+    /// Rewrite every reference to a type in `lazy` (bare, or instantiating a generic factory)
+    /// into `r.lazy(() => ...)`, so it resolves on first use instead of at module load.
+    ///
+    /// A recursion point infers as `any`: TypeScript cannot infer a `const` from an
+    /// initializer that refers to itself, so the thunk's return type is annotated
+    /// with the direction's codec bound to break the cycle.
+    fn defer_references(
+        &self,
+        expr: &CodecExpr,
+        lazy: &BTreeSet<String>,
+        codec_names: &BTreeMap<String, String>,
+    ) -> CodecExpr {
+        let target = match expr {
+            CodecExpr::TypeRef(name) => Some(name),
+            CodecExpr::Call(callee, _) => match callee.as_ref() {
+                CodecExpr::TypeRef(name) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        };
+        if target.is_some_and(|name| lazy.contains(name)) {
+            let inner = expr
+                .render(codec_names)
+                .expect("type references are validated before emission");
+            let annotation = if self.allow_typescript_syntax {
+                format!(": {}", self.direction.codec_types().0)
+            } else {
+                String::new()
+            };
+            return CodecExpr::raw(format!("r.lazy((){annotation} => {inner})"));
+        }
+        match expr {
+            CodecExpr::Call(callee, args) => CodecExpr::Call(
+                Box::new(self.defer_references(callee, lazy, codec_names)),
+                args.iter()
+                    .map(|arg| self.defer_references(arg, lazy, codec_names))
+                    .collect(),
+            ),
+            CodecExpr::Object(entries) => CodecExpr::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| {
+                        (key.clone(), self.defer_references(value, lazy, codec_names))
+                    })
+                    .collect(),
+            ),
+            CodecExpr::Array(elements) => CodecExpr::Array(
+                elements
+                    .iter()
+                    .map(|element| self.defer_references(element, lazy, codec_names))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
     }
 
     fn emit_type(
         &self,
         name: &str,
         kind: &TypeKind,
-        archived_names: &BTreeMap<String, String>,
-        codec_names: &BTreeMap<String, String>,
+        emission: &Emission,
     ) -> String {
-        let archived = archived_names
+        let archived = emission
+            .archived_names
             .get(name)
             .expect("emitted types have archived names")
             .clone();
+        let codec_names = &emission.codec_names;
+        let lazy = emission.deferred.get(name).cloned().unwrap_or_default();
         let render = |expr: &CodecExpr| -> String {
+            let expr = if lazy.is_empty() {
+                expr.clone()
+            } else {
+                self.defer_references(expr, &lazy, codec_names)
+            };
             expr.render(codec_names)
                 .expect("type references are validated before emission")
         };
 
+        let type_name = self.exported_type_name(name);
+        let doc = self.jsdoc(name, "");
+        // A primitive-valued enum exports its values as an `as const` object
+        // named after the type, ahead of the codec.
+        let mut values = None;
+        let style = self.unit_enum_style_of(name, kind);
+
         let codec_expr = match kind {
+            TypeKind::Enum(variants) if style != UnitEnumStyle::Tagged => {
+                let mut object = format!("{doc}export const {type_name} = {{\n");
+                for def in variants {
+                    let variant = self.variant_casing.apply(def.variant.name());
+                    object.push_str(&self.jsdoc(&format!("{name}::{}", def.variant.name()), "  "));
+                    let value = match style {
+                        UnitEnumStyle::Number => def.discriminant.to_string(),
+                        _ => format!("'{variant}'"),
+                    };
+                    object.push_str(&format!("  {variant}: {value},\n"));
+                }
+                object.push('}');
+                if self.allow_typescript_syntax {
+                    object.push_str(" as const");
+                }
+                object.push(';');
+                values = Some(object);
+                match style {
+                    UnitEnumStyle::Number => format!("r.numberEnum({type_name})"),
+                    _ => {
+                        let discriminants = CodecExpr::object(variants.iter().map(|def| {
+                            (
+                                self.variant_casing.apply(def.variant.name()),
+                                CodecExpr::LitInt(u64::from(def.discriminant)),
+                            )
+                        }));
+                        format!("r.stringEnum({})", render(&discriminants))
+                    }
+                }
+            }
             TypeKind::Struct(fields) => {
                 if fields.is_empty() {
                     "r.struct({})".to_string()
                 } else {
                     let mut body = String::from("r.struct({\n");
                     for (field, expr) in fields {
+                        body.push_str(&self.jsdoc(&format!("{name}.{field}"), "  "));
                         body.push_str(&format!(
                             "  {}: {},\n",
                             self.field_casing.apply(field),
@@ -832,13 +1948,33 @@ impl CodeGenerator {
                     "r.taggedEnum({})".to_string()
                 } else {
                     let mut body = String::from("r.taggedEnum({\n");
-                    for variant in variants {
+                    for VariantDef { variant, .. } in variants {
                         let value = match variant {
                             EnumVariant::Unit(_) => "null".to_string(),
                             EnumVariant::Newtype(_, expr) => render(expr),
                             EnumVariant::Tuple(_, exprs) => {
                                 render(&CodecExpr::array(exprs.iter().cloned()))
                             }
+                            EnumVariant::Struct(vname, fields)
+                                if fields.iter().any(|(field, _)| {
+                                    !self.jsdoc(&format!("{name}::{vname}.{field}"), "").is_empty()
+                                }) =>
+                            {
+                                // Documented fields need a line each for their JSDoc.
+                                let mut record = String::from("{\n");
+                                for (field, expr) in fields {
+                                    record.push_str(
+                                        &self.jsdoc(&format!("{name}::{vname}.{field}"), "    "),
+                                    );
+                                    record.push_str(&format!(
+                                        "    {}: {},\n",
+                                        self.field_casing.apply(field),
+                                        render(expr)
+                                    ));
+                                }
+                                record.push_str("  }");
+                                record
+                            }
                             EnumVariant::Struct(_, fields) => {
                                 let record = CodecExpr::object(fields.iter().map(
                                     |(field, expr)| {
@@ -848,13 +1984,18 @@ impl CodeGenerator {
                                 render(&record)
                             }
                         };
+                        body.push_str(&self.jsdoc(&format!("{name}::{}", variant.name()), "  "));
                         body.push_str(&format!(
                             "  {}: {},\n",
                             self.variant_casing.apply(variant.name()),
                             value
                         ));
                     }
-                    body.push_str("})");
+                    body.push('}');
+                    if let Some(options) = VariantDef::options_expr(variants, self.variant_casing) {
+                        body.push_str(&format!(", {}", render(&options)));
+                    }
+                    body.push(')');
                     body
                 }
             }
@@ -866,30 +2007,510 @@ impl CodeGenerator {
             None => codec_expr,
         };
 
+        let params = self.type_params.get(name).map(Vec::as_slice).unwrap_or_default();
+        let declaration = (self.allow_typescript_syntax && self.type_style == TypeStyle::Declared)
+            .then(|| self.declare_type(name, kind, style, &type_name, params, &emission.type_names));
+        if !params.is_empty() {
+            return self.emit_generic_type(
+                name,
+                emission,
+                params,
+                &codec_expr,
+                &doc,
+                declaration,
+            );
+        }
+
+        let annotation = if declaration.is_some() {
+            format!(": {}<{type_name}>", self.direction.codec_types().1)
+        } else {
+            String::new()
+        };
+        let raw_const = emission.raw_const();
         let mut block = if self.jit {
             // The compile functions detect a withFormat-bound codec and
             // prewarm for the bound format, so the JIT wrap stays outermost.
             let jit_fn = self.direction.jit_entry().1;
             format!(
-                "const {archived}$ = {codec_expr};\n\n\
-                 export const {archived} = {jit_fn}({archived}$);"
+                "{raw_const} {archived}${annotation} = {codec_expr};\n\n\
+                 {doc}export const {archived}{annotation} = {jit_fn}({archived}$);"
+            )
+        } else {
+            format!("{doc}export const {archived}{annotation} = {codec_expr};")
+        };
+        if let Some(declaration) = declaration {
+            block = format!("{declaration}\n\n{block}");
+        } else if self.allow_typescript_syntax {
+            block.push_str(&format!(
+                "\n\n{doc}export type {type_name} = r.Infer<typeof {archived}>;"
+            ));
+        }
+        if let Some(values) = values {
+            block = format!("{values}\n\n{block}");
+        }
+        block
+    }
+
+    /// The written-out value type of a type under [`TypeStyle::Declared`]:
+    /// an `interface` for a struct, a discriminated union for an enum.
+    fn declare_type(
+        &self,
+        name: &str,
+        kind: &TypeKind,
+        style: UnitEnumStyle,
+        type_name: &str,
+        params: &[String],
+        type_names: &BTreeMap<String, String>,
+    ) -> String {
+        let codec_type = self.direction.codec_types().1;
+        let ts = |expr: &CodecExpr| expr.ts_type(type_names, codec_type);
+        let doc = self.jsdoc(name, "");
+        let generics = if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        };
+        match kind {
+            TypeKind::Struct(fields) if fields.is_empty() => {
+                format!("{doc}export interface {type_name}{generics} {{}}")
+            }
+            TypeKind::Struct(fields) => {
+                let mut body = format!("{doc}export interface {type_name}{generics} {{\n");
+                for (field, expr) in fields {
+                    body.push_str(&self.jsdoc(&format!("{name}.{field}"), "  "));
+                    body.push_str(&format!(
+                        "  {}: {};\n",
+                        self.field_casing.apply(field),
+                        ts(expr)
+                    ));
+                }
+                body.push('}');
+                body
+            }
+            TypeKind::Enum(variants) if variants.is_empty() => {
+                format!("{doc}export type {type_name}{generics} = never;")
+            }
+            TypeKind::Enum(variants) if style != UnitEnumStyle::Tagged => {
+                let values: Vec<String> = variants
+                    .iter()
+                    .map(|def| match style {
+                        UnitEnumStyle::Number => def.discriminant.to_string(),
+                        _ => format!("'{}'", self.variant_casing.apply(def.variant.name())),
+                    })
+                    .collect();
+                format!("{doc}export type {type_name} = {};", values.join(" | "))
+            }
+            TypeKind::Enum(variants) => {
+                let mut body = format!("{doc}export type {type_name}{generics} =");
+                for VariantDef { variant, .. } in variants {
+                    let path = format!("{name}::{}", variant.name());
+                    let value = match variant {
+                        EnumVariant::Unit(_) => "null".to_string(),
+                        EnumVariant::Newtype(_, expr) => ts(expr),
+                        EnumVariant::Tuple(_, exprs) => ts(&CodecExpr::array(exprs.iter().cloned())),
+                        EnumVariant::Struct(_, fields) => ts(&CodecExpr::object(
+                            fields
+                                .iter()
+                                .map(|(field, expr)| (self.field_casing.apply(field), expr.clone())),
+                        )),
+                    };
+                    body.push('\n');
+                    body.push_str(&self.jsdoc(&path, "  "));
+                    body.push_str(&format!(
+                        "  | {{ tag: '{}'; value: {value} }}",
+                        self.variant_casing.apply(variant.name())
+                    ));
+                }
+                body.push(';');
+                body
+            }
+            TypeKind::Alias(expr) => {
+                format!("{doc}export type {type_name}{generics} = {};", ts(expr))
+            }
+        }
+    }
+
+    /// Emit a generic type as a codec factory taking one codec per type parameter.
+    ///
+    /// The value type is recovered by instantiating the factory with codecs
+    /// inferring to the type parameters: `Page<T>` is
+    /// `r.Infer<ReturnType<typeof ArchivedPage<r.Codec<T>>>>`.
+    fn emit_generic_type(
+        &self,
+        type_name: &str,
+        emission: &Emission,
+        params: &[String],
+        codec_expr: &str,
+        doc: &str,
+        declaration: Option<String>,
+    ) -> String {
+        let name = &emission.type_names[type_name];
+        let archived = &emission.archived_names[type_name];
+        let raw_const = emission.raw_const();
+        let (any_codec, codec_type) = self.direction.codec_types();
+        let args: Vec<String> = params.iter().map(|param| param_ident(param)).collect();
+        let args = args.join(", ");
+        let signature = if declaration.is_some() {
+            // Codec arguments typed by their values, returning the declared type.
+            let typed_args: Vec<String> = params
+                .iter()
+                .map(|param| format!("{}: {codec_type}<{param}>", param_ident(param)))
+                .collect();
+            format!(
+                "<{}>({}): {codec_type}<{name}<{}>>",
+                params.join(", "),
+                typed_args.join(", "),
+                params.join(", ")
+            )
+        } else if self.allow_typescript_syntax {
+            let bounds: Vec<String> = params
+                .iter()
+                .map(|param| format!("{param} extends {any_codec}"))
+                .collect();
+            let typed_args: Vec<String> = params
+                .iter()
+                .map(|param| format!("{}: {param}", param_ident(param)))
+                .collect();
+            format!("<{}>({})", bounds.join(", "), typed_args.join(", "))
+        } else {
+            format!("({args})")
+        };
+
+        let mut block = if self.jit {
+            // Each instantiation compiles its own concrete codec graph.
+            let jit_fn = self.direction.jit_entry().1;
+            format!(
+                "{raw_const} {archived}$ = {signature} => {codec_expr};\n\n\
+                 {doc}export const {archived} = {signature} => {jit_fn}({archived}$({args}));"
             )
         } else {
-            format!("export const {archived} = {codec_expr};")
+            format!("{doc}export const {archived} = {signature} => {codec_expr};")
         };
-        if self.allow_typescript_syntax {
+        if let Some(declaration) = declaration {
+            block = format!("{declaration}\n\n{block}");
+        } else if self.allow_typescript_syntax {
+            let instantiation: Vec<String> = params
+                .iter()
+                .map(|param| format!("{codec_type}<{param}>"))
+                .collect();
             block.push_str(&format!(
-                "\n\nexport type {name} = r.Infer<typeof {archived}>;"
+                "\n\n{doc}export type {name}<{}> = r.Infer<ReturnType<typeof {archived}<{}>>>;",
+                params.join(", "),
+                instantiation.join(", "),
             ));
         }
         block
     }
 
     /// Generate the bindings and write them to `path`.
-    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+    ///
+    /// A file that already holds the bindings is left untouched, so its modification time
+    /// does not invalidate bundler caches or trigger a reload; otherwise the bindings are written
+    /// to a temporary file and renamed over `path`, so readers never see a partial file.
+    /// Returns whether the file changed.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<bool, Error> {
         let code = self.generate()?;
-        fs::write(path, code)?;
-        Ok(())
+        write_if_changed(path.as_ref(), &code)
+    }
+
+    /// Generate the bindings as [`generate_modules`](Self::generate_modules) does
+    /// and write each module under `dir`, creating directories as needed.
+    ///
+    /// Modules are written like [`write_to_file`](Self::write_to_file) does;
+    /// returns whether any file changed. The written files are listed in a `.rkyv-js-codegen`
+    /// manifest in `dir`, and files the previous run listed but this one no longer generates
+    /// are deleted. Files the manifest does not list are never touched.
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>) -> Result<bool, Error> {
+        let dir = dir.as_ref();
+        let modules = self.generate_modules()?;
+        let mut changed = false;
+        for orphan in orphaned_modules(dir, &modules)? {
+            fs::remove_file(orphan)?;
+            changed = true;
+        }
+        fs::create_dir_all(dir)?;
+        for (file, code) in &modules {
+            let path = dir.join(file);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            changed |= write_if_changed(&path, code)?;
+        }
+        changed |= write_if_changed(&dir.join(MANIFEST), &manifest(&modules))?;
+        Ok(changed)
+    }
+
+    /// Generate the bindings in memory and compare them with `path`, without writing.
+    ///
+    /// A file that differs or does not exist fails with [`Error::Stale`], carrying a unified diff
+    /// from the file on disk to the generated bindings. Use it in CI or a test to catch
+    /// committed bindings that are out of date:
+    ///
+    /// ```no_run
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// CodeGenerator::new()
+    ///     .add_crate_root("src/lib.rs")?
+    ///     .check_file("generated/bindings.ts")?;
+    /// # Ok::<(), rkyv_js_codegen::Error>(())
+    /// ```
+    pub fn check_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        match stale_diff(path, &self.generate()?)? {
+            Some(diff) => Err(Error::Stale {
+                path: path.to_path_buf(),
+                diff,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// [`check_file`](Self::check_file) for every module [`write_to_dir`](Self::write_to_dir) would write,
+    /// and its manifest; the diff of [`Error::Stale`] covers all stale modules, and the deletion
+    /// of listed files `write_to_dir` would remove.
+    pub fn check_dir(&self, dir: impl AsRef<Path>) -> Result<(), Error> {
+        let dir = dir.as_ref();
+        let modules = self.generate_modules()?;
+        let mut diff = String::new();
+        for orphan in orphaned_modules(dir, &modules)? {
+            diff.push_str(&orphan_diff(&orphan)?);
+        }
+        for (file, code) in &modules {
+            if let Some(module_diff) = stale_diff(&dir.join(file), code)? {
+                diff.push_str(&module_diff);
+            }
+        }
+        if let Some(manifest_diff) = stale_diff(&dir.join(MANIFEST), &manifest(&modules))? {
+            diff.push_str(&manifest_diff);
+        }
+        if diff.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Stale {
+                path: dir.to_path_buf(),
+                diff,
+            })
+        }
+    }
+
+    /// The build-script form of [`write_to_file`](Self::write_to_file): writes the bindings,
+    /// or only [checks](Self::check_file) them when the `RKYV_JS_CODEGEN_CHECK` environment variable
+    /// is set, so CI can run `RKYV_JS_CODEGEN_CHECK=1 cargo build` to fail on stale committed bindings.
+    ///
+    /// ```no_run
+    /// // build.rs
+    /// use rkyv_js_codegen::CodeGenerator;
+    ///
+    /// fn main() -> Result<(), rkyv_js_codegen::Error> {
+    ///     CodeGenerator::new()
+    ///         .add_crate_root("src/lib.rs")?
+    ///         .write_or_check_file("generated/bindings.ts")?;
+    ///     println!("cargo:rerun-if-env-changed=RKYV_JS_CODEGEN_CHECK");
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Returns whether the file changed, which it never does when checking.
+    pub fn write_or_check_file(&self, path: impl AsRef<Path>) -> Result<bool, Error> {
+        if std::env::var_os(CHECK_ENV).is_some_and(|value| !value.is_empty() && value != "0") {
+            self.check_file(path).map(|()| false)
+        } else {
+            self.write_to_file(path)
+        }
+    }
+}
+
+/// The environment variable switching [`CodeGenerator::write_or_check_file`] to checking.
+const CHECK_ENV: &str = "RKYV_JS_CODEGEN_CHECK";
+
+/// Write `contents` to `path` unless it already holds exactly that,
+/// through a sibling temporary file renamed into place. Returns whether `path` changed.
+fn write_if_changed(path: &Path, contents: &str) -> Result<bool, Error> {
+    if fs::read(path).is_ok_and(|current| current == contents.as_bytes()) {
+        return Ok(false);
+    }
+    let Some(file_name) = path.file_name() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} is not a file path", path.display()),
+        )
+        .into());
+    };
+    let temporary = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::write(&temporary, contents)?;
+    if let Err(err) = fs::rename(&temporary, path) {
+        let _ = fs::remove_file(&temporary);
+        return Err(err.into());
+    }
+    Ok(true)
+}
+
+/// A unified diff from the contents of `path` to `expected`,
+/// or `None` when they are identical. A missing file diffs as `/dev/null`.
+fn stale_diff(path: &Path, expected: &str) -> Result<Option<String>, Error> {
+    let (current, old_header) = match fs::read_to_string(path) {
+        Ok(current) if current == expected => return Ok(None),
+        Ok(current) => (current, path.display().to_string()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            (String::new(), "/dev/null".to_string())
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let new_header = format!("{} (generated)", path.display());
+    let diff = similar::TextDiff::from_lines(current.as_str(), expected)
+        .unified_diff()
+        .header(&old_header, &new_header)
+        .to_string();
+    Ok(Some(diff))
+}
+
+/// A unified diff deleting the generated file at `path`.
+fn orphan_diff(path: &Path) -> Result<String, Error> {
+    let current = fs::read_to_string(path)?;
+    let diff = similar::TextDiff::from_lines(current.as_str(), "")
+        .unified_diff()
+        .header(&path.display().to_string(), "/dev/null")
+        .to_string();
+    Ok(diff)
+}
+
+/// The file [`CodeGenerator::write_to_dir`] lists the modules it wrote in,
+/// so a later run deletes only files it owns.
+const MANIFEST: &str = ".rkyv-js-codegen";
+
+/// The manifest listing `modules`, one relative path per line.
+fn manifest(modules: &BTreeMap<String, String>) -> String {
+    let mut manifest = String::from("# Files written by rkyv-js-codegen. DO NOT EDIT MANUALLY\n");
+    for file in modules.keys() {
+        manifest.push_str(file);
+        manifest.push('\n');
+    }
+    manifest
+}
+
+/// The files the manifest in `dir` lists that are not among `modules` but still exist.
+/// Entries that would leave `dir` are ignored.
+fn orphaned_modules(dir: &Path, modules: &BTreeMap<String, String>) -> Result<Vec<PathBuf>, Error> {
+    let listed = match fs::read_to_string(dir.join(MANIFEST)) {
+        Ok(listed) => listed,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let orphans = listed
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|file| !modules.contains_key(*file))
+        .filter(|file| {
+            Path::new(file)
+                .components()
+                .all(|component| matches!(component, std::path::Component::Normal(_)))
+        })
+        .map(|file| dir.join(file))
+        .filter(|path| path.is_file())
+        .collect();
+    Ok(orphans)
+}
+
+/// Whether `path` names `type_name` itself or a module containing it;
+/// the empty path matches every type.
+fn path_matches(path: &str, type_name: &str) -> bool {
+    path.is_empty()
+        || type_name == path
+        || type_name
+            .strip_prefix(path)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// The import specifier of output module `to` from output module `from`
+/// (`api/users` to `common` is `../common.ts`).
+fn relative_specifier(from: &str, to: &str, extension: &str) -> String {
+    let from_dir: Vec<&str> = from.split('/').collect();
+    let from_dir = &from_dir[..from_dir.len() - 1];
+    let to: Vec<&str> = to.split('/').collect();
+    let shared = from_dir
+        .iter()
+        .zip(&to)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut specifier = if shared == from_dir.len() {
+        "./".to_string()
+    } else {
+        "../".repeat(from_dir.len() - shared)
+    };
+    specifier.push_str(&to[shared..].join("/"));
+    format!("{specifier}.{extension}")
+}
+
+/// Tarjan's strongly-connected-components algorithm over the type reference graph.
+struct StronglyConnected<'a> {
+    deps: &'a BTreeMap<&'a str, BTreeSet<String>>,
+    index: BTreeMap<&'a str, usize>,
+    low_link: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> StronglyConnected<'a> {
+    /// Every component, each with its members in name order.
+    fn components(deps: &'a BTreeMap<&'a str, BTreeSet<String>>) -> Vec<Vec<&'a str>> {
+        let mut state = StronglyConnected {
+            deps,
+            index: BTreeMap::new(),
+            low_link: BTreeMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            components: Vec::new(),
+        };
+        for &name in deps.keys() {
+            if !state.index.contains_key(name) {
+                state.visit(name);
+            }
+        }
+        for component in &mut state.components {
+            component.sort_unstable();
+        }
+        state.components
+    }
+
+    fn visit(&mut self, name: &'a str) {
+        let index = self.index.len();
+        self.index.insert(name, index);
+        self.low_link.insert(name, index);
+        self.stack.push(name);
+        self.on_stack.insert(name);
+
+        for dep in &self.deps[name] {
+            let (&dep, _) = self
+                .deps
+                .get_key_value(dep.as_str())
+                .expect("references are filtered to emitted types");
+            if !self.index.contains_key(dep) {
+                self.visit(dep);
+                let low = self.low_link[name].min(self.low_link[dep]);
+                self.low_link.insert(name, low);
+            } else if self.on_stack.contains(dep) {
+                let low = self.low_link[name].min(self.index[dep]);
+                self.low_link.insert(name, low);
+            }
+        }
+
+        if self.low_link[name] == index {
+            let mut component = Vec::new();
+            loop {
+                let member = self.stack.pop().expect("the root is on the stack");
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == name {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
     }
 }
 
@@ -897,6 +2518,7 @@ impl CodeGenerator {
 mod tests {
     use super::*;
     use crate::expr::codec;
+    use crate::test_util::TempDir;
 
     fn diagnostics(error: Error) -> Vec<Diagnostic> {
         match error {
@@ -955,6 +2577,146 @@ mod tests {
         assert!(code.contains("export type MixedAlign = r.Infer<typeof ArchivedMixedAlign>;"));
     }
 
+    #[test]
+    fn explicit_discriminants_follow_variant_casing() {
+        let mut generator = CodeGenerator::new();
+        generator.set_variant_casing(Casing::Snake);
+        generator.add_enum_with_discriminants(
+            "Level",
+            [
+                (EnumVariant::Unit("LowPower".to_string()), 7),
+                (EnumVariant::Newtype("Custom".to_string(), codec::u16()), 200),
+            ],
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export const ArchivedLevel = r.taggedEnum({\n\
+             \x20 low_power: null,\n\
+             \x20 custom: r.u16,\n\
+             }, { discriminants: { low_power: 7, custom: 200 } });"
+        ));
+    }
+
+    #[test]
+    fn shared_discriminants_are_diagnosed() {
+        let mut generator = CodeGenerator::new();
+        generator.add_enum_with_discriminants(
+            "Flag",
+            [
+                (EnumVariant::Unit("On".to_string()), 1),
+                (EnumVariant::Unit("Yes".to_string()), 1),
+            ],
+        );
+        let Err(Error::Codegen(diagnostics)) = generator.generate() else {
+            panic!("expected a duplicate discriminant");
+        };
+        assert_eq!(
+            diagnostics[0].kind,
+            DiagnosticKind::DuplicateDiscriminant {
+                value: 1,
+                variants: vec!["On".to_string(), "Yes".to_string()],
+            }
+        );
+        assert_eq!(diagnostics[0].referenced_by.as_deref(), Some("Flag"));
+    }
+
+    #[test]
+    fn doc_comments_are_escaped_and_can_be_disabled() {
+        let mut generator = CodeGenerator::new();
+        generator.set_unit_enum_style(UnitEnumStyle::Number);
+        generator.add_enum("Status", [EnumVariant::Unit("Active".to_string())]);
+        generator.set_doc("Status", "Ends with */ here.");
+        generator.set_doc("Status::Active", "Signed in.");
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "/** Ends with *\\/ here. */\n\
+             export const Status = {\n\
+             \x20 /** Signed in. */\n\
+             \x20 Active: 0,\n\
+             } as const;\n\n\
+             /** Ends with *\\/ here. */\n\
+             export const ArchivedStatus = r.numberEnum(Status);"
+        ));
+
+        generator.set_doc_comments(false);
+        let code = generator.generate().unwrap();
+        assert!(!code.contains("/** "));
+    }
+
+    #[test]
+    fn string_unit_enums_export_a_const_object() {
+        let mut generator = CodeGenerator::new();
+        generator.set_unit_enum_style(UnitEnumStyle::String);
+        generator.set_variant_casing(Casing::Camel);
+        generator.add_enum_with_discriminants(
+            "Status",
+            [
+                (EnumVariant::Unit("Active".to_string()), 1),
+                (EnumVariant::Unit("OnHold".to_string()), 2),
+            ],
+        );
+        generator.add_enum(
+            "Shape",
+            [EnumVariant::Newtype("Circle".to_string(), codec::f64())],
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export const Status = {\n\
+             \x20 active: 'active',\n\
+             \x20 onHold: 'onHold',\n\
+             } as const;\n\n\
+             export const ArchivedStatus = r.stringEnum({ active: 1, onHold: 2 });\n\n\
+             export type Status = r.Infer<typeof ArchivedStatus>;"
+        ));
+        // Enums with data keep the tagged form.
+        assert!(code.contains("export const ArchivedShape = r.taggedEnum({"));
+    }
+
+    #[test]
+    fn unit_enum_style_per_type_overrides_the_default() {
+        let mut generator = CodeGenerator::new();
+        generator.set_unit_enum_style(UnitEnumStyle::Number);
+        generator.set_unit_enum_style_for("Legacy", UnitEnumStyle::Tagged);
+        generator.allow_typescript_syntax(false);
+        generator.add_enum("Legacy", [EnumVariant::Unit("Old".to_string())]);
+        generator.add_enum_with_discriminants(
+            "Level",
+            [
+                (EnumVariant::Unit("Low".to_string()), 3),
+                (EnumVariant::Unit("High".to_string()), 4),
+            ],
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains("export const ArchivedLegacy = r.taggedEnum({\n  Old: null,\n});"));
+        assert!(code.contains(
+            "export const Level = {\n  Low: 3,\n  High: 4,\n};\n\n\
+             export const ArchivedLevel = r.numberEnum(Level);"
+        ));
+    }
+
+    #[test]
+    fn unit_enum_style_targets_must_be_field_less_enums() {
+        let mut generator = CodeGenerator::new();
+        generator.set_unit_enum_style_for("Point", UnitEnumStyle::String);
+        generator.set_unit_enum_style_for("Missing", UnitEnumStyle::Number);
+        generator.add_struct("Point", [("x", codec::f64())]);
+        let Err(Error::Codegen(diagnostics)) = generator.generate() else {
+            panic!("expected invalid style targets");
+        };
+        let targets: Vec<_> = diagnostics.iter().map(|d| d.kind.clone()).collect();
+        assert_eq!(
+            targets,
+            [
+                DiagnosticKind::UnitEnumStyleTarget {
+                    type_name: "Missing".to_string()
+                },
+                DiagnosticKind::UnitEnumStyleTarget {
+                    type_name: "Point".to_string()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn alias_emission_snapshot() {
         let mut generator = CodeGenerator::new();
@@ -997,33 +2759,117 @@ mod tests {
             )],
         );
         let code = generator.generate().unwrap();
-        assert!(code.contains("import { hashMap, hashSet } from 'rkyv-js/lib/hashmap';"));
-        assert_eq!(code.matches("hashSet }").count(), 1);
+        assert!(code.contains("import { hashMap, hashSet } from 'rkyv-js/lib/hashmap';"));
+        assert_eq!(code.matches("hashSet }").count(), 1);
+    }
+
+    #[test]
+    fn import_conflict_is_reported() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("A", [("x", CodecExpr::import_from("pkg-a", "codec"))]);
+        generator.add_struct("B", [("y", CodecExpr::import_from("pkg-b", "codec"))]);
+        let errors = diagnostics(generator.generate().unwrap_err());
+        assert!(errors.iter().any(|diagnostic| matches!(
+            &diagnostic.kind,
+            DiagnosticKind::ImportConflict { export, .. } if export == "codec"
+        )));
+    }
+
+    #[test]
+    fn topo_sort_handles_forward_references() {
+        let mut generator = CodeGenerator::new();
+        // "AOuter" sorts before "Inner" alphabetically, but references it.
+        generator.add_struct("AOuter", [("inner", codec::named("Inner"))]);
+        generator.add_struct("Inner", [("value", codec::u32())]);
+        let code = generator.generate().unwrap();
+        let inner_pos = code.find("export const ArchivedInner").unwrap();
+        let outer_pos = code.find("export const ArchivedAOuter").unwrap();
+        assert!(inner_pos < outer_pos, "dependency must be emitted first");
+        assert!(code.contains("inner: ArchivedInner,"));
+    }
+
+    #[test]
+    fn self_reference_is_deferred_through_lazy() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct(
+            "Node",
+            [("value", codec::u32()), ("children", codec::vec(codec::named("Node")))],
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains("children: r.vec(r.lazy((): r.AnyCodec => ArchivedNode)),"));
+        assert!(code.contains("export type Node = r.Infer<typeof ArchivedNode>;"));
+    }
+
+    #[test]
+    fn mutual_recursion_defers_only_back_edges() {
+        let mut generator = CodeGenerator::new();
+        // Leaf <- Expr <-> Stmt: the cycle emits after its dependency, in name
+        // order, and only the reference to the later member is deferred.
+        generator.add_struct("Leaf", [("value", codec::u32())]);
+        generator.add_enum(
+            "Expr",
+            [
+                EnumVariant::Newtype("Lit".to_string(), codec::named("Leaf")),
+                EnumVariant::Newtype("Block".to_string(), codec::vec(codec::named("Stmt"))),
+            ],
+        );
+        generator.add_enum(
+            "Stmt",
+            [EnumVariant::Newtype("Eval".to_string(), codec::boxed(codec::named("Expr")))],
+        );
+        let code = generator.generate().unwrap();
+        let leaf = code.find("export const ArchivedLeaf").unwrap();
+        let expr = code.find("export const ArchivedExpr").unwrap();
+        let stmt = code.find("export const ArchivedStmt").unwrap();
+        assert!(leaf < expr && expr < stmt);
+        assert!(code.contains("Lit: ArchivedLeaf,"));
+        assert!(code.contains("Block: r.vec(r.lazy((): r.AnyCodec => ArchivedStmt)),"));
+        assert!(code.contains("Eval: r.box(ArchivedExpr),"));
     }
 
     #[test]
-    fn import_conflict_is_reported() {
+    fn dependents_of_a_cycle_emit_after_it() {
         let mut generator = CodeGenerator::new();
-        generator.add_struct("A", [("x", CodecExpr::import_from("pkg-a", "codec"))]);
-        generator.add_struct("B", [("y", CodecExpr::import_from("pkg-b", "codec"))]);
-        let errors = diagnostics(generator.generate().unwrap_err());
-        assert!(errors.iter().any(|diagnostic| matches!(
-            &diagnostic.kind,
-            DiagnosticKind::ImportConflict { export, .. } if export == "codec"
-        )));
+        generator.add_struct("AUser", [("tree", codec::named("Tree"))]);
+        generator.add_struct("Tree", [("children", codec::vec(codec::named("Tree")))]);
+        let code = generator.generate().unwrap();
+        assert!(code.find("ArchivedTree =").unwrap() < code.find("ArchivedAUser =").unwrap());
+        assert!(code.contains("tree: ArchivedTree,"));
     }
 
     #[test]
-    fn topo_sort_handles_forward_references() {
+    fn lazy_in_js_mode_has_no_annotation() {
         let mut generator = CodeGenerator::new();
-        // "AOuter" sorts before "Inner" alphabetically, but references it.
-        generator.add_struct("AOuter", [("inner", codec::named("Inner"))]);
-        generator.add_struct("Inner", [("value", codec::u32())]);
+        generator.allow_typescript_syntax(false);
+        generator.add_struct("Node", [("next", codec::option(codec::boxed(codec::named("Node"))))]);
         let code = generator.generate().unwrap();
-        let inner_pos = code.find("export const ArchivedInner").unwrap();
-        let outer_pos = code.find("export const ArchivedAOuter").unwrap();
-        assert!(inner_pos < outer_pos, "dependency must be emitted first");
-        assert!(code.contains("inner: ArchivedInner,"));
+        assert!(code.contains("next: r.option(r.box(r.lazy(() => ArchivedNode))),"));
+    }
+
+    #[test]
+    fn recursive_generic_defers_the_instantiation() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct(
+            "Tree",
+            [
+                ("value", codec::param("T")),
+                ("children", codec::vec(codec::instantiate("Tree", [codec::param("T")]))),
+            ],
+        );
+        generator.set_type_params("Tree", ["T"]);
+        let code = generator.generate().unwrap();
+        assert!(code.contains("children: r.vec(r.lazy((): r.AnyCodec => ArchivedTree(t))),"));
+    }
+
+    #[test]
+    fn set_jit_defers_to_raw_codecs() {
+        let mut generator = CodeGenerator::new();
+        generator.set_jit(true);
+        generator.set_direction(Direction::Decode);
+        generator.add_struct("Node", [("children", codec::vec(codec::named("Node")))]);
+        let code = generator.generate().unwrap();
+        assert!(code.contains("children: r.vec(r.lazy((): r.AnyDecoder => ArchivedNode$)),"));
+        assert!(code.contains("export const ArchivedNode = compileDecoder(ArchivedNode$);"));
     }
 
     #[test]
@@ -1094,6 +2940,68 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn roots_prune_to_reachable_types() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("User", [("id", codec::u32())]);
+        generator.add_struct("Page", [("users", codec::vec(codec::named("User")))]);
+        generator.add_struct("Cache", [("hits", codec::u64())]);
+        generator.add_failed_type(
+            "Internal".to_string(),
+            vec![Diagnostic::new(DiagnosticKind::UnsupportedFieldType {
+                rust_type: "Mutex<u8>".to_string(),
+            })],
+            None,
+        );
+        generator.set_roots(["Page"]);
+        let code = generator.generate().unwrap();
+        assert!(code.contains("export const ArchivedUser = "));
+        assert!(code.contains("export const ArchivedPage = "));
+        assert!(!code.contains("Cache"));
+    }
+
+    #[test]
+    fn missing_root_is_a_diagnostic() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("User", [("id", codec::u32())]);
+        generator.set_roots(["User", "Usr"]);
+        let errors = diagnostics(generator.generate().unwrap_err());
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0].kind,
+            DiagnosticKind::UnknownRoot { type_name } if type_name == "Usr"
+        ));
+    }
+
+    #[test]
+    fn allow_and_deny_paths_filter_types() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("api::User", [("id", codec::u32())]);
+        generator.add_struct("api::internal::Token", [("raw", codec::string())]);
+        generator.add_struct("db::Row", [("id", codec::u32())]);
+        generator.allow_path("api");
+        generator.deny_path("api::internal");
+        let code = generator.generate().unwrap();
+        assert!(code.contains("export const ArchivedUser = "));
+        assert!(!code.contains("Token"));
+        assert!(!code.contains("Row"));
+    }
+
+    #[test]
+    fn reference_to_denied_type_is_a_diagnostic() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("Secret", [("key", codec::string())]);
+        generator.add_struct("Session", [("secret", codec::named("Secret"))]);
+        generator.deny_path("Secret");
+        let errors = diagnostics(generator.generate().unwrap_err());
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0].kind,
+            DiagnosticKind::ExcludedType { name } if name == "Secret"
+        ));
+        assert_eq!(errors[0].referenced_by.as_deref(), Some("Session.secret"));
+    }
+
     #[test]
     fn archived_name_of_accessor() {
         let mut generator = CodeGenerator::new();
@@ -1104,6 +3012,26 @@ mod tests {
         assert_eq!(generator.archived_name_of("Bar"), None);
     }
 
+    #[test]
+    fn module_paths_fold_into_names_only_when_ambiguous() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("api::v1::Token", [("x", codec::u32())]);
+        generator.add_struct("api::v1::User", [("x", codec::u32())]);
+        assert_eq!(generator.archived_name_of("api::v1::User").as_deref(), Some("ArchivedUser"));
+        generator.add_struct("User", [("x", codec::u32())]);
+        assert_eq!(
+            generator.archived_name_of("api::v1::User").as_deref(),
+            Some("ArchivedApiV1User")
+        );
+        assert_eq!(generator.archived_name_of("User").as_deref(), Some("ArchivedUser"));
+        assert_eq!(
+            generator.archived_name_of("api::v1::Token").as_deref(),
+            Some("ArchivedToken")
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains("export type ApiV1User = r.Infer<typeof ArchivedApiV1User>;"));
+    }
+
     #[test]
     fn field_casing_defaults_to_preserve() {
         let mut generator = CodeGenerator::new();
@@ -1256,6 +3184,320 @@ mod tests {
         assert!(code.contains("fooBar: r.u32,"));
     }
 
+    #[test]
+    fn generic_struct_emission_snapshot() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct(
+            "Page",
+            [("items", codec::vec(codec::param("T"))), ("total", codec::u64())],
+        );
+        generator.set_type_params("Page", ["T"]);
+        let code = generator.generate().unwrap();
+        assert!(code.ends_with(
+            "export const ArchivedPage = <T extends r.AnyCodec>(t: T) => r.struct({\n\
+             \x20 items: r.vec(t),\n\
+             \x20 total: r.u64,\n\
+             });\n\
+             \n\
+             export type Page<T> = r.Infer<ReturnType<typeof ArchivedPage<r.Codec<T>>>>;\n"
+        ));
+    }
+
+    #[test]
+    fn declared_types_reference_each_other_by_name() {
+        let mut generator = CodeGenerator::new();
+        generator.set_type_style(TypeStyle::Declared);
+        generator.set_field_casing(Casing::Camel);
+        generator.set_variant_casing(Casing::Snake);
+        generator.set_archived_name("User", "UserCodec");
+        generator.add_struct(
+            "User",
+            [("user_id", codec::u64()), ("nick", codec::option(codec::string()))],
+        );
+        generator.set_doc("User.user_id", "Stable across renames.");
+        generator.add_enum(
+            "Event",
+            [
+                EnumVariant::Unit("LoggedOut".to_string()),
+                EnumVariant::Newtype("LoggedIn".to_string(), codec::named("User")),
+                EnumVariant::Tuple("Moved".to_string(), vec![codec::f32(), codec::f32()]),
+                EnumVariant::Struct(
+                    "Renamed".to_string(),
+                    vec![("old_name".to_string(), codec::vec(codec::option(codec::u8())))],
+                ),
+            ],
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export interface User {\n\
+             \x20 /** Stable across renames. */\n\
+             \x20 userId: bigint;\n\
+             \x20 nick: string | null;\n\
+             }\n\n\
+             export const UserCodec: r.Codec<User> = r.struct({"
+        ));
+        assert!(code.contains(
+            "export type Event =\n\
+             \x20 | { tag: 'logged_out'; value: null }\n\
+             \x20 | { tag: 'logged_in'; value: User }\n\
+             \x20 | { tag: 'moved'; value: [number, number] }\n\
+             \x20 | { tag: 'renamed'; value: { oldName: (number | null)[] } };\n\n\
+             export const ArchivedEvent: r.Codec<Event> = r.taggedEnum({"
+        ));
+        assert!(!code.contains("r.Infer"));
+    }
+
+    #[test]
+    fn declared_net_and_c_string_types_follow_their_style() {
+        let source = "
+            #[derive(rkyv::Archive)]
+            pub struct Peer {
+                pub ip: std::net::IpAddr,
+                pub addr: std::net::SocketAddr,
+                pub name: std::ffi::CString,
+            }
+        ";
+        let mut generator = CodeGenerator::new();
+        generator.set_type_style(TypeStyle::Declared);
+        generator.add_source_str(source).unwrap();
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export interface Peer {\n\
+             \x20 ip: string;\n\
+             \x20 addr: r.Infer<typeof socketAddr>;\n\
+             \x20 name: string;\n\
+             }"
+        ));
+
+        let mut generator = CodeGenerator::new();
+        generator.set_type_style(TypeStyle::Declared);
+        generator.set_net_style(TextStyle::Bytes);
+        generator.set_c_string_style(TextStyle::Bytes);
+        generator.add_source_str(source).unwrap();
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export interface Peer {\n\
+             \x20 ip: Uint8Array;\n\
+             \x20 addr: r.Infer<typeof socketAddrBytes>;\n\
+             \x20 name: Uint8Array;\n\
+             }"
+        ));
+    }
+
+    #[test]
+    fn declared_generic_factories_return_the_declared_type() {
+        let mut generator = CodeGenerator::new();
+        generator.set_type_style(TypeStyle::Declared);
+        generator.set_direction(Direction::Decode);
+        generator.set_jit(true);
+        generator.add_struct("Page", [("items", codec::vec(codec::param("T")))]);
+        generator.set_type_params("Page", ["T"]);
+        generator.add_alias("Feed", codec::instantiate("Page", [codec::string()]));
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export interface Page<T> {\n  items: T[];\n}\n\n\
+             const ArchivedPage$ = <T>(t: r.Decoder<T>): r.Decoder<Page<T>> => r.struct({"
+        ));
+        assert!(code.contains(
+            "export const ArchivedPage = <T>(t: r.Decoder<T>): r.Decoder<Page<T>> => \
+             compileDecoder(ArchivedPage$(t));"
+        ));
+        assert!(code.contains(
+            "export type Feed = Page<string>;\n\n\
+             const ArchivedFeed$: r.Decoder<Feed> = ArchivedPage$(r.string);\n\n\
+             export const ArchivedFeed: r.Decoder<Feed> = compileDecoder(ArchivedFeed$);"
+        ));
+    }
+
+    #[test]
+    fn modules_follow_rust_modules_and_import_each_other() {
+        let mut generator = CodeGenerator::new();
+        generator.set_type_style(TypeStyle::Declared);
+        generator.add_struct("Id", [("value", codec::u32())]);
+        generator.add_struct("api::users::User", [("id", codec::named("Id"))]);
+        generator.add_struct("api::Session", [("user", codec::named("api::users::User"))]);
+        let modules = generator.generate_modules().unwrap();
+        assert_eq!(
+            modules.keys().collect::<Vec<_>>(),
+            ["api.ts", "api/users.ts", "bindings.ts", "index.ts"]
+        );
+        assert!(modules["api/users.ts"].contains(
+            "import * as r from 'rkyv-js';\n\
+             import { ArchivedId, type Id } from '../bindings.ts';\n\n\
+             export interface User {"
+        ));
+        assert!(modules["api.ts"].contains("import { ArchivedUser, type User } from './api/users.ts';"));
+        assert!(!modules["bindings.ts"].contains("import {"));
+        assert!(modules["index.ts"].ends_with(
+            "export * from './api.ts';\n\
+             export * from './api/users.ts';\n\
+             export * from './bindings.ts';\n"
+        ));
+    }
+
+    #[test]
+    fn output_module_assignments_take_the_longest_match() {
+        let mut generator = CodeGenerator::new();
+        generator.allow_typescript_syntax(false);
+        generator.set_barrel(false);
+        generator.set_output_module("", "common");
+        generator.set_output_module("chat", "features/chat");
+        generator.set_output_module("chat::Draft", "features/editor");
+        generator.add_struct("chat::Message", [("body", codec::string())]);
+        generator.add_struct("chat::Draft", [("message", codec::named("chat::Message"))]);
+        generator.add_struct("Settings", [("drafts", codec::vec(codec::named("chat::Draft")))]);
+        let modules = generator.generate_modules().unwrap();
+        assert_eq!(
+            modules.keys().collect::<Vec<_>>(),
+            ["common.js", "features/chat.js", "features/editor.js"]
+        );
+        assert!(modules["features/editor.js"].contains("import { ArchivedMessage } from './chat.js';"));
+        assert!(modules["common.js"].contains("import { ArchivedDraft } from './features/editor.js';"));
+    }
+
+    #[test]
+    fn cycles_across_modules_defer_both_directions() {
+        let mut generator = CodeGenerator::new();
+        generator.set_jit(true);
+        generator.add_enum(
+            "a::Expr",
+            [EnumVariant::Newtype("Block".to_string(), codec::vec(codec::named("b::Stmt")))],
+        );
+        generator.add_enum(
+            "b::Stmt",
+            [EnumVariant::Newtype("Eval".to_string(), codec::named("a::Expr"))],
+        );
+        let modules = generator.generate_modules().unwrap();
+        // The raw codecs are exported for the other module to import.
+        assert!(modules["a.ts"].contains("import { ArchivedStmt$ } from './b.ts';"));
+        assert!(modules["a.ts"].contains(
+            "export const ArchivedExpr$ = r.taggedEnum({\n\
+             \x20 Block: r.vec(r.lazy((): r.AnyCodec => ArchivedStmt$)),\n"
+        ));
+        assert!(modules["b.ts"].contains("Eval: r.lazy((): r.AnyCodec => ArchivedExpr$),"));
+    }
+
+    #[test]
+    fn a_module_named_index_conflicts_with_the_barrel() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("index::Page", [("n", codec::u32())]);
+        let Err(Error::Codegen(diagnostics)) = generator.generate_modules() else {
+            panic!("expected a reserved module name");
+        };
+        assert_eq!(
+            diagnostics[0].kind,
+            DiagnosticKind::ReservedModuleName {
+                module: "index".to_string()
+            }
+        );
+        generator.set_barrel(false);
+        assert!(generator.generate_modules().unwrap().contains_key("index.ts"));
+    }
+
+    #[test]
+    fn a_module_named_bindings_conflicts_with_the_crate_root() {
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("Feed", [("page", codec::named("bindings::Page"))]);
+        generator.add_struct("bindings::Page", [("n", codec::u32())]);
+        let Err(Error::Codegen(diagnostics)) = generator.generate_modules() else {
+            panic!("expected a reserved module name");
+        };
+        assert_eq!(
+            diagnostics[0].kind,
+            DiagnosticKind::ReservedModuleName {
+                module: "bindings".to_string()
+            }
+        );
+        generator.set_output_module("bindings", "pages");
+        let modules = generator.generate_modules().unwrap();
+        assert!(modules["pages.ts"].contains("export const ArchivedPage = "));
+    }
+
+    #[test]
+    fn relative_specifiers_climb_to_the_shared_directory() {
+        assert_eq!(relative_specifier("bindings", "api/users", "ts"), "./api/users.ts");
+        assert_eq!(relative_specifier("api/users", "api/teams", "ts"), "./teams.ts");
+        assert_eq!(relative_specifier("api/v1/users", "common", "js"), "../../common.js");
+        assert_eq!(relative_specifier("a/b", "a/c/d", "ts"), "./c/d.ts");
+    }
+
+    #[test]
+    fn generic_js_mode_drops_type_syntax() {
+        let mut generator = CodeGenerator::new();
+        generator.allow_typescript_syntax(false);
+        generator.add_alias("Pair", codec::tuple([codec::param("K"), codec::param("V")]));
+        generator.set_type_params("Pair", ["K", "V"]);
+        let code = generator.generate().unwrap();
+        assert!(code.contains("export const ArchivedPair = (k, v) => r.tuple(k, v);"));
+        assert!(!code.contains("extends"));
+    }
+
+    #[test]
+    fn generic_bounds_follow_direction() {
+        let mut generator = CodeGenerator::new();
+        generator.set_direction(Direction::Decode);
+        generator.add_alias("Wrapper", codec::param("T"));
+        generator.set_type_params("Wrapper", ["T"]);
+        let code = generator.generate().unwrap();
+        assert!(code.contains("export const ArchivedWrapper = <T extends r.AnyDecoder>(t: T) => t;"));
+        assert!(code.contains(
+            "export type Wrapper<T> = r.Infer<ReturnType<typeof ArchivedWrapper<r.Decoder<T>>>>;"
+        ));
+    }
+
+    #[test]
+    fn generic_format_wraps_the_factory_body() {
+        let mut generator = CodeGenerator::new();
+        generator.set_format("big", 32, true);
+        generator.add_alias("Wrapper", codec::boxed(codec::param("T")));
+        generator.set_type_params("Wrapper", ["T"]);
+        let code = generator.generate().unwrap();
+        assert!(code.contains(
+            "export const ArchivedWrapper = <T extends r.AnyCodec>(t: T) => \
+             r.withFormat(r.box(t), FORMAT);"
+        ));
+    }
+
+    #[test]
+    fn set_jit_compiles_each_generic_instantiation() {
+        let mut generator = CodeGenerator::new();
+        generator.set_jit(true);
+        generator.add_struct("Page", [("items", codec::vec(codec::param("T")))]);
+        generator.set_type_params("Page", ["T"]);
+        generator.add_struct("User", [("id", codec::u32())]);
+        generator.add_struct(
+            "Feed",
+            [("page", codec::instantiate("Page", [codec::named("User")]))],
+        );
+        let code = generator.generate().unwrap();
+        assert!(code.contains("const ArchivedPage$ = <T extends r.AnyCodec>(t: T) => r.struct({"));
+        assert!(code.contains(
+            "export const ArchivedPage = <T extends r.AnyCodec>(t: T) => \
+             compileCodec(ArchivedPage$(t));"
+        ));
+        // Instantiations inside other types stay on the interpreter graph.
+        assert!(code.contains("page: ArchivedPage$(ArchivedUser$),"));
+    }
+
+    #[test]
+    fn generic_arity_mismatch_is_reported() {
+        let mut generator = CodeGenerator::new();
+        generator.add_alias("Wrapper", codec::param("T"));
+        generator.set_type_params("Wrapper", ["T"]);
+        generator.add_struct(
+            "Outer",
+            [("w", codec::instantiate("Wrapper", [codec::u8(), codec::u16()]))],
+        );
+        let errors = diagnostics(generator.generate().unwrap_err());
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0].kind,
+            DiagnosticKind::GenericArity { rust_path, expected: 1, found: 2 }
+                if rust_path == "Wrapper"
+        ));
+        assert_eq!(errors[0].referenced_by.as_deref(), Some("Outer.w"));
+    }
+
     #[test]
     fn js_mode_omits_type_lines() {
         let mut generator = CodeGenerator::new();
@@ -1455,4 +3697,113 @@ mod tests {
         assert!(code.contains("import { compileEncoder } from 'rkyv-js/jit/encode';"));
         assert!(code.contains("export const ArchivedPoint = compileEncoder(ArchivedPoint$);"));
     }
+
+    #[test]
+    fn check_file_reports_a_unified_diff() {
+        let dir = TempDir::new("check");
+        let path = dir.join("bindings.ts");
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("Point", [("x", codec::f64())]);
+
+        let Err(Error::Stale { diff, .. }) = generator.check_file(&path) else {
+            panic!("a missing file must be stale");
+        };
+        assert!(diff.starts_with("--- /dev/null\n"));
+
+        assert!(generator.write_to_file(&path).unwrap());
+        generator.check_file(&path).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        generator.add_struct("Line", [("from", codec::named("Point"))]);
+        let Err(Error::Stale { path: stale, diff }) = generator.check_file(&path) else {
+            panic!("a changed schema must be stale");
+        };
+        assert_eq!(stale, path);
+        assert!(diff.contains("\n+export const ArchivedLine = r.struct({\n"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn unchanged_output_is_not_rewritten() {
+        let dir = TempDir::new("write");
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("api::User", [("id", codec::u32())]);
+        generator.add_struct("Feed", [("user", codec::named("api::User"))]);
+
+        assert!(generator.write_to_dir(&dir).unwrap());
+        let modified = fs::metadata(dir.join("api.ts")).unwrap().modified().unwrap();
+        assert!(!generator.write_to_dir(&dir).unwrap());
+        assert_eq!(fs::metadata(dir.join("api.ts")).unwrap().modified().unwrap(), modified);
+
+        fs::write(dir.join("bindings.ts"), "// edited by hand\n").unwrap();
+        assert!(generator.write_to_dir(&dir).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.join("bindings.ts")).unwrap(),
+            generator.generate_modules().unwrap()["bindings.ts"]
+        );
+        let leftovers: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty(), "{leftovers:?}");
+    }
+
+    #[test]
+    fn check_dir_reports_modules_that_no_longer_exist() {
+        let dir = TempDir::new("check-dir");
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("api::User", [("id", codec::u32())]);
+        generator.add_struct("Feed", [("id", codec::u32())]);
+        generator.write_to_dir(&dir).unwrap();
+        generator.check_dir(&dir).unwrap();
+
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("Feed", [("id", codec::u32())]);
+        fs::write(
+            dir.join("index.ts"),
+            &generator.generate_modules().unwrap()["index.ts"],
+        )
+        .unwrap();
+        let Err(Error::Stale { diff, .. }) = generator.check_dir(&dir) else {
+            panic!("a removed module must be stale");
+        };
+        let api = dir.join("api.ts").display().to_string();
+        assert!(diff.starts_with(&format!("--- {api}\n+++ /dev/null\n")));
+        assert!(diff.contains("\n-export const ArchivedUser = r.struct({\n"));
+
+        generator.write_to_dir(&dir).unwrap();
+        generator.check_dir(&dir).unwrap();
+        generator.set_barrel(false);
+        let Err(Error::Stale { diff, .. }) = generator.check_dir(&dir) else {
+            panic!("a barrel that is no longer generated must be stale");
+        };
+        assert!(diff.contains("\n-export * from './bindings.ts';\n"));
+    }
+
+    #[test]
+    fn modules_that_no_longer_exist_are_deleted() {
+        let dir = TempDir::new("orphans");
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("api::v1::User", [("id", codec::u32())]);
+        generator.add_struct("Feed", [("id", codec::u32())]);
+        assert!(generator.write_to_dir(&dir).unwrap());
+        fs::write(dir.join("api/helpers.ts"), "export const answer = 42;\n").unwrap();
+        // Output of other generators sharing the directory.
+        generator.write_to_file(dir.join("all.ts")).unwrap();
+        generator.write_to_dir(dir.join("encode")).unwrap();
+
+        let mut generator = CodeGenerator::new();
+        generator.add_struct("Feed", [("id", codec::u32())]);
+        assert!(generator.write_to_dir(&dir).unwrap());
+        assert!(!dir.join("api/v1.ts").exists());
+        assert!(dir.join("api/helpers.ts").exists());
+        assert!(dir.join("all.ts").exists());
+        assert!(dir.join("encode/api/v1.ts").exists());
+        assert!(!generator.write_to_dir(&dir).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.join(MANIFEST)).unwrap(),
+            "# Files written by rkyv-js-codegen. DO NOT EDIT MANUALLY\nbindings.ts\nindex.ts\n"
+        );
+    }
 }
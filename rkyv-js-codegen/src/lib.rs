@@ -6,7 +6,8 @@
 //! extracts every type marked with `#[derive(Archive)]` (or a custom marker),
 //! and emits one `export const Archived{Name} = ...` codec per type.
 //!
-//! TypeScript types are always derived from the codecs via `r.Infer<typeof Archived{Name}>`.
+//! TypeScript types are derived from the codecs via `r.Infer<typeof Archived{Name}>`,
+//! or written out as interfaces with [`set_type_style`](CodeGenerator::set_type_style).
 //!
 //! ## build.rs
 //!
@@ -16,18 +17,34 @@
 //! fn main() -> Result<(), rkyv_js_codegen::Error> {
 //!     CodeGenerator::new()
 //!         .add_source_file("src/lib.rs")?
+//!         .emit_cargo_rerun_directives() // rerun when src/lib.rs changes
 //!         .write_to_file("generated/bindings.ts")?;
-//!
-//!     println!("cargo:rerun-if-changed=src/lib.rs");
 //!     Ok(())
 //! }
 //! ```
 //!
+//! Files that already hold the generated bindings are left untouched, so rebuilding does not
+//! invalidate bundler caches; changed files are replaced atomically.
+//!
+//! [`write_to_dir`](CodeGenerator::write_to_dir) splits the output into one module per Rust module
+//! instead, importing across files as needed, so bundlers can load each module's codecs separately.
+//!
+//! [`check_file`](CodeGenerator::check_file) and [`check_dir`](CodeGenerator::check_dir) generate in memory
+//! and fail with a unified diff when committed bindings are out of date, without writing anything;
+//! [`write_or_check_file`](CodeGenerator::write_or_check_file) checks instead of writing
+//! when `RKYV_JS_CODEGEN_CHECK` is set, so CI can verify through the same build script.
+//!
+//! Projects without a build script can run the `rkyv-js-codegen` binary instead, which exposes
+//! the same options as flags (`rkyv-js-codegen --help`) and fails with `--check` when the output is stale.
+//!
 //! ## Source extraction
 //!
+//! [`add_crate_root`](CodeGenerator::add_crate_root) extracts every type carrying the marker derive
+//! from a crate's module tree, following `mod foo;` declarations (and `#[path]`) from `src/lib.rs`.
 //! [`add_source_file`](CodeGenerator::add_source_file),
 //! [`add_source_dir`](CodeGenerator::add_source_dir) (recursive), and
-//! [`add_source_str`](CodeGenerator::add_source_str) all extract every type carrying the marker derive.
+//! [`add_source_str`](CodeGenerator::add_source_str) do the same for individual sources,
+//! each file parsed as a crate root of its own.
 //! `use` imports are resolved to fully-qualified paths — aliases and globs included —
 //! so registry lookups never depend on local names:
 //!
@@ -46,6 +63,46 @@
 //! # Ok::<(), rkyv_js_codegen::Error>(())
 //! ```
 //!
+//! Inline `mod` blocks are walked too. Types are keyed by their crate-relative
+//! path, so `self::`, `super::` and `crate::` references resolve to the right
+//! declaration. Exports keep the bare type name unless two modules declare the
+//! same one, in which case the module path is folded in (`api::User` →
+//! `ArchivedApiUser`).
+//!
+//! Generic types become codec factories taking one codec per type parameter,
+//! and use sites apply them:
+//!
+//! ```
+//! use rkyv_js_codegen::CodeGenerator;
+//!
+//! let mut generator = CodeGenerator::new();
+//! generator.add_source_str(r#"
+//!     use rkyv::Archive;
+//!
+//!     #[derive(Archive)]
+//!     pub struct Page<T> { pub items: Vec<T>, pub total: u64 }
+//!
+//!     #[derive(Archive)]
+//!     pub struct Feed { pub posts: Page<String> }
+//! "#)?;
+//!
+//! let code = generator.generate()?;
+//! assert!(code.contains("export const ArchivedPage = <T extends r.AnyCodec>(t: T) => r.struct({"));
+//! assert!(code.contains("posts: ArchivedPage(r.string),"));
+//! # Ok::<(), rkyv_js_codegen::Error>(())
+//! ```
+//!
+//! Rust `type` aliases are expanded at their use sites, generic arguments included, so `type UserId = u64;`
+//! or `type Pair<T> = (T, T);` may appear in any field, whichever module or source declares them.
+//! Array lengths and enum discriminants are evaluated as integer constant expressions over the sources' `const` items,
+//! so `[u8; HASH_LEN]` or `[f32; Self::DIMS * 4]` get the same length as in Rust;
+//! constants of other crates are supplied with [`register_const`](CodeGenerator::register_const).
+//!
+//! `#[cfg(...)]` and `#[cfg_attr(...)]` are evaluated against a [`CfgSet`] — inside a build script,
+//! the enabled features and target options of the crate being built — so a field behind a disabled feature
+//! never shifts the emitted layout, and `#[cfg_attr(feature = "rkyv", derive(Archive))]` is extracted
+//! exactly when the feature is on. Use [`set_cfg`](CodeGenerator::set_cfg) to generate for another configuration.
+//!
 //! `#[derive(Archive)]` must be resolvable — through `use rkyv::Archive`, an
 //! alias, a `use rkyv::*` glob, or an extra marker registered with
 //! [`add_marker_path`](CodeGenerator::add_marker_path).
@@ -58,9 +115,19 @@
 //! | [`set_archived_name`](CodeGenerator::set_archived_name) | Override an export name, matching `#[rkyv(archived = Name)]` |
 //! | [`set_direction`](CodeGenerator::set_direction) | Emit full, decode-only, or encode-only bindings |
 //! | [`set_format`](CodeGenerator::set_format) | Target a non-default rkyv wire format |
+//! | [`set_output_module`](CodeGenerator::set_output_module) | Group types into output modules of [`write_to_dir`](CodeGenerator::write_to_dir) |
+//! | [`set_barrel`](CodeGenerator::set_barrel) | Keep or drop the `index.ts` barrel of `write_to_dir` |
+//! | [`set_roots`](CodeGenerator::set_roots) | Emit only the given types and those they reference |
+//! | [`allow_path`](CodeGenerator::allow_path) / [`deny_path`](CodeGenerator::deny_path) | Include or exclude types by name or module path |
 //! | [`set_jit`](CodeGenerator::set_jit) | Wrap every export in the direction-matched `rkyv-js/jit` compile function |
 //! | [`set_field_casing`](CodeGenerator::set_field_casing) | Rewrite field names, e.g. Rust's `snake_case` to JavaScript's `camelCase` |
 //! | [`set_variant_casing`](CodeGenerator::set_variant_casing) | Rewrite enum variant tags |
+//! | [`set_unit_enum_style`](CodeGenerator::set_unit_enum_style) | Emit field-less enums as string or number values with an `as const` object |
+//! | [`set_type_style`](CodeGenerator::set_type_style) | Write out `interface`/union declarations instead of `r.Infer` aliases |
+//! | [`set_named_aliases`](CodeGenerator::set_named_aliases) | Export referenced Rust `type` aliases by name instead of inlining them |
+//! | [`set_net_style`](CodeGenerator::set_net_style) | Decode `std::net` addresses as text or octets |
+//! | [`set_c_string_style`](CodeGenerator::set_c_string_style) | Decode `CString` as UTF-8 text or bytes |
+//! | [`set_doc_comments`](CodeGenerator::set_doc_comments) | Keep or drop the JSDoc carried over from `///` comments |
 //! | [`allow_typescript_syntax`](CodeGenerator::allow_typescript_syntax) | Drop `export type` lines, emitting plain JavaScript |
 //! | [`on_unknown_type`](CodeGenerator::on_unknown_type) | Fail, or warn and omit, on unmappable types |
 //!
@@ -85,6 +152,8 @@
 //! so one schema can produce direction-matched bundles for a browser client and a Rust-facing service.
 //!
 //! Emission is deterministic: dependency-ordered, alphabetical within ties, so generated files diff cleanly.
+//! Recursive and mutually recursive types are supported: a reference that closes a cycle is emitted as
+//! `r.lazy(() => ...)`, so no `const` is read before it is initialized.
 //!
 //! ## Expressions instead of format strings
 //!
@@ -128,6 +197,21 @@
 //! );
 //! ```
 //!
+//! The same registrations can live in a TOML or JSON file loaded with
+//! [`add_config_file`](CodeGenerator::add_config_file), with codecs written as template strings:
+//!
+//! ```toml
+//! [types."my_crate::MyVec"]
+//! arity = 1
+//! codec = "myVec($0)"
+//! import = "my-package/codecs"
+//!
+//! [wrappers."AsJson"]
+//! kind = "replace"
+//! codec = "asJson"
+//! import = "./custom.ts"
+//! ```
+//!
 //! ## Error handling
 //!
 //! Parse failures surface immediately from `add_source_*`; everything else is validated in [`CodeGenerator::generate`],
@@ -136,14 +220,23 @@
 //! Set [`OnUnknown::SkipContainingType`] to emit `cargo:warning`s and omit affected types instead of failing.
 
 mod casing;
+mod cfg;
+mod config;
+mod consts;
 mod error;
 mod expr;
 mod extractor;
 mod generator;
 mod registry;
+#[cfg(test)]
+mod test_util;
 
 pub use casing::Casing;
+pub use cfg::CfgSet;
+pub use config::ConfigFormat;
 pub use error::{Diagnostic, DiagnosticKind, Error, SourceLocation};
-pub use expr::{CodecExpr, Import, codec, generate_import_block};
-pub use generator::{CodeGenerator, Direction, EnumVariant, OnUnknown};
+pub use expr::{CodecExpr, Import, Niching, codec, generate_import_block};
+pub use generator::{
+    CodeGenerator, Direction, EnumVariant, OnUnknown, TextStyle, TypeStyle, UnitEnumStyle,
+};
 pub use registry::{ExternalType, WithWrapper};
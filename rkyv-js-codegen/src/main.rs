@@ -0,0 +1,446 @@
+//! The `rkyv-js-codegen` command: generate bindings from CI, a Makefile or a JS build,
+//! without writing a build script.
+//!
+//! Every flag maps onto one [`CodeGenerator`] option; `rkyv-js-codegen --help` lists them.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use rkyv_js_codegen::{
+    Casing, CfgSet, CodeGenerator, Direction, Error, OnUnknown, TextStyle, TypeStyle, UnitEnumStyle,
+};
+
+const USAGE: &str = "\
+Generate rkyv-js bindings for the rkyv types of Rust sources.
+
+Usage: rkyv-js-codegen [OPTIONS] [PATH]...
+
+Sources:
+  [PATH]...                      Rust files or directories (scanned recursively)
+      --crate-root <FILE>        A crate root (`src/lib.rs`), following `mod` declarations
+      --marker <PATH>            An extra derive marking types for extraction
+      --cfg <NAME[=VALUE]>       Activate a `cfg` option, e.g. `unix` or `target_os=linux`
+      --features <LIST>          Activate comma-separated cargo features
+      --config <FILE>            Load external types, wrappers and naming from a TOML or JSON file;
+                                 options given after it take precedence
+
+Output:
+  -o, --out <FILE>               Write a single file (default: standard output)
+      --out-dir <DIR>            Write one module per Rust module, see `--output-module`
+      --check                    Write nothing; fail if the output on disk is stale
+
+Options:
+      --direction <full|decode|encode>
+      --endian <little|big>
+      --pointer-width <16|32|64>
+      --unaligned
+      --jit                      Wrap every export in the `rkyv-js/jit` compile function
+      --field-casing <preserve|camel|pascal|snake>
+      --variant-casing <preserve|camel|pascal|snake>
+      --unit-enum-style <tagged|string|number>
+      --type-style <inferred|declared>
+      --net-style <string|bytes>
+      --c-string-style <string|bytes>
+      --header <TEXT>            Replace the header comment
+      --no-doc-comments          Drop the JSDoc carried over from `///` comments
+      --named-aliases            Export referenced Rust `type` aliases instead of inlining them
+      --js                       Emit plain JavaScript
+      --archived-name <TYPE=NAME>
+      --output-module <PATH=MODULE>
+      --no-barrel                Drop the `index` barrel of `--out-dir`
+      --root <TYPE>              Emit only the roots and the types they reference
+      --allow <PATH>             Emit only types matching a type name or module path
+      --deny <PATH>              Never emit types matching a type name or module path
+      --skip-unknown             Warn and omit types with unmappable fields instead of failing
+  -h, --help
+  -V, --version
+";
+
+/// A source to extract, in command-line order.
+#[derive(Debug, PartialEq)]
+enum Source {
+    CrateRoot(PathBuf),
+    Path(PathBuf),
+}
+
+/// Where the generated bindings go.
+#[derive(Debug, PartialEq)]
+enum Output {
+    Stdout,
+    File(PathBuf),
+    Dir(PathBuf),
+}
+
+/// A parsed command line.
+struct Cli {
+    generator: CodeGenerator,
+    sources: Vec<Source>,
+    output: Output,
+    check: bool,
+}
+
+/// What the command line asks for.
+enum Command {
+    Run(Box<Cli>),
+    Help,
+    Version,
+}
+
+fn main() -> ExitCode {
+    let cli = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(cli)) => cli,
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("rkyv-js-codegen {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\nRun `rkyv-js-codegen --help` for usage.");
+            return ExitCode::from(2);
+        }
+    };
+    match run(*cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            report(&error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut generator = CodeGenerator::new();
+    let mut cfg = CfgSet::from_env();
+    let mut sources = Vec::new();
+    let mut output = Output::Stdout;
+    let mut check = false;
+    let mut roots = Vec::new();
+    let (mut endian, mut pointer_width, mut aligned) = (None, None, true);
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            sources.push(Source::Path(arg.into()));
+            continue;
+        }
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("`{flag}` expects a value"))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--crate-root" => sources.push(Source::CrateRoot(value()?.into())),
+            "--config" => {
+                let path = value()?;
+                generator
+                    .add_config_file(path)
+                    .map_err(|error| error.to_string())?;
+            }
+            "--marker" => {
+                generator.add_marker_path(value()?);
+            }
+            "--cfg" => {
+                let option = value()?;
+                match option.split_once('=') {
+                    Some((name, value)) => {
+                        cfg.enable_value(name.trim(), value.trim().trim_matches('"'));
+                    }
+                    None => {
+                        cfg.enable(option.trim());
+                    }
+                }
+            }
+            "--features" => {
+                for feature in value()?.split(',').filter(|feature| !feature.is_empty()) {
+                    cfg.enable_feature(feature.trim());
+                }
+            }
+            "-o" | "--out" => output = Output::File(value()?.into()),
+            "--out-dir" => output = Output::Dir(value()?.into()),
+            "--check" => check = true,
+            "--direction" => {
+                generator.set_direction(match value()?.as_str() {
+                    "full" => Direction::Full,
+                    "decode" => Direction::Decode,
+                    "encode" => Direction::Encode,
+                    other => return Err(invalid(&flag, other, "full, decode, encode")),
+                });
+            }
+            "--endian" => match value()?.as_str() {
+                endianness @ ("little" | "big") => endian = Some(endianness.to_string()),
+                other => return Err(invalid(&flag, other, "little, big")),
+            },
+            "--pointer-width" => match value()?.as_str() {
+                width @ ("16" | "32" | "64") => pointer_width = width.parse().ok(),
+                other => return Err(invalid(&flag, other, "16, 32, 64")),
+            },
+            "--unaligned" => aligned = false,
+            "--jit" => {
+                generator.set_jit(true);
+            }
+            "--field-casing" => {
+                generator.set_field_casing(parse_casing(&flag, &value()?)?);
+            }
+            "--variant-casing" => {
+                generator.set_variant_casing(parse_casing(&flag, &value()?)?);
+            }
+            "--unit-enum-style" => {
+                generator.set_unit_enum_style(match value()?.as_str() {
+                    "tagged" => UnitEnumStyle::Tagged,
+                    "string" => UnitEnumStyle::String,
+                    "number" => UnitEnumStyle::Number,
+                    other => return Err(invalid(&flag, other, "tagged, string, number")),
+                });
+            }
+            "--type-style" => {
+                generator.set_type_style(match value()?.as_str() {
+                    "inferred" => TypeStyle::Inferred,
+                    "declared" => TypeStyle::Declared,
+                    other => return Err(invalid(&flag, other, "inferred, declared")),
+                });
+            }
+            "--net-style" => {
+                generator.set_net_style(parse_text_style(&flag, &value()?)?);
+            }
+            "--c-string-style" => {
+                generator.set_c_string_style(parse_text_style(&flag, &value()?)?);
+            }
+            "--header" => {
+                generator.set_header(value()?);
+            }
+            "--no-doc-comments" => {
+                generator.set_doc_comments(false);
+            }
+            "--named-aliases" => {
+                generator.set_named_aliases(true);
+            }
+            "--js" => {
+                generator.allow_typescript_syntax(false);
+            }
+            "--archived-name" => {
+                let (type_name, name) = pair(&flag, value()?)?;
+                generator.set_archived_name(type_name, name);
+            }
+            "--output-module" => {
+                let (path, module) = pair(&flag, value()?)?;
+                generator.set_output_module(path, module);
+            }
+            "--no-barrel" => {
+                generator.set_barrel(false);
+            }
+            "--root" => roots.push(value()?),
+            "--allow" => {
+                generator.allow_path(value()?);
+            }
+            "--deny" => {
+                generator.deny_path(value()?);
+            }
+            "--skip-unknown" => {
+                generator.on_unknown_type(OnUnknown::SkipContainingType);
+            }
+            _ => return Err(format!("unknown option `{flag}`")),
+        }
+    }
+
+    if sources.is_empty() {
+        return Err("no sources given".to_string());
+    }
+    if check && output == Output::Stdout {
+        return Err("`--check` needs `--out` or `--out-dir`".to_string());
+    }
+    if endian.is_some() || pointer_width.is_some() || !aligned {
+        let endian = endian.as_deref().unwrap_or("little");
+        generator.set_format(endian, pointer_width.unwrap_or(32), aligned);
+    }
+    if !roots.is_empty() {
+        generator.set_roots(roots);
+    }
+    generator.set_cfg(cfg);
+    Ok(Command::Run(Box::new(Cli {
+        generator,
+        sources,
+        output,
+        check,
+    })))
+}
+
+fn parse_casing(flag: &str, value: &str) -> Result<Casing, String> {
+    Ok(match value {
+        "preserve" => Casing::Preserve,
+        "camel" => Casing::Camel,
+        "pascal" => Casing::Pascal,
+        "snake" => Casing::Snake,
+        other => return Err(invalid(flag, other, "preserve, camel, pascal, snake")),
+    })
+}
+
+fn parse_text_style(flag: &str, value: &str) -> Result<TextStyle, String> {
+    Ok(match value {
+        "string" => TextStyle::String,
+        "bytes" => TextStyle::Bytes,
+        other => return Err(invalid(flag, other, "string, bytes")),
+    })
+}
+
+/// Split a `KEY=VALUE` flag value.
+fn pair(flag: &str, value: String) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("`{flag}` expects `KEY=VALUE`, found `{value}`")),
+    }
+}
+
+fn invalid(flag: &str, value: &str, expected: &str) -> String {
+    format!("invalid value `{value}` for `{flag}`; expected one of: {expected}")
+}
+
+/// Extract the sources and write, print or check the bindings.
+fn run(mut cli: Cli) -> Result<(), Error> {
+    for source in &cli.sources {
+        match source {
+            Source::CrateRoot(path) => cli.generator.add_crate_root(path)?,
+            Source::Path(path) if path.is_dir() => cli.generator.add_source_dir(path)?,
+            Source::Path(path) => cli.generator.add_source_file(path)?,
+        };
+    }
+
+    match (&cli.output, cli.check) {
+        (Output::Stdout, _) => print!("{}", cli.generator.generate()?),
+        (Output::File(path), true) => cli.generator.check_file(path)?,
+        (Output::File(path), false) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            cli.generator.write_to_file(path)?;
+        }
+        (Output::Dir(dir), true) => cli.generator.check_dir(dir)?,
+        (Output::Dir(dir), false) => {
+            cli.generator.write_to_dir(dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Print an error to standard error, one block per diagnostic.
+fn report(error: &Error) {
+    let Error::Codegen(diagnostics) = error else {
+        eprintln!("error: {error}");
+        return;
+    };
+    for diagnostic in diagnostics {
+        eprintln!("error: {}", diagnostic.kind);
+        if let Some(location) = &diagnostic.location {
+            eprintln!("  --> {location}");
+        }
+        if let Some(referenced_by) = &diagnostic.referenced_by {
+            eprintln!("   = in `{referenced_by}`");
+        }
+        eprintln!();
+    }
+    eprintln!(
+        "error: code generation failed with {} error{}",
+        diagnostics.len(),
+        if diagnostics.len() == 1 { "" } else { "s" },
+    );
+}
+
+#[cfg(test)]
+#[path = "test_util.rs"]
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::TempDir;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        match parse_args(args.iter().map(|arg| arg.to_string()))? {
+            Command::Run(cli) => Ok(*cli),
+            _ => panic!("expected a run command"),
+        }
+    }
+
+    #[test]
+    fn flags_configure_the_generator() {
+        let mut cli = parse(&[
+            "--field-casing=camel",
+            "--direction",
+            "decode",
+            "--root",
+            "Feed",
+            "--crate-root",
+            "src/lib.rs",
+            "-o",
+            "bindings.ts",
+        ])
+        .unwrap();
+        assert_eq!(cli.sources, [Source::CrateRoot("src/lib.rs".into())]);
+        assert_eq!(cli.output, Output::File("bindings.ts".into()));
+
+        cli.generator
+            .add_source_str(
+                "#[derive(rkyv::Archive)] pub struct Feed { pub created_at: u64 }
+             #[derive(rkyv::Archive)] pub struct Unused { pub id: u64 }",
+            )
+            .unwrap();
+        let code = cli.generator.generate().unwrap();
+        assert!(code.contains("from 'rkyv-js/decode'"));
+        assert!(code.contains("createdAt: r.u64,"));
+        assert!(!code.contains("Unused"));
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        let error = |args: &[&str]| parse(args).err().unwrap();
+        assert_eq!(error(&["--bogus", "lib.rs"]), "unknown option `--bogus`");
+        assert!(error(&["--field-casing", "kebab", "lib.rs"]).contains("expected one of"));
+        assert_eq!(error(&["lib.rs", "--header"]), "`--header` expects a value");
+        assert_eq!(error(&["--jit"]), "no sources given");
+        assert!(error(&["--check", "lib.rs"]).contains("`--check` needs"));
+    }
+
+    #[test]
+    fn check_reports_stale_output() {
+        let dir = TempDir::with_files(
+            "cli",
+            &[(
+                "lib.rs",
+                "#[derive(rkyv::Archive)] pub struct Point { pub x: f64 }",
+            )],
+        );
+        let source = dir.join("lib.rs");
+        let out = dir.join("bindings.ts");
+        let args = |check: bool| {
+            let mut args = vec![
+                source.display().to_string(),
+                "-o".into(),
+                out.display().to_string(),
+            ];
+            if check {
+                args.push("--check".into());
+            }
+            match parse_args(args).unwrap() {
+                Command::Run(cli) => *cli,
+                _ => unreachable!(),
+            }
+        };
+
+        assert!(matches!(run(args(true)), Err(Error::Stale { .. })));
+        assert!(!out.exists());
+        run(args(false)).unwrap();
+        run(args(true)).unwrap();
+    }
+}
@@ -15,6 +15,7 @@ use std::collections::BTreeMap;
 
 use crate::error::DiagnosticKind;
 use crate::expr::{CodecExpr, codec};
+use crate::generator::TextStyle;
 
 /// A codec template for an external Rust type.
 ///
@@ -112,8 +113,11 @@ impl ExternalType {
 }
 
 /// The behavior of a with-wrapper.
+///
+/// The first four kinds work on the codec of the field type; the rest
+/// reshape the field *type* itself, so the extractor applies them.
 #[derive(Debug, Clone)]
-enum WithWrapperKind {
+pub(crate) enum WithWrapperKind {
     /// Emit a fixed expression, ignoring the underlying field type.
     Replace(CodecExpr),
     /// Transform the underlying field codec (template with `Param(0)`).
@@ -122,6 +126,27 @@ enum WithWrapperKind {
     Identity,
     /// Omit the field from the generated bindings entirely.
     Skip,
+    /// Archive the field type's type argument in its owned form
+    /// (`Lock` on `Mutex<T>`, `Unshare` on `Rc<T>`, `AsOwned` on `Cow<'_, T>`).
+    Unwrap,
+    /// Apply the wrapper's type argument to each element of an option, vector or array (`Map<W>`).
+    MapElements,
+    /// Apply the wrapper's two type arguments to the keys and values of a map (`MapKV<K, V>`).
+    MapEntries,
+    /// Archive a map as a vector of `{ key, value }` entries, and a set or slice as a vector (`AsVec`).
+    AsVec,
+    /// Box the field value, or point straight at the bytes of a `str` or slice
+    /// behind a reference (`AsBox`, `InlineAsBox`).
+    AsBox,
+    /// Archive an atomic integer as its plain value (`AtomicLoad<SO>`).
+    AtomicLoad,
+    /// Niche an `Option<NonZero*>` or `Option<Box<T>>` into its value (`Niche`).
+    Niche,
+    /// Niche an option into its value with the wrapper's niching, or the value's
+    /// default one (`NicheInto<N>`, `DefaultNiche`).
+    NicheInto,
+    /// Apply the wrapper's first type argument to the value, then niche it (`MapNiche<W, N>`).
+    MapNiche,
 }
 
 /// A handler for a `#[rkyv(with = W)]` field wrapper.
@@ -161,16 +186,21 @@ impl WithWrapper {
         }
     }
 
-    /// Whether this wrapper needs the underlying field type resolved.
-    pub(crate) fn needs_underlying(&self) -> bool {
-        matches!(
-            self.kind,
-            WithWrapperKind::Map(_) | WithWrapperKind::Identity
-        )
+    fn builtin(kind: WithWrapperKind) -> Self {
+        Self { kind }
+    }
+
+    /// How the extractor applies this wrapper.
+    pub(crate) fn kind(&self) -> &WithWrapperKind {
+        &self.kind
     }
 
     /// Apply the wrapper. `underlying` is only consulted for [`map`](WithWrapper::map) and [`identity`](WithWrapper::identity) wrappers;
     /// `None` is returned for [`skip`](WithWrapper::skip).
+    ///
+    /// # Panics
+    ///
+    /// Panics for the type-reshaping kinds, which the extractor applies itself.
     pub(crate) fn apply(&self, underlying: Option<CodecExpr>) -> Option<CodecExpr> {
         match &self.kind {
             WithWrapperKind::Replace(expr) => Some(expr.clone()),
@@ -182,6 +212,7 @@ impl WithWrapper {
                 Some(underlying.expect("identity wrapper requires the underlying codec"))
             }
             WithWrapperKind::Skip => None,
+            kind => unreachable!("{kind:?} reshapes the field type; the extractor applies it"),
         }
     }
 }
@@ -216,6 +247,57 @@ impl Registry {
         );
         registry.register_type("smol_str::SmolStr", ExternalType::leaf(codec::string()));
 
+        // `ArchivedDuration` (secs + nanos), decoded losslessly. rkyv 0.8 has no
+        // chrono/time/jiff integration, so there is no archived form to map for those.
+        let duration = CodecExpr::import_from("rkyv-js/lib/duration", "duration");
+        for path in ["std::time::Duration", "core::time::Duration"] {
+            registry.register_type(path, ExternalType::leaf(duration.clone()));
+        }
+
+        registry.register_net_types(TextStyle::default());
+        registry.register_c_string(TextStyle::default());
+
+        // `ArchivedResult` and `ArchivedBound` are `#[repr(u8)]` enums in variant order, decoded as
+        // `{ tag, value }`; the `#[repr(C)]` range structs decode as `{ start, end }`.
+        for path in ["std::result::Result", "core::result::Result"] {
+            registry.register_type(
+                path,
+                ExternalType::generic2(|ok, err| tagged_enum([("Ok", ok), ("Err", err)])),
+            );
+        }
+        for module in ["std::ops", "core::ops"] {
+            registry.register_type(
+                format!("{module}::Bound"),
+                ExternalType::generic1(|t| {
+                    tagged_enum([
+                        ("Included", t.clone()),
+                        ("Excluded", t),
+                        ("Unbounded", CodecExpr::raw("null")),
+                    ])
+                }),
+            );
+            for range in ["Range", "RangeInclusive"] {
+                registry.register_type(
+                    format!("{module}::{range}"),
+                    ExternalType::generic1(|t| record([("start", t.clone()), ("end", t)])),
+                );
+            }
+            registry.register_type(
+                format!("{module}::RangeFrom"),
+                ExternalType::generic1(|t| record([("start", t)])),
+            );
+            for range in ["RangeTo", "RangeToInclusive"] {
+                registry.register_type(
+                    format!("{module}::{range}"),
+                    ExternalType::generic1(|t| record([("end", t)])),
+                );
+            }
+            registry.register_type(
+                format!("{module}::RangeFull"),
+                ExternalType::leaf(codec::unit()),
+            );
+        }
+
         // Vec-shaped containers.
         registry.register_type(
             "std::collections::VecDeque",
@@ -290,14 +372,79 @@ impl Registry {
         }
 
         // Built-in with-wrappers.
-        registry.register_wrapper("rkyv::with::AsBox", WithWrapper::map(codec::boxed));
+        registry.register_wrapper(
+            "rkyv::with::AsBox",
+            WithWrapper::builtin(WithWrapperKind::AsBox),
+        );
         registry.register_wrapper("rkyv::with::Inline", WithWrapper::identity());
-        registry.register_wrapper("rkyv::with::InlineAsBox", WithWrapper::map(codec::boxed));
+        registry.register_wrapper(
+            "rkyv::with::InlineAsBox",
+            WithWrapper::builtin(WithWrapperKind::AsBox),
+        );
         registry.register_wrapper("rkyv::with::Skip", WithWrapper::skip());
+        registry.register_wrapper("rkyv::with::Identity", WithWrapper::identity());
+        registry.register_wrapper("rkyv::with::AsString", WithWrapper::replace(codec::string()));
+        for path in ["rkyv::with::Lock", "rkyv::with::Unsafe", "rkyv::with::Unshare", "rkyv::with::AsOwned"] {
+            registry.register_wrapper(path, WithWrapper::builtin(WithWrapperKind::Unwrap));
+        }
+        registry.register_wrapper("rkyv::with::Map", WithWrapper::builtin(WithWrapperKind::MapElements));
+        registry.register_wrapper("rkyv::with::MapKV", WithWrapper::builtin(WithWrapperKind::MapEntries));
+        registry.register_wrapper("rkyv::with::AsVec", WithWrapper::builtin(WithWrapperKind::AsVec));
+        registry.register_wrapper(
+            "rkyv::with::AtomicLoad",
+            WithWrapper::builtin(WithWrapperKind::AtomicLoad),
+        );
+        registry.register_wrapper("rkyv::with::Niche", WithWrapper::builtin(WithWrapperKind::Niche));
+        for path in ["rkyv::with::NicheInto", "rkyv::with::DefaultNiche", "rkyv::niche::niching::DefaultNiche"] {
+            registry.register_wrapper(path, WithWrapper::builtin(WithWrapperKind::NicheInto));
+        }
+        registry.register_wrapper("rkyv::with::MapNiche", WithWrapper::builtin(WithWrapperKind::MapNiche));
+        // `SystemTime` archived as the `Duration` since the Unix epoch.
+        registry.register_wrapper(
+            "rkyv::with::AsUnixTime",
+            WithWrapper::replace(CodecExpr::import_from("rkyv-js/lib/duration", "unixTime")),
+        );
 
         registry
     }
 
+    /// Map the `std::net` address types to `rkyv-js/lib/net`, decoding addresses as text or octets.
+    pub(crate) fn register_net_types(&mut self, style: TextStyle) {
+        for module in ["std::net", "core::net"] {
+            for (name, export) in [
+                ("Ipv4Addr", "ipv4Addr"),
+                ("Ipv6Addr", "ipv6Addr"),
+                ("IpAddr", "ipAddr"),
+                ("SocketAddrV4", "socketAddrV4"),
+                ("SocketAddrV6", "socketAddrV6"),
+                ("SocketAddr", "socketAddr"),
+            ] {
+                let export = match style {
+                    TextStyle::String => export.to_string(),
+                    TextStyle::Bytes => format!("{export}Bytes"),
+                };
+                self.register_type(
+                    format!("{module}::{name}"),
+                    ExternalType::leaf(CodecExpr::import_from("rkyv-js/lib/net", export)),
+                );
+            }
+        }
+    }
+
+    /// Map `CString` to `rkyv-js/lib/ffi`, decoding it as UTF-8 text or bytes.
+    pub(crate) fn register_c_string(&mut self, style: TextStyle) {
+        let export = match style {
+            TextStyle::String => "cString",
+            TextStyle::Bytes => "cStringBytes",
+        };
+        for path in ["std::ffi::CString", "alloc::ffi::CString"] {
+            self.register_type(
+                path,
+                ExternalType::leaf(CodecExpr::import_from("rkyv-js/lib/ffi", export)),
+            );
+        }
+    }
+
     pub(crate) fn register_type(&mut self, path: impl Into<String>, external: ExternalType) {
         self.types.insert(path.into(), external);
     }
@@ -328,6 +475,19 @@ impl Registry {
     }
 }
 
+/// `r.taggedEnum({ ... })` over the variants of a std enum, in discriminant order.
+fn tagged_enum<const N: usize>(variants: [(&str, CodecExpr); N]) -> CodecExpr {
+    CodecExpr::call(
+        CodecExpr::runtime("taggedEnum"),
+        [CodecExpr::object(variants)],
+    )
+}
+
+/// `r.struct({ ... })` over the fields of a std struct, in declaration order.
+fn record<const N: usize>(fields: [(&str, CodecExpr); N]) -> CodecExpr {
+    CodecExpr::call(CodecExpr::runtime("struct"), [CodecExpr::object(fields)])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -419,7 +579,7 @@ mod tests {
     #[test]
     fn wrapper_replace_ignores_underlying() {
         let wrapper = WithWrapper::replace(CodecExpr::import_from("./coord.ts", "Coord"));
-        assert!(!wrapper.needs_underlying());
+        assert!(matches!(wrapper.kind(), WithWrapperKind::Replace(_)));
         let expr = wrapper.apply(None).unwrap();
         assert_eq!(render(&expr), "Coord");
     }
@@ -427,7 +587,7 @@ mod tests {
     #[test]
     fn wrapper_map_transforms_underlying() {
         let wrapper = WithWrapper::map(codec::boxed);
-        assert!(wrapper.needs_underlying());
+        assert!(matches!(wrapper.kind(), WithWrapperKind::Map(_)));
         let expr = wrapper.apply(Some(codec::string())).unwrap();
         assert_eq!(render(&expr), "r.box(r.string)");
     }
@@ -442,7 +602,7 @@ mod tests {
     #[test]
     fn wrapper_skip_omits() {
         let wrapper = WithWrapper::skip();
-        assert!(!wrapper.needs_underlying());
+        assert!(matches!(wrapper.kind(), WithWrapperKind::Skip));
         assert!(wrapper.apply(None).is_none());
     }
 
@@ -471,6 +631,27 @@ mod tests {
             "triomphe::Arc",
             "std::rc::Weak",
             "std::sync::Weak",
+            "std::time::Duration",
+            "core::time::Duration",
+            "std::result::Result",
+            "core::result::Result",
+            "std::ops::Range",
+            "std::ops::RangeInclusive",
+            "std::ops::RangeFrom",
+            "std::ops::RangeTo",
+            "std::ops::RangeToInclusive",
+            "std::ops::RangeFull",
+            "std::ops::Bound",
+            "core::ops::Range",
+            "core::ops::Bound",
+            "std::net::Ipv4Addr",
+            "std::net::IpAddr",
+            "std::net::SocketAddr",
+            "core::net::Ipv6Addr",
+            "core::net::SocketAddrV4",
+            "core::net::SocketAddrV6",
+            "std::ffi::CString",
+            "alloc::ffi::CString",
         ] {
             assert!(registry.get_type(path).is_some(), "missing builtin {path}");
         }
@@ -479,11 +660,69 @@ mod tests {
             "rkyv::with::Inline",
             "rkyv::with::InlineAsBox",
             "rkyv::with::Skip",
+            "rkyv::with::AsUnixTime",
+            "rkyv::with::Identity",
+            "rkyv::with::AsString",
+            "rkyv::with::Lock",
+            "rkyv::with::Unsafe",
+            "rkyv::with::Unshare",
+            "rkyv::with::AsOwned",
+            "rkyv::with::Map",
+            "rkyv::with::MapKV",
+            "rkyv::with::AsVec",
+            "rkyv::with::AtomicLoad",
+            "rkyv::with::Niche",
+            "rkyv::with::NicheInto",
+            "rkyv::with::DefaultNiche",
+            "rkyv::with::MapNiche",
         ] {
             assert!(registry.get_wrapper(path).is_some(), "missing wrapper {path}");
         }
     }
 
+    #[test]
+    fn std_enums_and_ranges_keep_their_rust_shape() {
+        let registry = Registry::with_builtins();
+        let instantiate = |path: &str, args: Vec<CodecExpr>| {
+            render(&registry.get_type(path).unwrap().instantiate(args).unwrap())
+        };
+        assert_eq!(
+            instantiate("std::result::Result", vec![codec::u32(), codec::string()]),
+            "r.taggedEnum({ Ok: r.u32, Err: r.string })"
+        );
+        assert_eq!(
+            instantiate("std::ops::Bound", vec![codec::u64()]),
+            "r.taggedEnum({ Included: r.u64, Excluded: r.u64, Unbounded: null })"
+        );
+        assert_eq!(
+            instantiate("core::ops::RangeInclusive", vec![codec::char_()]),
+            "r.struct({ start: r.char, end: r.char })"
+        );
+        assert_eq!(
+            instantiate("std::ops::RangeTo", vec![codec::usize()]),
+            "r.struct({ end: r.usize })"
+        );
+        assert_eq!(instantiate("std::ops::RangeFull", vec![]), "r.unit");
+    }
+
+    #[test]
+    fn net_and_c_string_styles_pick_the_exports() {
+        let mut registry = Registry::with_builtins();
+        let codec = |registry: &Registry, path: &str| {
+            let leaf = registry.get_type(path).unwrap();
+            render(&leaf.instantiate(vec![]).unwrap())
+        };
+        assert_eq!(codec(&registry, "std::net::IpAddr"), "ipAddr");
+        assert_eq!(codec(&registry, "core::net::SocketAddr"), "socketAddr");
+        assert_eq!(codec(&registry, "std::ffi::CString"), "cString");
+
+        registry.register_net_types(TextStyle::Bytes);
+        registry.register_c_string(TextStyle::Bytes);
+        assert_eq!(codec(&registry, "std::net::IpAddr"), "ipAddrBytes");
+        assert_eq!(codec(&registry, "core::net::SocketAddr"), "socketAddrBytes");
+        assert_eq!(codec(&registry, "alloc::ffi::CString"), "cStringBytes");
+    }
+
     #[test]
     fn hashmap_accepts_trailing_hasher() {
         let registry = Registry::with_builtins();
@@ -144,12 +144,13 @@ function emitRead(ctx: EmitCtx<AnyDecoder>, codec: AnyDecoder, off: string): str
       const layout = meta.layout(ctx.fmt);
       const name = ctx.helperName();
       ctx.ancestors.add(codec);
-      const cases = variants.map((v, disc) => {
+      const cases = variants.map((v, index) => {
+        const disc = v.discriminant;
         const tag = JSON.stringify(v.name);
         if (v.fields.length === 0) {
           return `    case ${disc}: return { tag: ${tag}, value: null };`;
         }
-        const offsets = layout.variants[disc].fieldOffsets;
+        const offsets = layout.variants[index].fieldOffsets;
         if (v.fields.length === 1 && v.fields[0].name === null) {
           const value = emitRead(ctx, v.fields[0].codec, addOffset('o', offsets[0]));
           return `    case ${disc}: return { tag: ${tag}, value: ${value} };`;
@@ -178,7 +179,7 @@ function emitRead(ctx: EmitCtx<AnyDecoder>, codec: AnyDecoder, off: string): str
       return `${name}(r, ${off})`;
     }
 
-    // Kind.other (char/unit) and Kind.opaque (box/rc/weak/union/transform/
+    // Kind.other (char/unit/128-bit/pointer-sized) and Kind.opaque (box/rc/weak/union/transform/
     // lazy/maps/custom): dep call — still a monomorphic call site inside
     // this compiled function.
     default:
@@ -156,6 +156,47 @@ export interface EnumLayout extends Layout {
   variants: VariantLayout[];
 }
 
+/**
+ * Options of `taggedEnum`.
+ *
+ * `discriminants` maps variant names to the tag values written for them,
+ * mirroring explicit Rust discriminants (`Active = 2`). As in Rust, a variant
+ * without one takes its predecessor's value plus one, and the first defaults to 0.
+ */
+export interface EnumOptions<K extends PropertyKey = string> {
+  readonly discriminants?: { readonly [P in K]?: number };
+}
+
+/**
+ * The tag value of every variant, in declaration order.
+ *
+ * rkyv archives the tag as a `u8` whatever the Rust `repr`, so every value
+ * must be a distinct integer in `0..=255`.
+ */
+export function enumDiscriminants(
+  names: readonly string[],
+  options: EnumOptions | undefined,
+): number[] {
+  const explicit: { readonly [name: string]: number | undefined } = options?.discriminants ?? {};
+  for (const name of Object.keys(explicit)) {
+    if (!names.includes(name)) throw new Error(`unknown enum variant ${name}`);
+  }
+  const seen = new Set<number>();
+  let next = 0;
+  return names.map((name) => {
+    const disc = explicit[name] ?? next;
+    if (!Number.isInteger(disc) || disc < 0 || disc > 255) {
+      throw new Error(`enum discriminant ${disc} of ${name} does not fit rkyv's u8 tag`);
+    }
+    if (seen.has(disc)) {
+      throw new Error(`enum discriminant ${disc} of ${name} is already in use`);
+    }
+    seen.add(disc);
+    next = disc + 1;
+    return disc;
+  });
+}
+
 export function enumLayout(
   fmt: RkyvFormat,
   variantFields: readonly (readonly HasLayout[])[],
@@ -197,13 +197,14 @@ export interface CodecMetaVariantField<C> {
   readonly codec: C;
 }
 
-/** A normalized enum variant: tag name and flattened field list. */
+/** A normalized enum variant: tag name, archived tag value and flattened field list. */
 export interface CodecMetaVariant<C> {
   readonly name: string;
+  readonly discriminant: number;
   readonly fields: readonly CodecMetaVariantField<C>[];
 }
 
-/** rkyv's repr(u8)/repr(u16) tagged enum; variants in discriminant order. */
+/** rkyv's repr(u8)/repr(u16) tagged enum; variants in declaration order. */
 export interface EnumMeta<C> {
   readonly kind: (typeof Kind)['enum'];
   readonly variants: readonly CodecMetaVariant<C>[];
@@ -0,0 +1,80 @@
+/**
+ * Niching strategies: how a niched `Option<T>` stores `None` inside the
+ * bytes of `T` itself, so the option takes no tag byte (rkyv's `Niching`).
+ *
+ * A strategy is a pair of pure functions over a reader resp. writer passed
+ * in by the `niched` codecs; this module only imports types, so neither
+ * unidirectional bundle picks up the other half's machinery.
+ */
+
+import type { Layout } from './layout.ts';
+import type { RkyvReader } from './reader.ts';
+import type { RkyvWriter } from './writer.ts';
+
+/**
+ * A niche: a bit pattern the inner value never takes, standing in for `None`.
+ */
+export interface Niching {
+  /** Whether the `layout.size` bytes at `offset` hold the niche. */
+  isNiched(reader: RkyvReader, offset: number, layout: Layout): boolean;
+  /** Write the niche (`layout.size` bytes) at the writer's position. */
+  resolveNiched(writer: RkyvWriter, layout: Layout): void;
+}
+
+/**
+ * The built-in strategies, named after rkyv's `Niching` implementors.
+ *
+ * - `zero`: all-zero bytes, for `NonZero*` integers (`Niche`, `NicheInto<Zero>`).
+ * - `nan`: a NaN, for `f32`/`f64` (`NicheInto<NaN>`). Any NaN reads as `None`.
+ * - `bool`: a byte above `1`, for `bool` (`NicheInto<Bool>`).
+ * - `null`: rkyv's invalid relative pointer, for `Box<T>` and `Box<str>`/`Box<[T]>`
+ *   (`Niche`, `NicheInto<Null>`).
+ */
+export const niching: {
+  readonly zero: Niching;
+  readonly nan: Niching;
+  readonly bool: Niching;
+  readonly null: Niching;
+} = {
+  zero: {
+    isNiched(reader, offset, layout) {
+      for (let i = 0; i < layout.size; i++) {
+        if (reader.readU8(offset + i) !== 0) return false;
+      }
+      return true;
+    },
+    resolveNiched(writer, layout) {
+      writer.writeZeros(layout.size);
+    },
+  },
+  nan: {
+    isNiched(reader, offset, layout) {
+      return Number.isNaN(layout.size === 4 ? reader.readF32(offset) : reader.readF64(offset));
+    },
+    resolveNiched(writer, layout) {
+      // Rust's canonical quiet NaN (`f32::NAN` / `f64::NAN`), bit for bit.
+      if (layout.size === 4) writer.writeU32(0x7fc00000);
+      else writer.writeU64(0x7ff8000000000000n);
+    },
+  },
+  bool: {
+    isNiched(reader, offset) {
+      return reader.readU8(offset) > 1;
+    },
+    resolveNiched(writer) {
+      writer.writeU8(2);
+    },
+  },
+  null: {
+    isNiched(reader, offset) {
+      return reader.isInvalidPtr(offset);
+    },
+    resolveNiched(writer, layout) {
+      const start = writer.pos;
+      writer.writeInvalidPtrAt(writer.reserveRelPtr());
+      // Pointers to `str` and slices carry a length, zeroed like rkyv's
+      // default metadata.
+      writer.writeZeros(layout.size - (writer.pos - start));
+    },
+  },
+};
@@ -136,6 +136,16 @@ export class RkyvReader {
     return SCRATCH_VIEW.getBigInt64(0, this.littleEndian);
   }
 
+  readU128(offset: number): bigint {
+    const lo = this.readU64(this.littleEndian ? offset : offset + 8);
+    const hi = this.readU64(this.littleEndian ? offset + 8 : offset);
+    return (hi << 64n) | lo;
+  }
+
+  readI128(offset: number): bigint {
+    return BigInt.asIntN(128, this.readU128(offset));
+  }
+
   readF32(offset: number): number {
     const b = this.buffer;
     SCRATCH[0] = b[offset];
@@ -173,6 +183,24 @@ export class RkyvReader {
     }
   }
 
+  /**
+   * Read an archived `isize` (rkyv `FixedIsize` — the configured pointer
+   * width) as a number.
+   */
+  readIsize(offset: number): number {
+    switch (this.pointerBytes) {
+      case 2:
+        return this.readI16(offset);
+      case 4:
+        return this.readI32(offset);
+      case 8: {
+        const lo = this.littleEndian ? this.readU32(offset) : this.readU32(offset + 4);
+        const hi = this.littleEndian ? this.readI32(offset + 4) : this.readI32(offset);
+        return hi * 0x1_0000_0000 + lo;
+      }
+    }
+  }
+
   /**
    * Read a raw byte slice from the buffer (a view, not a copy).
    */
@@ -252,6 +252,19 @@ export class RkyvWriter {
     return pos;
   }
 
+  writeU128(value: bigint): number {
+    const v = BigInt.asUintN(128, value);
+    const lo = v & 0xffff_ffff_ffff_ffffn;
+    const hi = v >> 64n;
+    const pos = this.writeU64(this.#le ? lo : hi);
+    this.writeU64(this.#le ? hi : lo);
+    return pos;
+  }
+
+  writeI128(value: bigint): number {
+    return this.writeU128(value);
+  }
+
   writeF32(value: number): number {
     const pos = this.position;
     this.#ensureCapacity(4);
@@ -287,6 +300,21 @@ export class RkyvWriter {
     }
   }
 
+  /**
+   * Write an archived `isize` (rkyv `FixedIsize` — the configured pointer
+   * width).
+   */
+  writeIsize(value: number): number {
+    switch (this.pointerBytes) {
+      case 2:
+        return this.writeI16(value);
+      case 4:
+        return this.writeI32(value);
+      case 8:
+        return this.writeI64(BigInt(value));
+    }
+  }
+
   /**
    * Write raw bytes to the buffer.
    */
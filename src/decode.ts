@@ -7,9 +7,10 @@
  * pulls in the writer, the hasher, or any archive/resolve machinery.
  *
  * The factories mirror `./primitives.ts` name-for-name (struct, vec,
- * option, box, rc, weak, array, tuple, taggedEnum, union, transform,
- * newtype, lazy, plus the primitive singletons and string), so generated
- * bindings can switch direction by switching the import path.
+ * option, niched, box, rc, weak, array, tuple, taggedEnum, stringEnum,
+ * numberEnum, union, transform, newtype, lazy, plus the primitive singletons
+ * and string), so generated bindings can switch direction by switching the
+ * import path.
  */
 
 import type { Infer, Layout } from './core/base.ts';
@@ -20,11 +21,13 @@ import {
   FormatBoundDecoder,
   type AnyDecoder,
 } from './core/decoder.ts';
+import type { Niching } from './core/niching.ts';
 import type { RkyvReader } from './core/reader.ts';
 import { Kind, primitiveKindOf, type PrimitiveKindTag } from './core/meta.ts';
 import {
   arrayLayout,
   elementStride,
+  enumDiscriminants,
   enumLayout,
   optionLayout,
   ptrLayout,
@@ -34,6 +37,7 @@ import {
   vecLayout,
   type ArrayLayout,
   type EnumLayout,
+  type EnumOptions,
   type OptionLayout,
   type StringLayout,
   type StructLayout,
@@ -46,6 +50,8 @@ export { Kind, OPAQUE_META, type CodecMeta, type PrimitiveKindTag } from './core
 export type { Infer, Layout } from './core/base.ts';
 export { DEFAULT_FORMAT, format, type RkyvFormat } from './core/format.ts';
 export { RkyvReader } from './core/reader.ts';
+export { niching, type Niching } from './core/niching.ts';
+export type { EnumOptions } from './core/layout.ts';
 
 // ============================================================================
 // Primitive Codecs
@@ -93,6 +99,25 @@ export const u32: Decoder<number> = new PrimitiveDecoder(4, 4, Kind.u32, (r, o)
 export const i32: Decoder<number> = new PrimitiveDecoder(4, 4, Kind.i32, (r, o) => r.readI32(o), true);
 export const u64: Decoder<bigint> = new PrimitiveDecoder(8, 8, Kind.u64, (r, o) => r.readU64(o), true);
 export const i64: Decoder<bigint> = new PrimitiveDecoder(8, 8, Kind.i64, (r, o) => r.readI64(o), true);
+export const u128: Decoder<bigint> = new PrimitiveDecoder(16, 16, Kind.other, (r, o) => r.readU128(o), true);
+export const i128: Decoder<bigint> = new PrimitiveDecoder(16, 16, Kind.other, (r, o) => r.readI128(o), true);
+
+/**
+ * Archived `usize`/`isize` (rkyv `FixedUsize`/`FixedIsize`): sized by the
+ * format's pointer width, not the host's.
+ */
+export class PointerSizedDecoder extends PrimitiveDecoder<number> {
+  constructor(read: (reader: RkyvReader, offset: number) => number) {
+    super(0, 1, Kind.other, read, true);
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return ptrLayout(fmt);
+  }
+}
+
+export const usize: Decoder<number> = new PointerSizedDecoder((r, o) => r.readUsize(o));
+export const isize: Decoder<number> = new PointerSizedDecoder((r, o) => r.readIsize(o));
 
 // Float codecs (floats are not `Eq` in Rust, so they never hash).
 export const f32: Decoder<number> = new PrimitiveDecoder(4, 4, Kind.f32, (r, o) => r.readF32(o));
@@ -401,6 +426,45 @@ export function option<C extends AnyDecoder>(inner: C): Decoder<Infer<C> | null>
   return new OptionDecoder(inner);
 }
 
+// ============================================================================
+// Niched Option<T>
+// ============================================================================
+
+export class NichedDecoder<T> extends BaseDecoder<T | null> {
+  /** Inner codec (introspection surface). */
+  readonly inner: Decoder<T>;
+  /** Niching strategy (introspection surface). */
+  readonly niching: Niching;
+
+  constructor(inner: Decoder<T>, niching: Niching) {
+    super({ inline: inner.inline, hashable: false });
+    this.inner = inner;
+    this.niching = niching;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return this.inner.layout(fmt);
+  }
+
+  read(reader: RkyvReader, offset: number): T | null {
+    if (this.niching.isNiched(reader, offset, this.layout(reader.format))) return null;
+    return this.inner.read(reader, offset);
+  }
+
+  readLazy(reader: RkyvReader, offset: number): unknown {
+    if (this.niching.isNiched(reader, offset, this.layout(reader.format))) return null;
+    return this.inner.readLazy(reader, offset);
+  }
+}
+
+/**
+ * Niched Option<T> — laid out exactly like `T`, with `None` stored as a
+ * niche in `T`'s own bytes (`with = Niche` / `NicheInto<N>` / `MapNiche`).
+ */
+export function niched<C extends AnyDecoder>(inner: C, niching: Niching): Decoder<Infer<C> | null> {
+  return new NichedDecoder(inner, niching);
+}
+
 // ============================================================================
 // Box<T> / Rc<T> / Arc<T> / Weak<T>
 // ============================================================================
@@ -442,6 +506,29 @@ export function box<C extends AnyDecoder>(inner: C): Decoder<Infer<C>> {
  */
 export const rc: typeof box = box;
 
+/**
+ * Box<str> / Rc<str> / Arc<str> — a pointer to out-of-line UTF-8 bytes.
+ *
+ * Unlike `String`, the archived pointer never stores short text inline: it
+ * is a relative pointer plus a byte length, laid out like `Vec<u8>`.
+ */
+export class BoxedStrDecoder extends BaseDecoder<string, VecLayout> {
+  constructor() {
+    super({ inline: false, hashable: true });
+  }
+
+  computeLayout(fmt: RkyvFormat): VecLayout {
+    return vecLayout(fmt);
+  }
+
+  read(reader: RkyvReader, offset: number): string {
+    const l = this.layout(reader.format);
+    return reader.readText(reader.readRelPtr(offset), reader.readUsize(offset + l.pb));
+  }
+}
+
+export const boxedStr: Decoder<string> = new BoxedStrDecoder();
+
 export class WeakDecoder<T> extends BaseDecoder<T | null> {
   /** Inner codec (introspection surface). */
   readonly inner: Decoder<T>;
@@ -744,19 +831,22 @@ export interface EnumVariantField {
   readonly codec: AnyDecoder;
 }
 
-/** A normalized enum variant: its tag name and flattened field list. */
+/** A normalized enum variant: its tag name, archived tag value and flattened field list. */
 export interface EnumVariant {
   readonly name: string;
+  readonly discriminant: number;
   readonly fields: readonly EnumVariantField[];
 }
 
 export class EnumDecoder<T> extends BaseDecoder<T, EnumLayout> {
-  /** Normalized variants in discriminant order (introspection surface). */
+  /** Normalized variants in declaration order (introspection surface). */
   readonly variants: readonly EnumVariant[];
   #names: string[];
   #variantFields: (readonly EnumVariantField[])[];
+  /** Declaration index of each tag value; `undefined` for unused values. */
+  #indexOf: (number | undefined)[];
 
-  constructor(variants: EnumVariants) {
+  constructor(variants: EnumVariants, options?: EnumOptions) {
     const names = Object.keys(variants);
     // rkyv's derive rejects enums with more than 256 variants (u8 tag only).
     if (names.length > 256) {
@@ -783,9 +873,18 @@ export class EnumDecoder<T> extends BaseDecoder<T, EnumLayout> {
       inline: variantFields.every((fields) => fields.every((f) => f.codec.inline)),
       hashable: false,
     });
+    const discriminants = enumDiscriminants(names, options);
     this.#names = names;
     this.#variantFields = variantFields;
-    this.variants = names.map((name, i) => ({ name, fields: variantFields[i] }));
+    this.#indexOf = [];
+    discriminants.forEach((disc, i) => {
+      this.#indexOf[disc] = i;
+    });
+    this.variants = names.map((name, i) => ({
+      name,
+      discriminant: discriminants[i],
+      fields: variantFields[i],
+    }));
     this.meta = { kind: Kind.enum, variants: this.variants, layout: (fmt) => this.layout(fmt) };
   }
 
@@ -799,15 +898,16 @@ export class EnumDecoder<T> extends BaseDecoder<T, EnumLayout> {
   #readValue(reader: RkyvReader, offset: number, lazy: boolean): unknown {
     const l = this.layout(reader.format);
     const disc = l.discSize === 1 ? reader.readU8(offset) : reader.readU16(offset);
-    const tag = this.#names[disc];
-    if (tag === undefined) {
+    const index = this.#indexOf[disc];
+    if (index === undefined) {
       throw new Error(`invalid enum discriminant ${disc}`);
     }
-    const fields = this.#variantFields[disc];
+    const tag = this.#names[index];
+    const fields = this.#variantFields[index];
     if (fields.length === 0) {
       return { tag, value: null };
     }
-    const offsets = l.variants[disc].fieldOffsets;
+    const offsets = l.variants[index].fieldOffsets;
     if (fields.length === 1 && fields[0].name === null) {
       const codec = fields[0].codec;
       const fieldOffset = offset + offsets[0];
@@ -852,6 +952,9 @@ export class EnumDecoder<T> extends BaseDecoder<T, EnumLayout> {
  * aligned opaque body — the distinction matters whenever a variant's first
  * field has smaller alignment than its widest field.
  *
+ * Variants are tagged by declaration index unless `options.discriminants`
+ * gives explicit values.
+ *
  * @example
  * ```typescript
  * const Message = r.taggedEnum({
@@ -859,10 +962,81 @@ export class EnumDecoder<T> extends BaseDecoder<T, EnumLayout> {
  *   Move: { x: r.i32, y: r.i32 },
  *   Write: r.string,
  * });
+ * const Status = r.taggedEnum({ Active: null, Banned: null }, { discriminants: { Active: 1, Banned: 4 } });
+ * ```
+ */
+export function taggedEnum<const V extends EnumVariants>(
+  variants: V,
+  options?: EnumOptions<keyof V>,
+): Decoder<EnumValue<V>> {
+  return new EnumDecoder<EnumValue<V>>(variants, options);
+}
+
+// ============================================================================
+// Field-less enum as a primitive
+// ============================================================================
+
+/** rkyv's `repr(u8)` tag with no fields: one byte under every format. */
+const UNIT_ENUM_LAYOUT: Layout = { size: 1, align: 1 };
+
+/**
+ * A field-less enum decoded to a primitive: the variant whose discriminant
+ * is `discriminants[name]` decodes to `values[i]` (`name` being the `i`th key).
+ */
+export class UnitEnumDecoder<T extends string | number> extends BaseDecoder<T> {
+  #byDiscriminant: (T | undefined)[] = [];
+
+  constructor(discriminants: Readonly<Record<string, number>>, values: readonly T[]) {
+    // Rust hashes an enum by its `isize` discriminant, not its `u8` tag.
+    super({ inline: true, hashable: false });
+    enumDiscriminants(Object.keys(discriminants), { discriminants }).forEach((disc, i) => {
+      this.#byDiscriminant[disc] = values[i];
+    });
+  }
+
+  computeLayout(_fmt: RkyvFormat): Layout {
+    return UNIT_ENUM_LAYOUT;
+  }
+
+  read(reader: RkyvReader, offset: number): T {
+    const disc = reader.readU8(offset);
+    const value = this.#byDiscriminant[disc];
+    if (value === undefined) {
+      throw new Error(`invalid enum discriminant ${disc}`);
+    }
+    return value;
+  }
+}
+
+/**
+ * Field-less Rust enum decoded to its variant name.
+ *
+ * @example
+ * ```typescript
+ * const Status = r.stringEnum({ Active: 0, Banned: 1 });
+ * Status.decode(bytes); // 'Active' | 'Banned'
+ * ```
+ */
+export function stringEnum<const M extends Readonly<Record<string, number>>>(
+  discriminants: M,
+): Decoder<keyof M & string> {
+  return new UnitEnumDecoder(discriminants, Object.keys(discriminants) as (keyof M & string)[]);
+}
+
+/**
+ * Field-less Rust enum decoded to its discriminant.
+ *
+ * @example
+ * ```typescript
+ * const Status = { Active: 0, Banned: 1 } as const;
+ * const ArchivedStatus = r.numberEnum(Status);
+ * ArchivedStatus.decode(bytes) === Status.Banned;
  * ```
  */
-export function taggedEnum<const V extends EnumVariants>(variants: V): Decoder<EnumValue<V>> {
-  return new EnumDecoder<EnumValue<V>>(variants);
+export function numberEnum<const M extends Readonly<Record<string, number>>>(
+  discriminants: M,
+): Decoder<M[keyof M]> {
+  return new UnitEnumDecoder(discriminants, Object.values(discriminants) as M[keyof M][]);
 }
 
 // ============================================================================
@@ -10,14 +10,16 @@
  * encode-only codecs and full codecs both satisfy it.
  *
  * The factories mirror `./primitives.ts` name-for-name (struct, vec,
- * option, box, rc, weak, array, tuple, taggedEnum, union, transform,
- * newtype, lazy, plus the primitive singletons and string), so generated
- * bindings can switch direction by switching the import path.
+ * option, niched, box, rc, weak, array, tuple, taggedEnum, stringEnum,
+ * numberEnum, union, transform, newtype, lazy, plus the primitive singletons
+ * and string), so generated bindings can switch direction by switching the
+ * import path.
  */
 
 import type { Infer, Layout } from './core/base.ts';
 import type { RkyvFormat } from './core/format.ts';
 import type { RkyvHasher } from './core/hasher.ts';
+import type { Niching } from './core/niching.ts';
 import type { RkyvReader } from './core/reader.ts';
 import { BaseEncoder, FormatBoundEncoder, type AnyEncoder, type Encoder } from './core/encoder.ts';
 import type { RkyvTextEncoder, RkyvWriter } from './core/writer.ts';
@@ -25,6 +27,7 @@ import { Kind, primitiveKindOf, type PrimitiveKindTag } from './core/meta.ts';
 import {
   arrayLayout,
   elementStride,
+  enumDiscriminants,
   enumLayout,
   optionLayout,
   ptrLayout,
@@ -34,6 +37,7 @@ import {
   vecLayout,
   type ArrayLayout,
   type EnumLayout,
+  type EnumOptions,
   type OptionLayout,
   type StringLayout,
   type StructLayout,
@@ -46,6 +50,8 @@ export { Kind, OPAQUE_META, type CodecMeta, type PrimitiveKindTag } from './core
 export type { Infer, Layout } from './core/base.ts';
 export { DEFAULT_FORMAT, format, type RkyvFormat } from './core/format.ts';
 export { RkyvWriter } from './core/writer.ts';
+export { niching, type Niching } from './core/niching.ts';
+export type { EnumOptions } from './core/layout.ts';
 export type { RkyvTextEncoder } from './core/writer.ts';
 export type { RkyvHasher } from './core/hasher.ts';
 
@@ -185,6 +191,22 @@ export class PrimitiveEncoder<T> extends BaseEncoder<T, undefined> {
   }
 }
 
+const U128_BYTES = new Uint8Array(16);
+
+/**
+ * `Hasher::write_u128`: the 16 little-endian bytes, which is how both rkyv's
+ * FxHasher64 (two 64-bit words, low first) and the std default digest it.
+ * Also serves `i128` through the two's-complement cast.
+ */
+export function hashU128(hasher: RkyvHasher, value: bigint): void {
+  let v = BigInt.asUintN(128, value);
+  for (let i = 0; i < 16; i++) {
+    U128_BYTES[i] = Number(v & 0xffn);
+    v >>= 8n;
+  }
+  hasher.writeBytes(U128_BYTES);
+}
+
 // Integer codecs. `hash` mirrors Rust's `Hash` impls: unsigned ints write
 // their value; signed ints forward with a two's-complement cast.
 export const u8: Encoder<number> = new PrimitiveEncoder(1, 1, Kind.u8, (w, v) => w.writeU8(v), (h, v) => h.writeU8(v));
@@ -195,6 +217,31 @@ export const u32: Encoder<number> = new PrimitiveEncoder(4, 4, Kind.u32, (w, v)
 export const i32: Encoder<number> = new PrimitiveEncoder(4, 4, Kind.i32, (w, v) => w.writeI32(v), (h, v) => h.writeU32(v));
 export const u64: Encoder<bigint> = new PrimitiveEncoder(8, 8, Kind.u64, (w, v) => w.writeU64(v), (h, v) => h.writeU64(v));
 export const i64: Encoder<bigint> = new PrimitiveEncoder(8, 8, Kind.i64, (w, v) => w.writeI64(v), (h, v) => h.writeU64(v));
+export const u128: Encoder<bigint> = new PrimitiveEncoder(16, 16, Kind.other, (w, v) => w.writeU128(v), hashU128);
+export const i128: Encoder<bigint> = new PrimitiveEncoder(16, 16, Kind.other, (w, v) => w.writeI128(v), hashU128);
+
+/**
+ * Archived `usize`/`isize` (rkyv `FixedUsize`/`FixedIsize`): sized by the
+ * format's pointer width, not the host's.
+ */
+export class PointerSizedEncoder extends PrimitiveEncoder<number> {
+  constructor(
+    write: (writer: RkyvWriter, value: number) => number,
+    hash: (hasher: RkyvHasher, value: number) => void,
+  ) {
+    super(0, 1, Kind.other, write, hash);
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return ptrLayout(fmt);
+  }
+}
+
+// `write_usize` is truncated to the archived width by the hasher itself;
+// `write_isize` sign-extends to 64 bits under both rkyv's FxHasher64 and
+// the std default (which forwards to a 64-bit host `write_usize`).
+export const usize: Encoder<number> = new PointerSizedEncoder((w, v) => w.writeUsize(v), (h, v) => h.writeUsize(v));
+export const isize: Encoder<number> = new PointerSizedEncoder((w, v) => w.writeIsize(v), (h, v) => h.writeU64(BigInt(v)));
 
 // Float codecs (floats are not `Eq` in Rust, so they never hash).
 export const f32: Encoder<number> = new PrimitiveEncoder(4, 4, Kind.f32, (w, v) => w.writeF32(v));
@@ -510,6 +557,48 @@ export function option<C extends AnyEncoder>(inner: C): Encoder<Infer<C> | null>
   return new OptionEncoder(inner);
 }
 
+// ============================================================================
+// Niched Option<T>
+// ============================================================================
+
+export class NichedEncoder<T> extends BaseEncoder<T | null> {
+  #inner: Encoder<T>;
+  #niching: Niching;
+
+  constructor(inner: Encoder<T>, niching: Niching) {
+    super({ inline: inner.inline, hashable: false });
+    this.#inner = inner;
+    this.#niching = niching;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return this.#inner.layout(fmt);
+  }
+
+  archive(writer: RkyvWriter, value: T | null): unknown {
+    if (value === null || this.#inner.inline) return null;
+    return this.#inner.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: T | null, resolver: unknown): number {
+    const pos = writer.pos;
+    if (value === null) {
+      this.#niching.resolveNiched(writer, this.layout(writer.format));
+    } else {
+      this.#inner.resolve(writer, value, resolver);
+    }
+    return pos;
+  }
+}
+
+/**
+ * Niched Option<T> — laid out exactly like `T`, with `None` stored as a
+ * niche in `T`'s own bytes (`with = Niche` / `NicheInto<N>` / `MapNiche`).
+ */
+export function niched<C extends AnyEncoder>(inner: C, niching: Niching): Encoder<Infer<C> | null> {
+  return new NichedEncoder(inner, niching);
+}
+
 // ============================================================================
 // Box<T> / Rc<T> / Arc<T> / Weak<T>
 // ============================================================================
@@ -565,6 +654,42 @@ export function box<C extends AnyEncoder>(inner: C): Encoder<Infer<C>> {
  */
 export const rc: typeof box = box;
 
+/**
+ * Box<str> / Rc<str> / Arc<str> — a pointer to out-of-line UTF-8 bytes.
+ *
+ * Unlike `String`, the archived pointer never stores short text inline: it
+ * is a relative pointer plus a byte length, laid out like `Vec<u8>`.
+ */
+export class BoxedStrEncoder extends BaseEncoder<string, VecResolver, VecLayout> {
+  constructor() {
+    super({ inline: false, hashable: true });
+  }
+
+  computeLayout(fmt: RkyvFormat): VecLayout {
+    return vecLayout(fmt);
+  }
+
+  archive(writer: RkyvWriter, value: string): VecResolver {
+    const pos = writer.pos;
+    const len = writer.writeText(value);
+    return { pos, len };
+  }
+
+  resolve(writer: RkyvWriter, _value: string, resolver: VecResolver): number {
+    const structPos = writer.pos;
+    const ptrPos = writer.reserveRelPtr();
+    writer.writeUsize(resolver.len);
+    writer.writeRelPtrAt(ptrPos, resolver.pos);
+    return structPos;
+  }
+
+  hash(hasher: RkyvHasher, value: string, encoder: RkyvTextEncoder): void {
+    hashStringInto(hasher, value, encoder);
+  }
+}
+
+export const boxedStr: Encoder<string> = new BoxedStrEncoder();
+
 export class WeakEncoder<T> extends BaseEncoder<T | null, PtrResolver | null> {
   #inner: Encoder<T>;
 
@@ -892,11 +1017,13 @@ function normalizeEnumVariant(def: EnumVariantDef): readonly EnumEncodeVariantFi
 export class EnumEncoder<T> extends BaseEncoder<T, unknown[] | null, EnumLayout> {
   #names: readonly string[];
   #variantFields: readonly (readonly EnumEncodeVariantField[])[];
+  #discriminants: readonly number[];
   #variantIndex: Map<string, number>;
 
   constructor(
     names: readonly string[],
     variantFields: readonly (readonly EnumEncodeVariantField[])[],
+    options?: EnumOptions,
   ) {
     // rkyv's derive rejects enums with more than 256 variants (u8 tag only).
     if (names.length > 256) {
@@ -906,12 +1033,18 @@ export class EnumEncoder<T> extends BaseEncoder<T, unknown[] | null, EnumLayout>
       inline: variantFields.every((fields) => fields.every((f) => f.codec.inline)),
       hashable: false,
     });
+    const discriminants = enumDiscriminants(names, options);
     this.#names = names;
     this.#variantFields = variantFields;
+    this.#discriminants = discriminants;
     this.#variantIndex = new Map(names.map((name, i) => [name, i]));
     this.meta = {
       kind: Kind.enum,
-      variants: names.map((name, i) => ({ name, fields: variantFields[i] })),
+      variants: names.map((name, i) => ({
+        name,
+        discriminant: discriminants[i],
+        fields: variantFields[i],
+      })),
       layout: (fmt) => this.layout(fmt),
     };
   }
@@ -923,12 +1056,12 @@ export class EnumEncoder<T> extends BaseEncoder<T, unknown[] | null, EnumLayout>
     );
   }
 
-  #discriminant(tag: string): number {
-    const disc = this.#variantIndex.get(tag);
-    if (disc === undefined) {
+  #index(tag: string): number {
+    const index = this.#variantIndex.get(tag);
+    if (index === undefined) {
       throw new Error(`unknown enum variant ${tag}`);
     }
-    return disc;
+    return index;
   }
 
   #fieldValues(fields: readonly EnumEncodeVariantField[], value: unknown): unknown[] {
@@ -941,8 +1074,7 @@ export class EnumEncoder<T> extends BaseEncoder<T, unknown[] | null, EnumLayout>
 
   archive(writer: RkyvWriter, value: T): unknown[] | null {
     const v = value as { tag: string; value: unknown };
-    const disc = this.#discriminant(v.tag);
-    const fields = this.#variantFields[disc];
+    const fields = this.#variantFields[this.#index(v.tag)];
     if (fields.length === 0) return null;
     const values = this.#fieldValues(fields, v.value);
     const resolvers: unknown[] = new Array<unknown>(fields.length);
@@ -956,16 +1088,17 @@ export class EnumEncoder<T> extends BaseEncoder<T, unknown[] | null, EnumLayout>
   resolve(writer: RkyvWriter, value: T, resolver: unknown[] | null): number {
     const v = value as { tag: string; value: unknown };
     const l = this.layout(writer.format);
-    const disc = this.#discriminant(v.tag);
+    const index = this.#index(v.tag);
+    const disc = this.#discriminants[index];
     const pos = writer.pos;
     if (l.discSize === 1) {
       writer.writeU8(disc);
     } else {
       writer.writeU16(disc);
     }
-    const fields = this.#variantFields[disc];
+    const fields = this.#variantFields[index];
     if (fields.length > 0) {
-      const offsets = l.variants[disc].fieldOffsets;
+      const offsets = l.variants[index].fieldOffsets;
       const values = this.#fieldValues(fields, v.value);
       for (let i = 0; i < fields.length; i++) {
         writer.padTo(pos + offsets[i]);
@@ -983,15 +1116,70 @@ export class EnumEncoder<T> extends BaseEncoder<T, unknown[] | null, EnumLayout>
  *
  * Each variant is laid out as a C struct `{ tag, ...fields }` with fields
  * flattened directly after the tag (RFC 2195).
+ *
+ * Variants are tagged by declaration index unless `options.discriminants`
+ * gives explicit values.
  */
-export function taggedEnum<const V extends EnumVariants>(variants: V): Encoder<EnumValue<V>> {
+export function taggedEnum<const V extends EnumVariants>(
+  variants: V,
+  options?: EnumOptions<keyof V>,
+): Encoder<EnumValue<V>> {
   const names = Object.keys(variants);
   return new EnumEncoder<EnumValue<V>>(
     names,
     names.map((name) => normalizeEnumVariant(variants[name])),
+    options,
   );
 }
 
+// ============================================================================
+// Field-less enum as a primitive
+// ============================================================================
+
+/** rkyv's `repr(u8)` tag with no fields: one byte under every format. */
+const UNIT_ENUM_LAYOUT: Layout = { size: 1, align: 1 };
+
+/**
+ * A field-less enum encoded from a primitive: `values[i]` encodes to the
+ * discriminant of the `i`th variant in `discriminants`.
+ */
+export class UnitEnumEncoder<T extends string | number> extends BaseEncoder<T, undefined> {
+  #discriminants: Map<T, number>;
+
+  constructor(discriminants: Readonly<Record<string, number>>, values: readonly T[]) {
+    // Rust hashes an enum by its `isize` discriminant, not its `u8` tag.
+    super({ inline: true, hashable: false });
+    const discs = enumDiscriminants(Object.keys(discriminants), { discriminants });
+    this.#discriminants = new Map(values.map((value, i) => [value, discs[i]]));
+  }
+
+  computeLayout(_fmt: RkyvFormat): Layout {
+    return UNIT_ENUM_LAYOUT;
+  }
+
+  resolve(writer: RkyvWriter, value: T, _resolver: undefined): number {
+    const disc = this.#discriminants.get(value);
+    if (disc === undefined) {
+      throw new Error(`unknown enum variant ${value}`);
+    }
+    return writer.writeU8(disc);
+  }
+}
+
+/** Field-less Rust enum encoded from its variant name. */
+export function stringEnum<const M extends Readonly<Record<string, number>>>(
+  discriminants: M,
+): Encoder<keyof M & string> {
+  return new UnitEnumEncoder(discriminants, Object.keys(discriminants) as (keyof M & string)[]);
+}
+
+/** Field-less Rust enum encoded from its discriminant. */
+export function numberEnum<const M extends Readonly<Record<string, number>>>(
+  discriminants: M,
+): Encoder<M[keyof M]> {
+  return new UnitEnumEncoder(discriminants, Object.values(discriminants) as M[keyof M][]);
+}
+
 // ============================================================================
 // Untagged union
 // ============================================================================
@@ -0,0 +1,68 @@
+/**
+ * core::time::Duration decoder for rkyv-js
+ *
+ * Also decodes `SystemTime` fields archived through `rkyv::with::AsUnixTime`,
+ * which rkyv stores as the `Duration` since the Unix epoch.
+ * @see https://docs.rs/rkyv/0.8/rkyv/time/struct.ArchivedDuration.html
+ */
+
+import {
+  BaseDecoder,
+  type Decoder,
+  type Layout,
+  type RkyvFormat,
+  type RkyvReader,
+} from 'rkyv-js/core';
+
+/** A `Duration` as its archived parts: whole seconds and subsecond nanoseconds. */
+export interface Duration {
+  secs: bigint;
+  nanos: number;
+}
+
+// `ArchivedDuration` is `repr(C) { secs: u64, nanos: u32 }`: trailing
+// padding when aligned, none when every field is align 1.
+const DURATION_ALIGNED: Layout = { size: 16, align: 8 };
+const DURATION_UNALIGNED: Layout = { size: 12, align: 1 };
+
+export class DurationDecoder<T> extends BaseDecoder<T> {
+  #fromParts: (secs: bigint, nanos: number) => T;
+
+  constructor(fromParts: (secs: bigint, nanos: number) => T, hashable: boolean = true) {
+    super({ inline: true, hashable });
+    this.#fromParts = fromParts;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? DURATION_ALIGNED : DURATION_UNALIGNED;
+  }
+
+  read(reader: RkyvReader, offset: number): T {
+    return this.#fromParts(reader.readU64(offset), reader.readU32(offset + 8));
+  }
+}
+
+/** Milliseconds of a `Duration`, subsecond precision kept as a fraction. */
+export function partsToMillis(secs: bigint, nanos: number): number {
+  return Number(secs) * 1000 + nanos / 1_000_000;
+}
+
+/**
+ * core::time::Duration — decoded losslessly as `{ secs: bigint, nanos: number }`.
+ */
+export const duration: Decoder<Duration> = new DurationDecoder((secs, nanos) => ({ secs, nanos }));
+
+/**
+ * core::time::Duration — decoded as (fractional) milliseconds.
+ */
+export const durationMillis: Decoder<number> = new DurationDecoder(partsToMillis);
+
+/**
+ * std::time::SystemTime with `rkyv::with::AsUnixTime` — decoded as a `Date`
+ * (millisecond precision).
+ */
+export const unixTime: Decoder<Date> = new DurationDecoder(
+  (secs, nanos) => new Date(Math.floor(partsToMillis(secs, nanos))),
+  // `SystemTime` only archives through a field wrapper, so it never keys a map.
+  false,
+);
@@ -0,0 +1,91 @@
+/**
+ * core::time::Duration encoder for rkyv-js
+ *
+ * Also encodes `SystemTime` fields archived through `rkyv::with::AsUnixTime`,
+ * which rkyv stores as the `Duration` since the Unix epoch.
+ * @see https://docs.rs/rkyv/0.8/rkyv/time/struct.ArchivedDuration.html
+ */
+
+import {
+  BaseEncoder,
+  type Encoder,
+  type Layout,
+  type RkyvFormat,
+  type RkyvHasher,
+  type RkyvTextEncoder,
+  type RkyvWriter,
+} from 'rkyv-js/core';
+
+import type { Duration } from './duration.decode.ts';
+
+const NANOS_PER_SEC = 1_000_000_000;
+const U64_MAX = 0xffff_ffff_ffff_ffffn;
+
+const DURATION_ALIGNED: Layout = { size: 16, align: 8 };
+const DURATION_UNALIGNED: Layout = { size: 12, align: 1 };
+
+export class DurationEncoder<T> extends BaseEncoder<T, undefined> {
+  #toParts: (value: T) => Duration;
+
+  constructor(toParts: (value: T) => Duration, hashable: boolean = true) {
+    super({ inline: true, hashable });
+    this.#toParts = toParts;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? DURATION_ALIGNED : DURATION_UNALIGNED;
+  }
+
+  resolve(writer: RkyvWriter, value: T, _resolver: undefined): number {
+    const { secs, nanos } = checkedParts(this.#toParts(value));
+    const pos = writer.writeU64(secs);
+    writer.writeU32(nanos);
+    writer.padTo(pos + this.layout(writer.format).size);
+    return pos;
+  }
+
+  // Duration derives Hash over `secs: u64` then `nanos: u32`.
+  hash(hasher: RkyvHasher, value: T, _encoder: RkyvTextEncoder): void {
+    const { secs, nanos } = checkedParts(this.#toParts(value));
+    hasher.writeU64(secs);
+    hasher.writeU32(nanos);
+  }
+}
+
+// `ArchivedDuration` validation rejects subsecond parts of a second or more.
+function checkedParts(parts: Duration): Duration {
+  const { secs, nanos } = parts;
+  if (secs < 0n || secs > U64_MAX || !Number.isInteger(nanos) || nanos < 0 || nanos >= NANOS_PER_SEC) {
+    throw new RangeError(`Invalid Duration: { secs: ${secs}, nanos: ${nanos} }`);
+  }
+  return parts;
+}
+
+/** Split (fractional) milliseconds into `Duration` parts, rounding to whole nanoseconds. */
+export function millisToParts(millis: number): Duration {
+  if (!Number.isFinite(millis) || millis < 0) {
+    throw new RangeError(`Invalid Duration: ${millis}ms`);
+  }
+  const secs = Math.floor(millis / 1000);
+  const nanos = Math.round((millis - secs * 1000) * 1_000_000);
+  // Rounding can carry a full second (e.g. 999.9999999ms).
+  return nanos === NANOS_PER_SEC
+    ? { secs: BigInt(secs + 1), nanos: 0 }
+    : { secs: BigInt(secs), nanos };
+}
+
+/**
+ * core::time::Duration — archived from `{ secs: bigint, nanos: number }`.
+ */
+export const duration: Encoder<Duration> = new DurationEncoder((value) => value);
+
+/**
+ * core::time::Duration — archived from (fractional) milliseconds.
+ */
+export const durationMillis: Encoder<number> = new DurationEncoder(millisToParts);
+
+/**
+ * std::time::SystemTime with `rkyv::with::AsUnixTime` — archived from a
+ * `Date`. Like rkyv, times before the Unix epoch are rejected.
+ */
+export const unixTime: Encoder<Date> = new DurationEncoder((value) => millisToParts(value.getTime()), false);
@@ -0,0 +1,87 @@
+/**
+ * core::time::Duration codecs for rkyv-js
+ *
+ * Also covers `SystemTime` fields archived through `rkyv::with::AsUnixTime`,
+ * which rkyv stores as the `Duration` since the Unix epoch.
+ * @see https://docs.rs/rkyv/0.8/rkyv/time/struct.ArchivedDuration.html
+ *
+ * The logic lives once per direction: the full codecs here EXTEND the read
+ * class from `./duration.decode.ts` and CONTAIN the encode class from
+ * `./duration.encode.ts`, delegating `resolve`/`hash` to it. One-direction
+ * consumers import those modules directly instead.
+ */
+
+import {
+  DEFAULT_FORMAT,
+  encodeIntoWriter,
+  encodePooled,
+  type Codec,
+  type RkyvFormat,
+  type RkyvHasher,
+  type RkyvTextEncoder,
+  type RkyvWriter,
+} from 'rkyv-js/core';
+
+import { DurationDecoder, partsToMillis, type Duration } from './duration.decode.ts';
+import { DurationEncoder, millisToParts } from './duration.encode.ts';
+
+export { DurationDecoder, type Duration } from './duration.decode.ts';
+export { DurationEncoder } from './duration.encode.ts';
+
+export class DurationCodec<T> extends DurationDecoder<T> {
+  #write: DurationEncoder<T>;
+
+  constructor(
+    fromParts: (secs: bigint, nanos: number) => T,
+    toParts: (value: T) => Duration,
+    hashable: boolean = true,
+  ) {
+    super(fromParts, hashable);
+    this.#write = new DurationEncoder(toParts, hashable);
+  }
+
+  archive(writer: RkyvWriter, value: T): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: T, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: T, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: T, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: T): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+/**
+ * core::time::Duration — `{ secs: bigint, nanos: number }`, lossless.
+ */
+export const duration: Codec<Duration> = new DurationCodec(
+  (secs, nanos) => ({ secs, nanos }),
+  (value) => value,
+);
+
+/**
+ * core::time::Duration — (fractional) milliseconds.
+ */
+export const durationMillis: Codec<number> = new DurationCodec(partsToMillis, millisToParts);
+
+/**
+ * std::time::SystemTime with `rkyv::with::AsUnixTime` — a `Date`
+ * (millisecond precision). Like rkyv, times before the Unix epoch are
+ * rejected on encode.
+ */
+export const unixTime: Codec<Date> = new DurationCodec(
+  (secs, nanos) => new Date(Math.floor(partsToMillis(secs, nanos))),
+  (value) => millisToParts(value.getTime()),
+  // `SystemTime` only archives through a field wrapper, so it never keys a map.
+  false,
+);
@@ -0,0 +1,57 @@
+/**
+ * std::ffi::CString decoder for rkyv-js
+ *
+ * rkyv archives a `CString` as a relative pointer to its bytes, trailing nul
+ * included, and their count.
+ * @see https://docs.rs/rkyv/0.8/rkyv/ffi/struct.ArchivedCString.html
+ */
+
+import {
+  BaseDecoder,
+  type Decoder,
+  type Layout,
+  type RkyvFormat,
+  type RkyvReader,
+} from 'rkyv-js/core';
+
+export interface CStringLayout extends Layout {
+  pb: 2 | 4 | 8;
+}
+
+export class CStringDecoder<T> extends BaseDecoder<T, CStringLayout> {
+  #fromBytes: (reader: RkyvReader, offset: number, length: number) => T;
+
+  /** `fromBytes` receives the string's bytes without the trailing nul. */
+  constructor(fromBytes: (reader: RkyvReader, offset: number, length: number) => T) {
+    super({ inline: false, hashable: true });
+    this.#fromBytes = fromBytes;
+  }
+
+  computeLayout(fmt: RkyvFormat): CStringLayout {
+    const pb = (fmt.pointerWidth / 8) as 2 | 4 | 8;
+    return { size: pb * 2, align: fmt.aligned ? pb : 1, pb };
+  }
+
+  read(reader: RkyvReader, offset: number): T {
+    const l = this.layout(reader.format);
+    const dataOffset = reader.readRelPtr(offset);
+    const lengthWithNul = reader.readUsize(offset + l.pb);
+    if (lengthWithNul === 0) {
+      throw new Error('Invalid CString: missing the trailing nul');
+    }
+    return this.#fromBytes(reader, dataOffset, lengthWithNul - 1);
+  }
+}
+
+/** std::ffi::CString — decoded as UTF-8 text, without the trailing nul. */
+export const cString: Decoder<string> = new CStringDecoder((reader, offset, length) =>
+  reader.readText(offset, length),
+);
+
+/**
+ * std::ffi::CString — decoded as a zero-copy `Uint8Array` view of its bytes,
+ * without the trailing nul. Use it for strings that need not be UTF-8.
+ */
+export const cStringBytes: Decoder<Uint8Array> = new CStringDecoder((reader, offset, length) =>
+  reader.readBytes(offset, length),
+);
@@ -0,0 +1,88 @@
+/**
+ * std::ffi::CString encoder for rkyv-js
+ *
+ * rkyv archives a `CString` as a relative pointer to its bytes, trailing nul
+ * included, and their count.
+ * @see https://docs.rs/rkyv/0.8/rkyv/ffi/struct.ArchivedCString.html
+ */
+
+import {
+  BaseEncoder,
+  type Encoder,
+  type Layout,
+  type RkyvFormat,
+  type RkyvHasher,
+  type RkyvTextEncoder,
+  type RkyvWriter,
+} from 'rkyv-js/core';
+
+export interface CStringLayout extends Layout {
+  pb: 2 | 4 | 8;
+}
+
+export interface CStringResolver {
+  pos: number;
+  /** Byte count, trailing nul included. */
+  len: number;
+}
+
+export class CStringEncoder<T> extends BaseEncoder<T, CStringResolver, CStringLayout> {
+  #toBytes: (value: T, encoder: RkyvTextEncoder) => Uint8Array;
+
+  /** `toBytes` returns the string's bytes without the trailing nul. */
+  constructor(toBytes: (value: T, encoder: RkyvTextEncoder) => Uint8Array) {
+    super({ inline: false, hashable: true });
+    this.#toBytes = toBytes;
+  }
+
+  computeLayout(fmt: RkyvFormat): CStringLayout {
+    const pb = (fmt.pointerWidth / 8) as 2 | 4 | 8;
+    return { size: pb * 2, align: fmt.aligned ? pb : 1, pb };
+  }
+
+  archive(writer: RkyvWriter, value: T): CStringResolver {
+    const bytes = this.#checkedBytes(value, writer.textEncoder);
+    const pos = writer.writeBytes(bytes);
+    writer.writeU8(0);
+    return { pos, len: bytes.length + 1 };
+  }
+
+  resolve(writer: RkyvWriter, _value: T, resolver: CStringResolver): number {
+    const structPos = writer.pos;
+    const ptrPos = writer.reserveRelPtr();
+    writer.writeUsize(resolver.len);
+    writer.writeRelPtrAt(ptrPos, resolver.pos);
+    return structPos;
+  }
+
+  // CString hashes its bytes with the nul as a slice: length prefix, then the raw bytes.
+  hash(hasher: RkyvHasher, value: T, encoder: RkyvTextEncoder): void {
+    const bytes = this.#checkedBytes(value, encoder);
+    hasher.writeUsize(bytes.length + 1);
+    hasher.writeBytes(bytes);
+    hasher.writeU8(0);
+  }
+
+  #checkedBytes(value: T, encoder: RkyvTextEncoder): Uint8Array {
+    const bytes = this.#toBytes(value, encoder);
+    const nul = bytes.indexOf(0);
+    if (nul !== -1) {
+      throw new RangeError(`Invalid CString: interior nul byte at position ${nul}`);
+    }
+    return bytes;
+  }
+}
+
+/** UTF-8 encode `value` with the writer's text encoder. */
+export function encodeUtf8(value: string, encoder: RkyvTextEncoder): Uint8Array {
+  // Worst case: 3 bytes per UTF-16 code unit.
+  const buf = new Uint8Array(value.length * 3);
+  const { written } = encoder.encodeInto(value, buf);
+  return buf.subarray(0, written);
+}
+
+/** std::ffi::CString — archived from UTF-8 text; the trailing nul is appended. */
+export const cString: Encoder<string> = new CStringEncoder(encodeUtf8);
+
+/** std::ffi::CString — archived from its bytes; the trailing nul is appended. */
+export const cStringBytes: Encoder<Uint8Array> = new CStringEncoder((value) => value);
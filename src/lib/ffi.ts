@@ -0,0 +1,77 @@
+/**
+ * std::ffi::CString codecs for rkyv-js
+ *
+ * rkyv archives a `CString` as a relative pointer to its bytes, trailing nul
+ * included, and their count.
+ * @see https://docs.rs/rkyv/0.8/rkyv/ffi/struct.ArchivedCString.html
+ *
+ * The logic lives once per direction: the full codecs here EXTEND the read
+ * class from `./ffi.decode.ts` and CONTAIN the encode class from
+ * `./ffi.encode.ts`, delegating `archive`/`resolve`/`hash` to it.
+ * One-direction consumers import those modules directly instead.
+ */
+
+import {
+  DEFAULT_FORMAT,
+  encodeIntoWriter,
+  encodePooled,
+  type Codec,
+  type RkyvFormat,
+  type RkyvHasher,
+  type RkyvReader,
+  type RkyvTextEncoder,
+  type RkyvWriter,
+} from 'rkyv-js/core';
+
+import { CStringDecoder } from './ffi.decode.ts';
+import { CStringEncoder, encodeUtf8, type CStringResolver } from './ffi.encode.ts';
+
+export { CStringDecoder } from './ffi.decode.ts';
+export { CStringEncoder } from './ffi.encode.ts';
+
+export class CStringCodec<T> extends CStringDecoder<T> {
+  #write: CStringEncoder<T>;
+
+  constructor(
+    fromBytes: (reader: RkyvReader, offset: number, length: number) => T,
+    toBytes: (value: T, encoder: RkyvTextEncoder) => Uint8Array,
+  ) {
+    super(fromBytes);
+    this.#write = new CStringEncoder(toBytes);
+  }
+
+  archive(writer: RkyvWriter, value: T): CStringResolver {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: T, resolver: CStringResolver): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: T, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: T, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: T): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+/** std::ffi::CString — UTF-8 text, without the trailing nul. */
+export const cString: Codec<string> = new CStringCodec(
+  (reader, offset, length) => reader.readText(offset, length),
+  encodeUtf8,
+);
+
+/**
+ * std::ffi::CString — its bytes without the trailing nul, decoded as a
+ * zero-copy `Uint8Array` view. Use it for strings that need not be UTF-8.
+ */
+export const cStringBytes: Codec<Uint8Array> = new CStringCodec(
+  (reader, offset, length) => reader.readBytes(offset, length),
+  (value) => value,
+);
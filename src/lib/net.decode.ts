@@ -0,0 +1,252 @@
+/**
+ * std::net address decoders for rkyv-js
+ *
+ * Decodes `Ipv4Addr`, `Ipv6Addr`, `IpAddr`, `SocketAddrV4`, `SocketAddrV6`
+ * and `SocketAddr` as archived by rkyv, with addresses in their text form
+ * (`192.168.0.1`, `2001:db8::1`) or as their octets.
+ * @see https://docs.rs/rkyv/0.8/rkyv/net/index.html
+ */
+
+import {
+  BaseDecoder,
+  type Decoder,
+  type Layout,
+  type RkyvFormat,
+  type RkyvReader,
+} from 'rkyv-js/core';
+
+/** A `SocketAddrV4`: an IPv4 address and a port. */
+export interface SocketAddrV4<Ip = string> {
+  ip: Ip;
+  port: number;
+}
+
+/** A `SocketAddrV6`: an IPv6 address, a port, and the flow and scope identifiers. */
+export interface SocketAddrV6<Ip = string> {
+  ip: Ip;
+  port: number;
+  flowinfo: number;
+  scopeId: number;
+}
+
+/** A `SocketAddr`, either version; the address tells them apart. */
+export type SocketAddr<Ip = string> = SocketAddrV4<Ip> | SocketAddrV6<Ip>;
+
+/**
+ * Format 4 or 16 octets like Rust's `Display`: dotted decimal for IPv4, and
+ * RFC 5952 text for IPv6 (lowercase hex, the longest run of two or more zero
+ * groups compressed to `::`, IPv4-mapped addresses as `::ffff:a.b.c.d`).
+ */
+export function formatIp(octets: Uint8Array): string {
+  if (octets.length === 4) {
+    return octets.join('.');
+  }
+  const groups: number[] = [];
+  for (let i = 0; i < 16; i += 2) {
+    groups.push((octets[i] << 8) | octets[i + 1]);
+  }
+  if (groups.slice(0, 5).every((g) => g === 0) && groups[5] === 0xffff) {
+    return `::ffff:${octets.subarray(12).join('.')}`;
+  }
+  let bestStart = -1;
+  let bestLen = 1;
+  for (let i = 0; i < 8; ) {
+    let j = i;
+    while (j < 8 && groups[j] === 0) j++;
+    if (j - i > bestLen) {
+      bestStart = i;
+      bestLen = j - i;
+    }
+    i = j === i ? i + 1 : j;
+  }
+  const hex = (gs: number[]) => gs.map((g) => g.toString(16)).join(':');
+  if (bestStart < 0) {
+    return hex(groups);
+  }
+  return `${hex(groups.slice(0, bestStart))}::${hex(groups.slice(bestStart + bestLen))}`;
+}
+
+const IPV4: Layout = { size: 4, align: 1 };
+const IPV6: Layout = { size: 16, align: 1 };
+// `ArchivedIpAddr` is a `repr(u8)` enum over align-1 payloads.
+const IP: Layout = { size: 17, align: 1 };
+
+// `ArchivedSocketAddrV4` is `repr(C) { ip: [u8; 4], port: u16 }` and
+// `ArchivedSocketAddrV6` is `repr(C) { ip: [u8; 16], port: u16, flowinfo: u32, scope_id: u32 }`.
+const SOCKET_V4_ALIGNED: Layout = { size: 6, align: 2 };
+const SOCKET_V4_UNALIGNED: Layout = { size: 6, align: 1 };
+const SOCKET_V6_ALIGNED: Layout = { size: 28, align: 4 };
+const SOCKET_V6_UNALIGNED: Layout = { size: 26, align: 1 };
+// `ArchivedSocketAddr` is a `repr(u8)` enum: each payload follows the tag at its own alignment.
+const SOCKET_ALIGNED: Layout = { size: 32, align: 4 };
+const SOCKET_UNALIGNED: Layout = { size: 27, align: 1 };
+
+export class IpDecoder<Ip> extends BaseDecoder<Ip> {
+  #size: 4 | 16;
+  #fromOctets: (octets: Uint8Array) => Ip;
+
+  /** `size` is 4 for `Ipv4Addr` and 16 for `Ipv6Addr`. */
+  constructor(size: 4 | 16, fromOctets: (octets: Uint8Array) => Ip) {
+    super({ inline: true, hashable: true });
+    this.#size = size;
+    this.#fromOctets = fromOctets;
+  }
+
+  computeLayout(_fmt: RkyvFormat): Layout {
+    // [u8; N] — alignment 1 under every format.
+    return this.#size === 4 ? IPV4 : IPV6;
+  }
+
+  read(reader: RkyvReader, offset: number): Ip {
+    return this.#fromOctets(reader.readBytes(offset, this.#size).slice());
+  }
+}
+
+export class IpAddrDecoder<Ip> extends BaseDecoder<Ip> {
+  #fromOctets: (octets: Uint8Array) => Ip;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip) {
+    super({ inline: true, hashable: true });
+    this.#fromOctets = fromOctets;
+  }
+
+  computeLayout(_fmt: RkyvFormat): Layout {
+    return IP;
+  }
+
+  read(reader: RkyvReader, offset: number): Ip {
+    return this.#fromOctets(reader.readBytes(offset + 1, ipSize(reader.readU8(offset))).slice());
+  }
+}
+
+export class SocketAddrV4Decoder<Ip> extends BaseDecoder<SocketAddrV4<Ip>> {
+  #fromOctets: (octets: Uint8Array) => Ip;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip) {
+    super({ inline: true, hashable: true });
+    this.#fromOctets = fromOctets;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? SOCKET_V4_ALIGNED : SOCKET_V4_UNALIGNED;
+  }
+
+  read(reader: RkyvReader, offset: number): SocketAddrV4<Ip> {
+    return readSocketV4(reader, offset, this.#fromOctets);
+  }
+}
+
+export class SocketAddrV6Decoder<Ip> extends BaseDecoder<SocketAddrV6<Ip>> {
+  #fromOctets: (octets: Uint8Array) => Ip;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip) {
+    super({ inline: true, hashable: true });
+    this.#fromOctets = fromOctets;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? SOCKET_V6_ALIGNED : SOCKET_V6_UNALIGNED;
+  }
+
+  read(reader: RkyvReader, offset: number): SocketAddrV6<Ip> {
+    return readSocketV6(reader, offset, this.#fromOctets);
+  }
+}
+
+export class SocketAddrDecoder<Ip> extends BaseDecoder<SocketAddr<Ip>> {
+  #fromOctets: (octets: Uint8Array) => Ip;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip) {
+    super({ inline: true, hashable: true });
+    this.#fromOctets = fromOctets;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? SOCKET_ALIGNED : SOCKET_UNALIGNED;
+  }
+
+  read(reader: RkyvReader, offset: number): SocketAddr<Ip> {
+    const tag = reader.readU8(offset);
+    const payload = offset + socketPayloadOffset(reader.format, ipSize(tag));
+    return tag === 0
+      ? readSocketV4(reader, payload, this.#fromOctets)
+      : readSocketV6(reader, payload, this.#fromOctets);
+  }
+}
+
+/** The octet count of the `V4` (tag 0) or `V6` (tag 1) variant. */
+function ipSize(tag: number): 4 | 16 {
+  if (tag > 1) {
+    throw new Error(`Invalid address version tag: ${tag}`);
+  }
+  return tag === 0 ? 4 : 16;
+}
+
+/** Where a `SocketAddr` variant's payload starts: after the tag, at the payload's alignment. */
+function socketPayloadOffset(fmt: RkyvFormat, ipSize: 4 | 16): number {
+  if (!fmt.aligned) return 1;
+  return ipSize === 4 ? 2 : 4;
+}
+
+function readSocketV4<Ip>(
+  reader: RkyvReader,
+  offset: number,
+  fromOctets: (octets: Uint8Array) => Ip,
+): SocketAddrV4<Ip> {
+  return {
+    ip: fromOctets(reader.readBytes(offset, 4).slice()),
+    port: reader.readU16(offset + 4),
+  };
+}
+
+function readSocketV6<Ip>(
+  reader: RkyvReader,
+  offset: number,
+  fromOctets: (octets: Uint8Array) => Ip,
+): SocketAddrV6<Ip> {
+  const flowinfo = offset + (reader.format.aligned ? 20 : 18);
+  return {
+    ip: fromOctets(reader.readBytes(offset, 16).slice()),
+    port: reader.readU16(offset + 16),
+    flowinfo: reader.readU32(flowinfo),
+    scopeId: reader.readU32(flowinfo + 4),
+  };
+}
+
+const octets = (octets: Uint8Array) => octets;
+
+/** std::net::Ipv4Addr — decoded as dotted decimal text (`192.168.0.1`). */
+export const ipv4Addr: Decoder<string> = new IpDecoder(4, formatIp);
+
+/** std::net::Ipv6Addr — decoded as RFC 5952 text (`2001:db8::1`). */
+export const ipv6Addr: Decoder<string> = new IpDecoder(16, formatIp);
+
+/** std::net::IpAddr — decoded as the text of either version. */
+export const ipAddr: Decoder<string> = new IpAddrDecoder(formatIp);
+
+/** std::net::SocketAddrV4 — decoded as `{ ip, port }` with a text address. */
+export const socketAddrV4: Decoder<SocketAddrV4> = new SocketAddrV4Decoder(formatIp);
+
+/** std::net::SocketAddrV6 — decoded as `{ ip, port, flowinfo, scopeId }` with a text address. */
+export const socketAddrV6: Decoder<SocketAddrV6> = new SocketAddrV6Decoder(formatIp);
+
+/** std::net::SocketAddr — decoded as a socket address of either version with a text address. */
+export const socketAddr: Decoder<SocketAddr> = new SocketAddrDecoder(formatIp);
+
+/** std::net::Ipv4Addr — decoded as its 4 octets. */
+export const ipv4AddrBytes: Decoder<Uint8Array> = new IpDecoder(4, octets);
+
+/** std::net::Ipv6Addr — decoded as its 16 octets. */
+export const ipv6AddrBytes: Decoder<Uint8Array> = new IpDecoder(16, octets);
+
+/** std::net::IpAddr — decoded as 4 or 16 octets. */
+export const ipAddrBytes: Decoder<Uint8Array> = new IpAddrDecoder(octets);
+
+/** std::net::SocketAddrV4 — decoded as `{ ip, port }` with the address octets. */
+export const socketAddrV4Bytes: Decoder<SocketAddrV4<Uint8Array>> = new SocketAddrV4Decoder(octets);
+
+/** std::net::SocketAddrV6 — decoded as `{ ip, port, flowinfo, scopeId }` with the address octets. */
+export const socketAddrV6Bytes: Decoder<SocketAddrV6<Uint8Array>> = new SocketAddrV6Decoder(octets);
+
+/** std::net::SocketAddr — decoded as a socket address of either version with the address octets. */
+export const socketAddrBytes: Decoder<SocketAddr<Uint8Array>> = new SocketAddrDecoder(octets);
@@ -0,0 +1,310 @@
+/**
+ * std::net address encoders for rkyv-js
+ *
+ * Encodes `Ipv4Addr`, `Ipv6Addr`, `IpAddr`, `SocketAddrV4`, `SocketAddrV6`
+ * and `SocketAddr` as archived by rkyv, from addresses in their text form
+ * (`192.168.0.1`, `2001:db8::1`) or as their octets.
+ * @see https://docs.rs/rkyv/0.8/rkyv/net/index.html
+ */
+
+import {
+  BaseEncoder,
+  type Encoder,
+  type Layout,
+  type RkyvFormat,
+  type RkyvHasher,
+  type RkyvTextEncoder,
+  type RkyvWriter,
+} from 'rkyv-js/core';
+
+import type { SocketAddr, SocketAddrV4, SocketAddrV6 } from './net.decode.ts';
+
+/**
+ * Parse an address the way Rust's `FromStr` does: dotted decimal IPv4
+ * (no leading zeros) or IPv6 text, `::` compression and a dotted IPv4
+ * tail included. Returns 4 or 16 octets.
+ */
+export function parseIp(text: string): Uint8Array {
+  const octets = text.includes(':') ? parseIpv6(text) : parseIpv4(text);
+  if (octets === null) {
+    throw new Error(`Invalid IP address: ${text}`);
+  }
+  return octets;
+}
+
+function parseIpv4(text: string): Uint8Array | null {
+  const parts = text.split('.');
+  if (parts.length !== 4) return null;
+  const octets = new Uint8Array(4);
+  for (let i = 0; i < 4; i++) {
+    const part = parts[i];
+    if (!/^(0|[1-9][0-9]{0,2})$/.test(part) || Number(part) > 255) return null;
+    octets[i] = Number(part);
+  }
+  return octets;
+}
+
+function parseIpv6(text: string): Uint8Array | null {
+  const halves = text.split('::');
+  if (halves.length > 2) return null;
+  const head = groups(halves[0]);
+  const tail = halves.length === 2 ? groups(halves[1]) : [];
+  if (head === null || tail === null) return null;
+  const explicit = head.length + tail.length;
+  // `::` stands for at least one zero group.
+  if (halves.length === 2 ? explicit > 7 : explicit !== 8) return null;
+  const all = [...head, ...new Array<number>(8 - explicit).fill(0), ...tail];
+  const octets = new Uint8Array(16);
+  all.forEach((group, i) => {
+    octets[i * 2] = group >> 8;
+    octets[i * 2 + 1] = group & 0xff;
+  });
+  return octets;
+}
+
+/** The 16-bit groups of one side of `::`; a trailing dotted IPv4 counts as two. */
+function groups(text: string): number[] | null {
+  if (text === '') return [];
+  const parts = text.split(':');
+  const out: number[] = [];
+  for (let i = 0; i < parts.length; i++) {
+    const part = parts[i];
+    if (i === parts.length - 1 && part.includes('.')) {
+      const v4 = parseIpv4(part);
+      if (v4 === null) return null;
+      out.push((v4[0] << 8) | v4[1], (v4[2] << 8) | v4[3]);
+    } else if (/^[0-9a-fA-F]{1,4}$/.test(part)) {
+      out.push(parseInt(part, 16));
+    } else {
+      return null;
+    }
+  }
+  return out;
+}
+
+const IPV4: Layout = { size: 4, align: 1 };
+const IPV6: Layout = { size: 16, align: 1 };
+const IP: Layout = { size: 17, align: 1 };
+const SOCKET_V4_ALIGNED: Layout = { size: 6, align: 2 };
+const SOCKET_V4_UNALIGNED: Layout = { size: 6, align: 1 };
+const SOCKET_V6_ALIGNED: Layout = { size: 28, align: 4 };
+const SOCKET_V6_UNALIGNED: Layout = { size: 26, align: 1 };
+const SOCKET_ALIGNED: Layout = { size: 32, align: 4 };
+const SOCKET_UNALIGNED: Layout = { size: 27, align: 1 };
+
+export class IpEncoder<Ip> extends BaseEncoder<Ip, undefined> {
+  #size: 4 | 16;
+  #toOctets: (ip: Ip) => Uint8Array;
+
+  /** `size` is 4 for `Ipv4Addr` and 16 for `Ipv6Addr`. */
+  constructor(size: 4 | 16, toOctets: (ip: Ip) => Uint8Array) {
+    super({ inline: true, hashable: true });
+    this.#size = size;
+    this.#toOctets = toOctets;
+  }
+
+  computeLayout(_fmt: RkyvFormat): Layout {
+    return this.#size === 4 ? IPV4 : IPV6;
+  }
+
+  resolve(writer: RkyvWriter, value: Ip, _resolver: undefined): number {
+    return writer.writeBytes(checkedOctets(this.#toOctets(value), this.#size));
+  }
+
+  hash(hasher: RkyvHasher, value: Ip, _encoder: RkyvTextEncoder): void {
+    hashIp(hasher, checkedOctets(this.#toOctets(value), this.#size));
+  }
+}
+
+export class IpAddrEncoder<Ip> extends BaseEncoder<Ip, undefined> {
+  #toOctets: (ip: Ip) => Uint8Array;
+
+  constructor(toOctets: (ip: Ip) => Uint8Array) {
+    super({ inline: true, hashable: true });
+    this.#toOctets = toOctets;
+  }
+
+  computeLayout(_fmt: RkyvFormat): Layout {
+    return IP;
+  }
+
+  resolve(writer: RkyvWriter, value: Ip, _resolver: undefined): number {
+    const octets = checkedOctets(this.#toOctets(value));
+    const pos = writer.writeU8(octets.length === 4 ? 0 : 1);
+    writer.writeBytes(octets);
+    writer.padTo(pos + IP.size);
+    return pos;
+  }
+
+  // `IpAddr` derives Hash: the discriminant as an `isize`, then the address.
+  hash(hasher: RkyvHasher, value: Ip, _encoder: RkyvTextEncoder): void {
+    const octets = checkedOctets(this.#toOctets(value));
+    hasher.writeUsize(octets.length === 4 ? 0 : 1);
+    hashIp(hasher, octets);
+  }
+}
+
+export class SocketAddrV4Encoder<Ip> extends BaseEncoder<SocketAddrV4<Ip>, undefined> {
+  #toOctets: (ip: Ip) => Uint8Array;
+
+  constructor(toOctets: (ip: Ip) => Uint8Array) {
+    super({ inline: true, hashable: true });
+    this.#toOctets = toOctets;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? SOCKET_V4_ALIGNED : SOCKET_V4_UNALIGNED;
+  }
+
+  resolve(writer: RkyvWriter, value: SocketAddrV4<Ip>, _resolver: undefined): number {
+    return writeSocket(writer, checkedOctets(this.#toOctets(value.ip), 4), value);
+  }
+
+  hash(hasher: RkyvHasher, value: SocketAddrV4<Ip>, _encoder: RkyvTextEncoder): void {
+    hashSocket(hasher, checkedOctets(this.#toOctets(value.ip), 4), value);
+  }
+}
+
+export class SocketAddrV6Encoder<Ip> extends BaseEncoder<SocketAddrV6<Ip>, undefined> {
+  #toOctets: (ip: Ip) => Uint8Array;
+
+  constructor(toOctets: (ip: Ip) => Uint8Array) {
+    super({ inline: true, hashable: true });
+    this.#toOctets = toOctets;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? SOCKET_V6_ALIGNED : SOCKET_V6_UNALIGNED;
+  }
+
+  resolve(writer: RkyvWriter, value: SocketAddrV6<Ip>, _resolver: undefined): number {
+    return writeSocket(writer, checkedOctets(this.#toOctets(value.ip), 16), value);
+  }
+
+  hash(hasher: RkyvHasher, value: SocketAddrV6<Ip>, _encoder: RkyvTextEncoder): void {
+    hashSocket(hasher, checkedOctets(this.#toOctets(value.ip), 16), value);
+  }
+}
+
+export class SocketAddrEncoder<Ip> extends BaseEncoder<SocketAddr<Ip>, undefined> {
+  #toOctets: (ip: Ip) => Uint8Array;
+
+  constructor(toOctets: (ip: Ip) => Uint8Array) {
+    super({ inline: true, hashable: true });
+    this.#toOctets = toOctets;
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return fmt.aligned ? SOCKET_ALIGNED : SOCKET_UNALIGNED;
+  }
+
+  resolve(writer: RkyvWriter, value: SocketAddr<Ip>, _resolver: undefined): number {
+    const octets = checkedOctets(this.#toOctets(value.ip));
+    const pos = writer.writeU8(octets.length === 4 ? 0 : 1);
+    writer.padTo(pos + socketPayloadOffset(writer.format, octets.length));
+    writeSocket(writer, octets, value);
+    writer.padTo(pos + this.layout(writer.format).size);
+    return pos;
+  }
+
+  // `SocketAddr` derives Hash: the discriminant as an `isize`, then the socket address.
+  hash(hasher: RkyvHasher, value: SocketAddr<Ip>, _encoder: RkyvTextEncoder): void {
+    const octets = checkedOctets(this.#toOctets(value.ip));
+    hasher.writeUsize(octets.length === 4 ? 0 : 1);
+    hashSocket(hasher, octets, value);
+  }
+}
+
+/** Where a `SocketAddr` variant's payload starts: after the tag, at the payload's alignment. */
+function socketPayloadOffset(fmt: RkyvFormat, ipSize: number): number {
+  if (!fmt.aligned) return 1;
+  return ipSize === 4 ? 2 : 4;
+}
+
+/** The octets of an address of the expected version (either, if `size` is omitted). */
+function checkedOctets(octets: Uint8Array, size?: 4 | 16): Uint8Array {
+  const valid = size === undefined ? octets.length === 4 || octets.length === 16 : octets.length === size;
+  if (!valid) {
+    const expected = size === undefined ? 'an IPv4 or IPv6' : `an IPv${size === 4 ? 4 : 6}`;
+    throw new Error(`Expected ${expected} address, got ${octets.length} octets`);
+  }
+  return octets;
+}
+
+// std hashes an `Ipv4Addr` as its in-memory `u32` (the octets read little-endian),
+// and an `Ipv6Addr` as its 16 octets, without a length prefix.
+function hashIp(hasher: RkyvHasher, octets: Uint8Array): void {
+  if (octets.length === 4) {
+    hasher.writeU32((octets[0] | (octets[1] << 8) | (octets[2] << 16) | (octets[3] << 24)) >>> 0);
+  } else {
+    hasher.writeBytes(octets);
+  }
+}
+
+/** A socket address body: the address, the port, and for IPv6 the flow and scope identifiers. */
+function writeSocket(writer: RkyvWriter, octets: Uint8Array, value: SocketAddrV4<unknown> | SocketAddrV6<unknown>): number {
+  const pos = writer.writeBytes(octets);
+  writer.writeU16(checkedInt(value.port, 0xffff, 'port'));
+  if (octets.length === 16) {
+    const v6 = value as SocketAddrV6<unknown>;
+    writer.padTo(pos + (writer.format.aligned ? 20 : 18));
+    writer.writeU32(checkedInt(v6.flowinfo, 0xffff_ffff, 'flowinfo'));
+    writer.writeU32(checkedInt(v6.scopeId, 0xffff_ffff, 'scopeId'));
+  }
+  return pos;
+}
+
+function hashSocket(hasher: RkyvHasher, octets: Uint8Array, value: SocketAddrV4<unknown> | SocketAddrV6<unknown>): void {
+  hashIp(hasher, octets);
+  hasher.writeU16(value.port);
+  if (octets.length === 16) {
+    const v6 = value as SocketAddrV6<unknown>;
+    hasher.writeU32(v6.flowinfo);
+    hasher.writeU32(v6.scopeId);
+  }
+}
+
+function checkedInt(value: number, max: number, name: string): number {
+  if (!Number.isInteger(value) || value < 0 || value > max) {
+    throw new RangeError(`Invalid ${name}: ${value}`);
+  }
+  return value;
+}
+
+const octets = (octets: Uint8Array) => octets;
+
+/** std::net::Ipv4Addr — archived from dotted decimal text (`192.168.0.1`). */
+export const ipv4Addr: Encoder<string> = new IpEncoder(4, parseIp);
+
+/** std::net::Ipv6Addr — archived from IPv6 text (`2001:db8::1`). */
+export const ipv6Addr: Encoder<string> = new IpEncoder(16, parseIp);
+
+/** std::net::IpAddr — archived from the text of either version. */
+export const ipAddr: Encoder<string> = new IpAddrEncoder(parseIp);
+
+/** std::net::SocketAddrV4 — archived from `{ ip, port }` with a text address. */
+export const socketAddrV4: Encoder<SocketAddrV4> = new SocketAddrV4Encoder(parseIp);
+
+/** std::net::SocketAddrV6 — archived from `{ ip, port, flowinfo, scopeId }` with a text address. */
+export const socketAddrV6: Encoder<SocketAddrV6> = new SocketAddrV6Encoder(parseIp);
+
+/** std::net::SocketAddr — archived from a socket address of either version with a text address. */
+export const socketAddr: Encoder<SocketAddr> = new SocketAddrEncoder(parseIp);
+
+/** std::net::Ipv4Addr — archived from its 4 octets. */
+export const ipv4AddrBytes: Encoder<Uint8Array> = new IpEncoder(4, octets);
+
+/** std::net::Ipv6Addr — archived from its 16 octets. */
+export const ipv6AddrBytes: Encoder<Uint8Array> = new IpEncoder(16, octets);
+
+/** std::net::IpAddr — archived from 4 or 16 octets. */
+export const ipAddrBytes: Encoder<Uint8Array> = new IpAddrEncoder(octets);
+
+/** std::net::SocketAddrV4 — archived from `{ ip, port }` with the address octets. */
+export const socketAddrV4Bytes: Encoder<SocketAddrV4<Uint8Array>> = new SocketAddrV4Encoder(octets);
+
+/** std::net::SocketAddrV6 — archived from `{ ip, port, flowinfo, scopeId }` with the address octets. */
+export const socketAddrV6Bytes: Encoder<SocketAddrV6<Uint8Array>> = new SocketAddrV6Encoder(octets);
+
+/** std::net::SocketAddr — archived from a socket address of either version with the address octets. */
+export const socketAddrBytes: Encoder<SocketAddr<Uint8Array>> = new SocketAddrEncoder(octets);
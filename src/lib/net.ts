@@ -0,0 +1,251 @@
+/**
+ * std::net address codecs for rkyv-js
+ *
+ * Covers `Ipv4Addr`, `Ipv6Addr`, `IpAddr`, `SocketAddrV4`, `SocketAddrV6`
+ * and `SocketAddr`, with addresses as their text form (`192.168.0.1`,
+ * `2001:db8::1`) or as their octets.
+ * @see https://docs.rs/rkyv/0.8/rkyv/net/index.html
+ *
+ * The logic lives once per direction: the full codecs here EXTEND the read
+ * classes from `./net.decode.ts` and CONTAIN the encode classes from
+ * `./net.encode.ts`, delegating `resolve`/`hash` to them. One-direction
+ * consumers import those modules directly instead.
+ */
+
+import {
+  DEFAULT_FORMAT,
+  encodeIntoWriter,
+  encodePooled,
+  type Codec,
+  type RkyvFormat,
+  type RkyvHasher,
+  type RkyvTextEncoder,
+  type RkyvWriter,
+} from 'rkyv-js/core';
+
+import {
+  formatIp,
+  IpAddrDecoder,
+  IpDecoder,
+  SocketAddrDecoder,
+  SocketAddrV4Decoder,
+  SocketAddrV6Decoder,
+  type SocketAddr,
+  type SocketAddrV4,
+  type SocketAddrV6,
+} from './net.decode.ts';
+import {
+  IpAddrEncoder,
+  IpEncoder,
+  parseIp,
+  SocketAddrEncoder,
+  SocketAddrV4Encoder,
+  SocketAddrV6Encoder,
+} from './net.encode.ts';
+
+export {
+  formatIp,
+  IpAddrDecoder,
+  IpDecoder,
+  SocketAddrDecoder,
+  SocketAddrV4Decoder,
+  SocketAddrV6Decoder,
+  type SocketAddr,
+  type SocketAddrV4,
+  type SocketAddrV6,
+} from './net.decode.ts';
+export {
+  IpAddrEncoder,
+  IpEncoder,
+  parseIp,
+  SocketAddrEncoder,
+  SocketAddrV4Encoder,
+  SocketAddrV6Encoder,
+} from './net.encode.ts';
+
+export class IpCodec<Ip> extends IpDecoder<Ip> {
+  #write: IpEncoder<Ip>;
+
+  constructor(
+    size: 4 | 16,
+    fromOctets: (octets: Uint8Array) => Ip,
+    toOctets: (ip: Ip) => Uint8Array,
+  ) {
+    super(size, fromOctets);
+    this.#write = new IpEncoder(size, toOctets);
+  }
+
+  archive(writer: RkyvWriter, value: Ip): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: Ip, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: Ip, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: Ip, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: Ip): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+export class IpAddrCodec<Ip> extends IpAddrDecoder<Ip> {
+  #write: IpAddrEncoder<Ip>;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip, toOctets: (ip: Ip) => Uint8Array) {
+    super(fromOctets);
+    this.#write = new IpAddrEncoder(toOctets);
+  }
+
+  archive(writer: RkyvWriter, value: Ip): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: Ip, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: Ip, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: Ip, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: Ip): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+export class SocketAddrV4Codec<Ip> extends SocketAddrV4Decoder<Ip> {
+  #write: SocketAddrV4Encoder<Ip>;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip, toOctets: (ip: Ip) => Uint8Array) {
+    super(fromOctets);
+    this.#write = new SocketAddrV4Encoder(toOctets);
+  }
+
+  archive(writer: RkyvWriter, value: SocketAddrV4<Ip>): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: SocketAddrV4<Ip>, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: SocketAddrV4<Ip>, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: SocketAddrV4<Ip>, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: SocketAddrV4<Ip>): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+export class SocketAddrV6Codec<Ip> extends SocketAddrV6Decoder<Ip> {
+  #write: SocketAddrV6Encoder<Ip>;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip, toOctets: (ip: Ip) => Uint8Array) {
+    super(fromOctets);
+    this.#write = new SocketAddrV6Encoder(toOctets);
+  }
+
+  archive(writer: RkyvWriter, value: SocketAddrV6<Ip>): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: SocketAddrV6<Ip>, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: SocketAddrV6<Ip>, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: SocketAddrV6<Ip>, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: SocketAddrV6<Ip>): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+export class SocketAddrCodec<Ip> extends SocketAddrDecoder<Ip> {
+  #write: SocketAddrEncoder<Ip>;
+
+  constructor(fromOctets: (octets: Uint8Array) => Ip, toOctets: (ip: Ip) => Uint8Array) {
+    super(fromOctets);
+    this.#write = new SocketAddrEncoder(toOctets);
+  }
+
+  archive(writer: RkyvWriter, value: SocketAddr<Ip>): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: SocketAddr<Ip>, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: SocketAddr<Ip>, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: SocketAddr<Ip>, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: SocketAddr<Ip>): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+const octets = (octets: Uint8Array) => octets;
+
+/** std::net::Ipv4Addr — dotted decimal text (`192.168.0.1`). */
+export const ipv4Addr: Codec<string> = new IpCodec(4, formatIp, parseIp);
+
+/** std::net::Ipv6Addr — RFC 5952 text (`2001:db8::1`), as Rust displays it. */
+export const ipv6Addr: Codec<string> = new IpCodec(16, formatIp, parseIp);
+
+/** std::net::IpAddr — the text of either version; the text picks the variant on encode. */
+export const ipAddr: Codec<string> = new IpAddrCodec(formatIp, parseIp);
+
+/** std::net::SocketAddrV4 — `{ ip, port }` with a text address. */
+export const socketAddrV4: Codec<SocketAddrV4> = new SocketAddrV4Codec(formatIp, parseIp);
+
+/** std::net::SocketAddrV6 — `{ ip, port, flowinfo, scopeId }` with a text address. */
+export const socketAddrV6: Codec<SocketAddrV6> = new SocketAddrV6Codec(formatIp, parseIp);
+
+/** std::net::SocketAddr — a socket address of either version with a text address. */
+export const socketAddr: Codec<SocketAddr> = new SocketAddrCodec(formatIp, parseIp);
+
+/** std::net::Ipv4Addr — its 4 octets. */
+export const ipv4AddrBytes: Codec<Uint8Array> = new IpCodec(4, octets, octets);
+
+/** std::net::Ipv6Addr — its 16 octets. */
+export const ipv6AddrBytes: Codec<Uint8Array> = new IpCodec(16, octets, octets);
+
+/** std::net::IpAddr — 4 or 16 octets; the length picks the variant on encode. */
+export const ipAddrBytes: Codec<Uint8Array> = new IpAddrCodec(octets, octets);
+
+/** std::net::SocketAddrV4 — `{ ip, port }` with the address octets. */
+export const socketAddrV4Bytes: Codec<SocketAddrV4<Uint8Array>> = new SocketAddrV4Codec(octets, octets);
+
+/** std::net::SocketAddrV6 — `{ ip, port, flowinfo, scopeId }` with the address octets. */
+export const socketAddrV6Bytes: Codec<SocketAddrV6<Uint8Array>> = new SocketAddrV6Codec(octets, octets);
+
+/** std::net::SocketAddr — a socket address of either version with the address octets. */
+export const socketAddrBytes: Codec<SocketAddr<Uint8Array>> = new SocketAddrCodec(octets, octets);
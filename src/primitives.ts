@@ -2,10 +2,11 @@
  * Intrinsic codecs for rkyv-js
  *
  * This module provides all built-in codecs:
- * - Primitives: u8, i8, u16, i16, u32, i32, u64, i64, f32, f64, bool, unit, char, string
- * - Containers: vec, option, box, array, tuple
- * - Structs & Enums: struct, taggedEnum, union
- * - Smart pointers: rc, weak
+ * - Primitives: u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize,
+ *   f32, f64, bool, unit, char, string
+ * - Containers: vec, option, niched, box, array, tuple
+ * - Structs & Enums: struct, taggedEnum, stringEnum, numberEnum, union
+ * - Smart pointers: rc, weak, boxedStr
  * - Utilities: transform, newtype, lazy
  *
  * Every codec is a self-contained {@link Codec}: encode/decode/access live on
@@ -26,14 +27,18 @@ import {
 } from './core/codec.ts';
 import { DEFAULT_FORMAT, type RkyvFormat } from './core/format.ts';
 import type { RkyvHasher } from './core/hasher.ts';
+import type { Niching } from './core/niching.ts';
+import { ptrLayout, type EnumOptions, type Layout } from './core/layout.ts';
 import type { RkyvReader } from './core/reader.ts';
 import type { RkyvTextEncoder, RkyvWriter } from './core/writer.ts';
 import {
   ArrayDecoder,
   BoxDecoder,
+  BoxedStrDecoder,
   EnumDecoder,
   LazyDecoder,
   Kind,
+  NichedDecoder,
   OptionDecoder,
   PrimitiveDecoder,
   StringDecoder,
@@ -41,6 +46,7 @@ import {
   TransformDecoder,
   TupleDecoder,
   UnionDecoder,
+  UnitEnumDecoder,
   VecDecoder,
   WeakDecoder,
   type PrimitiveKindTag,
@@ -48,8 +54,10 @@ import {
 import {
   ArrayEncoder,
   BoxEncoder,
+  BoxedStrEncoder,
   EnumEncoder,
   LazyEncoder,
+  NichedEncoder,
   OptionEncoder,
   PrimitiveEncoder,
   StringEncoder,
@@ -57,13 +65,18 @@ import {
   TransformEncoder,
   TupleEncoder,
   UnionEncoder,
+  UnitEnumEncoder,
   VecEncoder,
   WeakEncoder,
+  hashU128,
   type PtrResolver,
   type StringResolver,
   type VecResolver,
 } from './encode.ts';
 
+export { niching, type Niching } from './core/niching.ts';
+export type { EnumOptions } from './core/layout.ts';
+
 // ============================================================================
 // Primitive Codecs
 // ============================================================================
@@ -115,6 +128,37 @@ export const u32: Codec<number> = new PrimitiveCodec(4, 4, Kind.u32, (r, o) => r
 export const i32: Codec<number> = new PrimitiveCodec(4, 4, Kind.i32, (r, o) => r.readI32(o), (w, v) => w.writeI32(v), (h, v) => h.writeU32(v));
 export const u64: Codec<bigint> = new PrimitiveCodec(8, 8, Kind.u64, (r, o) => r.readU64(o), (w, v) => w.writeU64(v), (h, v) => h.writeU64(v));
 export const i64: Codec<bigint> = new PrimitiveCodec(8, 8, Kind.i64, (r, o) => r.readI64(o), (w, v) => w.writeI64(v), (h, v) => h.writeU64(v));
+export const u128: Codec<bigint> = new PrimitiveCodec(16, 16, Kind.other, (r, o) => r.readU128(o), (w, v) => w.writeU128(v), hashU128);
+export const i128: Codec<bigint> = new PrimitiveCodec(16, 16, Kind.other, (r, o) => r.readI128(o), (w, v) => w.writeI128(v), hashU128);
+
+/**
+ * Archived `usize`/`isize` (rkyv `FixedUsize`/`FixedIsize`): sized by the
+ * format's pointer width, not the host's.
+ */
+export class PointerSizedCodec extends PrimitiveCodec<number> {
+  constructor(
+    read: (reader: RkyvReader, offset: number) => number,
+    write: (writer: RkyvWriter, value: number) => number,
+    hash: (hasher: RkyvHasher, value: number) => void,
+  ) {
+    super(0, 1, Kind.other, read, write, hash);
+  }
+
+  computeLayout(fmt: RkyvFormat): Layout {
+    return ptrLayout(fmt);
+  }
+}
+
+export const usize: Codec<number> = new PointerSizedCodec(
+  (r, o) => r.readUsize(o),
+  (w, v) => w.writeUsize(v),
+  (h, v) => h.writeUsize(v),
+);
+export const isize: Codec<number> = new PointerSizedCodec(
+  (r, o) => r.readIsize(o),
+  (w, v) => w.writeIsize(v),
+  (h, v) => h.writeU64(BigInt(v)),
+);
 
 // Float codecs (floats are not `Eq` in Rust, so they never hash).
 export const f32: Codec<number> = new PrimitiveCodec(4, 4, Kind.f32, (r, o) => r.readF32(o), (w, v) => w.writeF32(v));
@@ -272,6 +316,47 @@ export function option<C extends AnyCodec>(inner: C): Codec<Infer<C> | null> {
   return new OptionCodec(inner);
 }
 
+// ============================================================================
+// Niched Option<T>
+// ============================================================================
+
+export class NichedCodec<T> extends NichedDecoder<T> {
+  #write: NichedEncoder<T>;
+
+  constructor(inner: Codec<T>, niching: Niching) {
+    super(inner, niching);
+    this.#write = new NichedEncoder(inner, niching);
+  }
+
+  archive(writer: RkyvWriter, value: T | null): unknown {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: T | null, resolver: unknown): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: T | null, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: T | null, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: T | null): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+/**
+ * Niched Option<T> — laid out exactly like `T`, with `None` stored as a
+ * niche in `T`'s own bytes (`with = Niche` / `NicheInto<N>` / `MapNiche`).
+ */
+export function niched<C extends AnyCodec>(inner: C, niching: Niching): Codec<Infer<C> | null> {
+  return new NichedCodec(inner, niching);
+}
+
 // ============================================================================
 // Box<T> / Rc<T> / Arc<T> / Weak<T>
 // ============================================================================
@@ -323,6 +408,40 @@ export function box<C extends AnyCodec>(inner: C): Codec<Infer<C>> {
  */
 export const rc: typeof box = box;
 
+export class BoxedStrCodec extends BoxedStrDecoder {
+  #write: BoxedStrEncoder = new BoxedStrEncoder();
+
+  archive(writer: RkyvWriter, value: string): VecResolver {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: string, resolver: VecResolver): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: string, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: string, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: string): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+/**
+ * Box<str> / Rc<str> / Arc<str> — a pointer to out-of-line UTF-8 bytes.
+ *
+ * Unlike `String`, the archived pointer never stores short text inline: it
+ * is a relative pointer plus a byte length, laid out like `Vec<u8>`.
+ * Boxed and shared slices (`Box<[T]>`, `Arc<[T]>`) need no codec of their
+ * own: they archive exactly like `Vec<T>`, so use {@link vec}.
+ */
+export const boxedStr: Codec<string> = new BoxedStrCodec();
+
 export class WeakCodec<T> extends WeakDecoder<T> {
   #write: WeakEncoder<T>;
 
@@ -552,14 +671,15 @@ export type EnumValue<V extends EnumVariants> = {
 export class EnumCodec<V extends EnumVariants> extends EnumDecoder<EnumValue<V>> {
   #write: EnumEncoder<EnumValue<V>>;
 
-  constructor(variants: V) {
-    super(variants);
+  constructor(variants: V, options?: EnumOptions) {
+    super(variants, options);
     // The read base already normalized the variants (its `instanceof` on the
     // read classes covers full codecs too, since they extend them); hand the
     // same normalized inputs to the write delegate.
     this.#write = new EnumEncoder(
       this.variants.map((v) => v.name),
       this.variants.map((v) => v.fields.map((f) => ({ name: f.name, codec: f.codec as AnyCodec }))),
+      options,
     );
   }
 
@@ -592,6 +712,9 @@ export class EnumCodec<V extends EnumVariants> extends EnumDecoder<EnumValue<V>>
  * aligned opaque body — the distinction matters whenever a variant's first
  * field has smaller alignment than its widest field.
  *
+ * Variants are tagged by declaration index unless `options.discriminants`
+ * gives explicit values.
+ *
  * @example
  * ```typescript
  * const Message = r.taggedEnum({
@@ -599,10 +722,80 @@ export class EnumCodec<V extends EnumVariants> extends EnumDecoder<EnumValue<V>>
  *   Move: { x: r.i32, y: r.i32 },
  *   Write: r.string,
  * });
+ * const Status = r.taggedEnum({ Active: null, Banned: null }, { discriminants: { Active: 1, Banned: 4 } });
+ * ```
+ */
+export function taggedEnum<const V extends EnumVariants>(
+  variants: V,
+  options?: EnumOptions<keyof V>,
+): Codec<EnumValue<V>> {
+  return new EnumCodec(variants, options);
+}
+
+// ============================================================================
+// Field-less enum as a primitive
+// ============================================================================
+
+export class UnitEnumCodec<T extends string | number> extends UnitEnumDecoder<T> {
+  #write: UnitEnumEncoder<T>;
+
+  constructor(discriminants: Readonly<Record<string, number>>, values: readonly T[]) {
+    super(discriminants, values);
+    this.#write = new UnitEnumEncoder(discriminants, values);
+  }
+
+  archive(writer: RkyvWriter, value: T): undefined {
+    return this.#write.archive(writer, value);
+  }
+
+  resolve(writer: RkyvWriter, value: T, resolver: undefined): number {
+    return this.#write.resolve(writer, value, resolver);
+  }
+
+  hash(hasher: RkyvHasher, value: T, encoder: RkyvTextEncoder): void {
+    this.#write.hash(hasher, value, encoder);
+  }
+
+  encode(value: T, format: RkyvFormat = DEFAULT_FORMAT): Uint8Array {
+    return encodePooled(this, value, format);
+  }
+
+  encodeInto(writer: RkyvWriter, value: T): Uint8Array {
+    return encodeIntoWriter(this, writer, value);
+  }
+}
+
+/**
+ * Field-less Rust enum as its variant name — the `repr(u8)` tag alone,
+ * without `taggedEnum`'s `{ tag, value }` wrapper.
+ *
+ * @example
+ * ```typescript
+ * const Status = r.stringEnum({ Active: 0, Banned: 1 });
+ * Status.decode(Status.encode('Banned')); // 'Banned'
+ * ```
+ */
+export function stringEnum<const M extends Readonly<Record<string, number>>>(
+  discriminants: M,
+): Codec<keyof M & string> {
+  return new UnitEnumCodec(discriminants, Object.keys(discriminants) as (keyof M & string)[]);
+}
+
+/**
+ * Field-less Rust enum as its discriminant — the `repr(u8)` tag alone,
+ * so values compare against a plain `as const` object.
+ *
+ * @example
+ * ```typescript
+ * const Status = { Active: 0, Banned: 1 } as const;
+ * const ArchivedStatus = r.numberEnum(Status);
+ * ArchivedStatus.decode(bytes) === Status.Banned;
  * ```
  */
-export function taggedEnum<const V extends EnumVariants>(variants: V): Codec<EnumValue<V>> {
-  return new EnumCodec(variants);
+export function numberEnum<const M extends Readonly<Record<string, number>>>(
+  discriminants: M,
+): Codec<M[keyof M]> {
+  return new UnitEnumCodec(discriminants, Object.values(discriminants) as M[keyof M][]);
 }
 
 // ============================================================================
@@ -14,6 +14,9 @@ export function revive(value: unknown): unknown {
     if (typeof obj.$bigint === 'string') {
       return BigInt(obj.$bigint);
     }
+    if (typeof obj.$date === 'number') {
+      return new Date(obj.$date);
+    }
     if (typeof obj.$base64 === 'string') {
       return Uint8Array.from(Buffer.from(obj.$base64, 'base64'));
     }
@@ -54,6 +57,10 @@ function deepEqual(a: unknown, b: unknown, ordered: boolean): boolean {
   if (a === null || b === null || typeof a !== 'object' || typeof b !== 'object') {
     return a === b;
   }
+  if (a instanceof Date || b instanceof Date) {
+    if (!(a instanceof Date) || !(b instanceof Date)) return false;
+    return a.getTime() === b.getTime();
+  }
   if (a instanceof Uint8Array || b instanceof Uint8Array) {
     if (!(a instanceof Uint8Array) || !(b instanceof Uint8Array)) return false;
     return a.length === b.length && a.every((byte, i) => byte === b[i]);
@@ -137,6 +144,7 @@ export function conformanceEqual(decoded: unknown, revived: unknown, ordered: bo
     !(decoded instanceof Map) &&
     !(decoded instanceof Set) &&
     !(decoded instanceof Uint8Array) &&
+    !(decoded instanceof Date) &&
     !Array.isArray(decoded)
   ) {
     const dk = Object.keys(decoded as Record<string, unknown>).sort();
@@ -5,8 +5,22 @@ import * as r from '#src/index.ts';
 import { format } from '#src/core/format.ts';
 import { btreeMap, btreeSet } from '#src/lib/btreemap.ts';
 import { bytes } from '#src/lib/bytes.ts';
+import { cString, cStringBytes } from '#src/lib/ffi.ts';
+import { duration, durationMillis, unixTime } from '#src/lib/duration.ts';
 import { hashMap, hashSet } from '#src/lib/hashmap.ts';
 import { indexMap, indexSet } from '#src/lib/indexmap.ts';
+import {
+  formatIp,
+  ipAddr,
+  ipAddrBytes,
+  ipv4Addr,
+  ipv6Addr,
+  parseIp,
+  socketAddr,
+  socketAddrBytes,
+  socketAddrV4,
+  socketAddrV6,
+} from '#src/lib/net.ts';
 import { uuid } from '#src/lib/uuid.ts';
 
 function hex(data: Uint8Array): string {
@@ -30,6 +44,26 @@ describe('Codec API', () => {
       assert.strictEqual(r.u64.decode(data), 0x1122_3344_5566_7788n);
     });
 
+    it('roundtrips u128/i128 as bigint', () => {
+      const big = (1n << 127n) + 0x1122_3344_5566_7788n;
+      assert.strictEqual(r.u128.decode(r.u128.encode(big)), big);
+      assert.strictEqual(r.i128.decode(r.i128.encode(-big + 1n)), -big + 1n);
+      assert.strictEqual(hex(r.u128.encode(1n)), '01 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00');
+      assert.strictEqual(r.u128.layout(r.DEFAULT_FORMAT).align, 16);
+    });
+
+    it('sizes usize/isize by the pointer width', () => {
+      for (const pointerWidth of [16, 32, 64] as const) {
+        const fmt = format({ pointerWidth });
+        assert.strictEqual(r.usize.encode(513, fmt).length, pointerWidth / 8);
+        assert.strictEqual(r.usize.decode(r.usize.encode(513, fmt), fmt), 513);
+        assert.strictEqual(r.isize.decode(r.isize.encode(-513, fmt), fmt), -513);
+      }
+      const pw64 = format({ pointerWidth: 64 });
+      assert.strictEqual(r.usize.decode(r.usize.encode(2 ** 40 + 3, pw64), pw64), 2 ** 40 + 3);
+      assert.strictEqual(r.isize.decode(r.isize.encode(-(2 ** 40), pw64), pw64), -(2 ** 40));
+    });
+
     it('roundtrips bool', () => {
       assert.strictEqual(r.bool.decode(r.bool.encode(true)), true);
       assert.strictEqual(r.bool.decode(r.bool.encode(false)), false);
@@ -162,6 +196,49 @@ describe('Codec API', () => {
     });
   });
 
+  describe('r.niched', () => {
+    it('takes no tag byte', () => {
+      assert.strictEqual(r.niched(r.u32, r.niching.zero).layout(r.DEFAULT_FORMAT).size, 4);
+      assert.strictEqual(r.niched(r.box(r.u32), r.niching.null).layout(r.DEFAULT_FORMAT).size, 4);
+    });
+
+    it('stores None as zero for NonZero integers', () => {
+      const codec = r.niched(r.u32, r.niching.zero);
+      assert.strictEqual(hex(codec.encode(null)), '00 00 00 00');
+      assert.strictEqual(codec.decode(codec.encode(7)), 7);
+      assert.strictEqual(codec.decode(codec.encode(null)), null);
+    });
+
+    it('stores None as the canonical NaN for floats', () => {
+      assert.strictEqual(hex(r.niched(r.f32, r.niching.nan).encode(null)), '00 00 c0 7f');
+      const codec = r.niched(r.f64, r.niching.nan);
+      assert.strictEqual(hex(codec.encode(null)), '00 00 00 00 00 00 f8 7f');
+      assert.strictEqual(codec.decode(codec.encode(2.5)), 2.5);
+      assert.strictEqual(codec.decode(codec.encode(null)), null);
+    });
+
+    it('stores None as 2 for bools', () => {
+      const codec = r.niched(r.bool, r.niching.bool);
+      assert.strictEqual(hex(codec.encode(null)), '02');
+      assert.strictEqual(codec.decode(codec.encode(false)), false);
+      assert.strictEqual(codec.decode(codec.encode(null)), null);
+    });
+
+    it('stores None as the invalid pointer for boxes', () => {
+      const codec = r.niched(r.box(r.string), r.niching.null);
+      assert.strictEqual(hex(codec.encode(null)), '01 00 00 00');
+      assert.strictEqual(codec.decode(codec.encode('a string too long to be inline')), 'a string too long to be inline');
+      assert.strictEqual(codec.decode(codec.encode(null)), null);
+    });
+
+    it('zeroes the length of a null str or slice pointer', () => {
+      const codec = r.niched(r.boxedStr, r.niching.null);
+      assert.strictEqual(hex(codec.encode(null)), '01 00 00 00 00 00 00 00');
+      assert.strictEqual(codec.decode(codec.encode('ü')), 'ü');
+      assert.strictEqual(codec.decode(codec.encode(null)), null);
+    });
+  });
+
   describe('r.struct', () => {
     it('roundtrips simple struct', () => {
       const Point = r.struct({ x: r.f64, y: r.f64 });
@@ -307,6 +384,59 @@ describe('Codec API', () => {
     it('rejects unknown variants', () => {
       assert.throws(() => Message.encode({ tag: 'Nope', value: null } as never));
     });
+
+    it('writes explicit discriminants, continuing implicitly like Rust', () => {
+      // #[repr(u8)] enum Status { Pending = 1, Active, Banned(u16) = 10 }
+      const Status = r.taggedEnum(
+        { Pending: null, Active: null, Banned: r.u16 },
+        { discriminants: { Pending: 1, Banned: 10 } },
+      );
+      assert.strictEqual(hex(Status.encode({ tag: 'Active', value: null })), '02 00 00 00');
+      const banned = Status.encode({ tag: 'Banned', value: 0x1234 });
+      assert.strictEqual(hex(banned), '0a 00 34 12');
+      assert.deepStrictEqual(Status.decode(banned), { tag: 'Banned', value: 0x1234 });
+      assert.throws(() => Status.decode(new Uint8Array([0, 0, 0, 0])), /invalid enum discriminant 0/);
+    });
+
+    it('rejects discriminants rkyv cannot archive', () => {
+      assert.throws(() => r.taggedEnum({ A: null }, { discriminants: { A: 256 } }), /u8/);
+      assert.throws(() => r.taggedEnum({ A: null, B: null }, { discriminants: { B: 0 } }), /in use/);
+      assert.throws(() => r.taggedEnum({ A: null }, { discriminants: { B: 0 } as never }), /unknown/);
+    });
+  });
+
+  describe('r.stringEnum / r.numberEnum', () => {
+    // #[repr(u8)] enum Status { Pending = 1, Active, Banned = 10 }
+    const discriminants = { Pending: 1, Active: 2, Banned: 10 } as const;
+
+    it('encodes the bare u8 tag, byte-identical to taggedEnum', () => {
+      const tagged = r.taggedEnum({ Pending: null, Active: null, Banned: null }, { discriminants });
+      const strings = r.stringEnum(discriminants);
+      const numbers = r.numberEnum(discriminants);
+      assert.strictEqual(hex(strings.encode('Banned')), '0a');
+      assert.deepStrictEqual(strings.encode('Active'), tagged.encode({ tag: 'Active', value: null }));
+      assert.deepStrictEqual(numbers.encode(discriminants.Active), strings.encode('Active'));
+    });
+
+    it('decodes to the variant name or the discriminant', () => {
+      const bytes = r.stringEnum(discriminants).encode('Pending');
+      assert.strictEqual(r.stringEnum(discriminants).decode(bytes), 'Pending');
+      assert.strictEqual(r.numberEnum(discriminants).decode(bytes), discriminants.Pending);
+    });
+
+    it('lays out as one byte inside structs and vecs', () => {
+      const Row = r.struct({ status: r.numberEnum(discriminants), id: r.u16 });
+      assert.strictEqual(hex(Row.encode({ status: 10, id: 0x0102 })), '0a 00 02 01');
+      const list = r.vec(r.stringEnum(discriminants));
+      assert.deepStrictEqual(list.decode(list.encode(['Active', 'Banned'])), ['Active', 'Banned']);
+    });
+
+    it('rejects unknown values and discriminants', () => {
+      assert.throws(() => r.stringEnum(discriminants).encode('Nope' as never), /unknown enum variant/);
+      assert.throws(() => r.numberEnum(discriminants).encode(3 as never), /unknown enum variant/);
+      assert.throws(() => r.stringEnum(discriminants).decode(new Uint8Array([0])), /invalid enum discriminant 0/);
+      assert.throws(() => r.numberEnum({ A: 300 }), /u8/);
+    });
   });
 
   describe('r.tuple', () => {
@@ -394,6 +524,14 @@ describe('Codec API', () => {
       const value = { shared: [1, 2, 3] };
       assert.deepStrictEqual(codec.decode(codec.encode(value)), value);
     });
+
+    it('r.boxedStr keeps short strings out of line', () => {
+      assert.strictEqual(r.boxedStr.layout(r.DEFAULT_FORMAT).size, 8);
+      const data = r.boxedStr.encode('hi');
+      assert.strictEqual(hex(data.subarray(0, 2)), '68 69');
+      assert.strictEqual(r.boxedStr.decode(data), 'hi');
+      assert.strictEqual(r.boxedStr.decode(r.boxedStr.encode('')), '');
+    });
   });
 
   describe('r.weak', () => {
@@ -548,6 +686,47 @@ describe('Codec API', () => {
       });
     });
 
+    describe('duration', () => {
+      it('roundtrips Duration parts losslessly', () => {
+        const value = { secs: 0xffff_ffff_ffff_ffffn, nanos: 999_999_999 };
+        assert.deepStrictEqual(duration.decode(duration.encode(value)), value);
+      });
+
+      it('lays out secs then nanos like ArchivedDuration', () => {
+        assert.strictEqual(
+          hex(duration.encode({ secs: 3n, nanos: 1 })),
+          '03 00 00 00 00 00 00 00 01 00 00 00 00 00 00 00',
+        );
+        assert.strictEqual(duration.encode({ secs: 3n, nanos: 1 }, format({ aligned: false })).length, 12);
+      });
+
+      it('rejects out-of-range parts', () => {
+        assert.throws(() => duration.encode({ secs: -1n, nanos: 0 }), RangeError);
+        assert.throws(() => duration.encode({ secs: 0n, nanos: 1_000_000_000 }), RangeError);
+      });
+
+      it('converts milliseconds', () => {
+        assert.deepStrictEqual(duration.decode(durationMillis.encode(1500.25)), { secs: 1n, nanos: 500_250_000 });
+        assert.strictEqual(durationMillis.decode(duration.encode({ secs: 2n, nanos: 5_000_000 })), 2005);
+      });
+
+      it('reads AsUnixTime timestamps as Dates', () => {
+        const at = new Date('2023-11-14T22:13:20.123Z');
+        assert.strictEqual(unixTime.decode(unixTime.encode(at)).getTime(), at.getTime());
+        assert.throws(() => unixTime.encode(new Date(-1)), RangeError);
+      });
+
+      it('is hashable (usable as a map key)', () => {
+        const codec = hashMap(duration, r.string);
+        const value = new Map([
+          [{ secs: 1n, nanos: 0 }, 'a'],
+          [{ secs: 2n, nanos: 500 }, 'b'],
+        ]);
+        const decoded = [...codec.decode(codec.encode(value)).entries()];
+        assert.deepStrictEqual(decoded.sort(([, a], [, b]) => a.localeCompare(b)), [...value.entries()]);
+      });
+    });
+
     describe('bytes', () => {
       it('roundtrips byte payloads', () => {
         const value = new Uint8Array([1, 2, 3, 250, 251, 252]);
@@ -567,6 +746,111 @@ describe('Codec API', () => {
       });
     });
 
+    describe('net', () => {
+      it('formats and parses addresses like Rust', () => {
+        for (const text of [
+          '192.168.0.1',
+          '::',
+          '::1',
+          '2001:db8::ff00:42:8329',
+          '::ffff:10.0.0.7',
+          'fe80::1:0:1:0:0',
+          '2001:db8:0:0:1::',
+          '1:2:3:4:5:6:7:8',
+        ]) {
+          assert.strictEqual(formatIp(parseIp(text)), text);
+        }
+        assert.strictEqual(formatIp(parseIp('2001:DB8:0:0:0:0:0:1')), '2001:db8::1');
+        assert.strictEqual(formatIp(parseIp('::1.2.3.4')), '::102:304');
+      });
+
+      it('rejects malformed addresses', () => {
+        for (const text of ['1.2.3', '1.2.3.256', '01.2.3.4', '1::2::3', '1:2:3:4:5:6:7:8:9', '::g', 'fe80::1%eth0']) {
+          assert.throws(() => parseIp(text), /Invalid IP address/, text);
+        }
+      });
+
+      it('roundtrips addresses as text', () => {
+        assert.strictEqual(ipv4Addr.decode(ipv4Addr.encode('10.0.0.1')), '10.0.0.1');
+        assert.strictEqual(ipv6Addr.decode(ipv6Addr.encode('2001:db8::1')), '2001:db8::1');
+        assert.strictEqual(ipAddr.decode(ipAddr.encode('127.0.0.1')), '127.0.0.1');
+        assert.strictEqual(ipAddr.decode(ipAddr.encode('::1')), '::1');
+        assert.throws(() => ipv4Addr.encode('::1'), /Expected an IPv4 address/);
+      });
+
+      it('lays out IpAddr as a tagged enum over the octets', () => {
+        assert.strictEqual(hex(ipAddr.encode('1.2.3.4')), '00 01 02 03 04 00 00 00 00 00 00 00 00 00 00 00 00');
+        assert.strictEqual(ipAddr.encode('::1').length, 17);
+        assert.deepStrictEqual(ipAddrBytes.decode(ipAddr.encode('1.2.3.4')), new Uint8Array([1, 2, 3, 4]));
+      });
+
+      it('roundtrips socket addresses', () => {
+        const v4 = { ip: '203.0.113.9', port: 65535 };
+        const v6 = { ip: 'fe80::1', port: 443, flowinfo: 0xcafe0, scopeId: 3 };
+        assert.deepStrictEqual(socketAddrV4.decode(socketAddrV4.encode(v4)), v4);
+        assert.deepStrictEqual(socketAddrV6.decode(socketAddrV6.encode(v6)), v6);
+        assert.deepStrictEqual(socketAddr.decode(socketAddr.encode(v4)), v4);
+        assert.deepStrictEqual(socketAddr.decode(socketAddr.encode(v6)), v6);
+        const unaligned = format({ aligned: false });
+        assert.deepStrictEqual(socketAddr.decode(socketAddr.encode(v6, unaligned), unaligned), v6);
+        assert.throws(() => socketAddrV4.encode({ ip: '1.2.3.4', port: 65536 }), RangeError);
+      });
+
+      it('aligns socket address payloads like rkyv', () => {
+        assert.strictEqual(
+          hex(socketAddrV4.encode({ ip: '1.2.3.4', port: 0x1f90 })),
+          '01 02 03 04 90 1f',
+        );
+        assert.strictEqual(socketAddrV6.encode({ ip: '::', port: 0, flowinfo: 0, scopeId: 0 }).length, 28);
+        assert.strictEqual(hex(socketAddr.encode({ ip: '1.2.3.4', port: 1 }).subarray(0, 8)), '00 00 01 02 03 04 01 00');
+        assert.strictEqual(socketAddr.encode({ ip: '1.2.3.4', port: 1 }).length, 32);
+        assert.strictEqual(socketAddr.encode({ ip: '1.2.3.4', port: 1 }, format({ aligned: false })).length, 27);
+        assert.deepStrictEqual(socketAddrBytes.decode(socketAddr.encode({ ip: '1.2.3.4', port: 1 })), {
+          ip: new Uint8Array([1, 2, 3, 4]),
+          port: 1,
+        });
+      });
+
+      it('is hashable (usable as a map key)', () => {
+        const codec = hashMap(ipAddr, r.u32);
+        const value = new Map([
+          ['1.2.3.4', 3],
+          ['255.255.255.255', 1],
+          ['::1', 7],
+          ['2606:4700::1111', 42],
+        ]);
+        assert.deepStrictEqual(codec.decode(codec.encode(value)), value);
+      });
+    });
+
+    describe('ffi', () => {
+      it('roundtrips C strings', () => {
+        assert.strictEqual(cString.decode(cString.encode('eth0 — uplink')), 'eth0 — uplink');
+        assert.strictEqual(cString.decode(cString.encode('')), '');
+        const value = new Uint8Array([0xff, 0xfe, 1]);
+        assert.deepStrictEqual(new Uint8Array(cStringBytes.decode(cStringBytes.encode(value))), value);
+      });
+
+      it('archives the trailing nul', () => {
+        // Bytes "hi\0", then the relative pointer back to them and the length with the nul.
+        assert.strictEqual(hex(cString.encode('hi')), '68 69 00 00 fc ff ff ff 03 00 00 00');
+      });
+
+      it('rejects interior nul bytes', () => {
+        assert.throws(() => cString.encode('a\0b'), RangeError);
+        assert.throws(() => cStringBytes.encode(new Uint8Array([1, 0])), RangeError);
+      });
+
+      it('is hashable (usable as a map key)', () => {
+        const codec = hashMap(cString, r.u32);
+        const value = new Map([
+          ['eth0', 1],
+          ['lo', 2],
+        ]);
+        assert.deepStrictEqual(codec.decode(codec.encode(value)), value);
+      });
+    });
+
     describe('indexMap / indexSet', () => {
       it('roundtrips and preserves insertion order', () => {
         const codec = indexMap(r.string, r.u32);
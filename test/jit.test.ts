@@ -81,6 +81,24 @@ describe('compileCodec', () => {
     }
   });
 
+  it('switches on explicit enum discriminants', () => {
+    const Level = r.struct({
+      level: r.taggedEnum(
+        { Debug: null, Warn: r.string, Error: { code: r.u16 } },
+        { discriminants: { Debug: 10, Error: 40 } },
+      ),
+    });
+    const compiled = compileCodec(Level);
+    for (const level of [
+      { tag: 'Debug', value: null },
+      { tag: 'Warn', value: 'disk almost full' },
+      { tag: 'Error', value: { code: 503 } },
+    ] as const) {
+      const bytes = Level.encode({ level });
+      assert.deepStrictEqual(compiled.decode(bytes), { level });
+    }
+  });
+
   it('compiled vec write loops stay byte-identical to the interpreter', () => {
     // Tier 1: fully-primitive element (single reservation, strided stores),
     // with a nested inline struct and an alignment gap in the stride.
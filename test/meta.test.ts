@@ -77,6 +77,18 @@ describe('decoder meta descriptors', () => {
     assert.strictEqual(enumMeta.variants[1].fields[0].codec, rd.f64);
     assert.strictEqual(enumMeta.variants[2].fields[0].name, null);
     assert.strictEqual(enumMeta.variants[2].fields[0].codec, rd.string);
+    assert.deepStrictEqual(
+      enumMeta.variants.map((v) => v.discriminant),
+      [0, 1, 2],
+    );
+
+    const explicit = rd.taggedEnum({ Low: null, High: null }, { discriminants: { Low: 3, High: 200 } });
+    const explicitMeta = explicit.meta;
+    assert.ok(explicitMeta.kind === Kind.enum);
+    assert.deepStrictEqual(
+      explicitMeta.variants.map((v) => v.discriminant),
+      [3, 200],
+    );
   });
 });
 